@@ -1,10 +1,16 @@
 use bevy::{prelude::*, reflect::TypePath, utils::HashMap};
 use bevy_asset_loader::prelude::*;
 use bevy_common_assets::yaml::YamlAssetPlugin;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use std::num::{NonZeroU8, NonZeroUsize};
 
-use crate::{components::Side, states::GameState};
+use strum::IntoEnumIterator;
+
+use crate::{
+    components::{CrabAnimation, PlayerSlot, Side},
+    settings::CameraMode,
+    states::GameState,
+};
 
 pub(super) struct AssetsPlugin;
 
@@ -20,7 +26,8 @@ impl Plugin for AssetsPlugin {
                 LoadingStateConfig::new(GameState::Loading)
                     .load_collection::<GameAssets>()
                     .init_resource::<CachedAssets>(),
-            );
+            )
+            .add_systems(OnExit(GameState::Loading), reject_invalid_game_modes);
     }
 }
 
@@ -42,31 +49,119 @@ pub struct GameConfig {
     pub ball_fade_time_in_secs: f32,
     pub ball_diameter: f32,
     pub ball_height_from_ground: f32,
+    pub gamepad_stick_deadzone: f32,
+    pub attract_idle_time_in_secs: f32,
+    pub particle_size: f32,
+    pub sand_puff_particle_count: u32,
+    pub sand_puff_lifetime_in_secs: f32,
+    pub splash_particle_count: u32,
+    pub splash_lifetime_in_secs: f32,
+    pub confetti_particle_count: u32,
+    pub confetti_lifetime_in_secs: f32,
+    pub pole_burst_particle_count: u32,
+    pub pole_burst_lifetime_in_secs: f32,
+    pub low_effects_particle_fraction: f32,
+    pub music_crossfade_time_in_secs: f32,
+    pub sound_reference_ball_speed: f32,
 }
 
-#[derive(Asset, Debug, Deserialize, Resource, TypePath)]
+#[derive(Asset, Clone, Debug, Deserialize, Resource, Serialize, TypePath)]
 pub struct GameMode {
     pub name: String,
     pub ball_count: NonZeroU8,
     pub ball_scale: f32,
     pub ball_speed: f32,
     pub competitors: HashMap<Side, CompetitorConfig>,
+
+    /// Overrides the camera mode from the settings.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub camera_mode: Option<CameraMode>,
+
+    /// Spawns fewer particles, as if the low effects setting were on.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub low_effects: bool,
+}
+
+impl GameMode {
+    /// Finds a [`PlayerSlot`] that's explicitly given to more than one
+    /// [`CrabController::Player`] side.
+    pub fn duplicate_player_slot(&self) -> Option<PlayerSlot> {
+        let mut used_slots = Vec::new();
+
+        self.competitors
+            .values()
+            .filter(|competitor| {
+                competitor.controller == CrabController::Player
+            })
+            .filter_map(|competitor| competitor.player_slot)
+            .find(|slot| {
+                let is_duplicate = used_slots.contains(slot);
+
+                used_slots.push(*slot);
+                is_duplicate
+            })
+    }
+
+    /// Gets the [`PlayerSlot`] for each [`CrabController::Player`] side.
+    ///
+    /// Sides without an explicit slot get the lowest free one, in [`Side`]
+    /// order.
+    pub fn player_slots(&self) -> HashMap<Side, PlayerSlot> {
+        let mut player_slots: HashMap<Side, PlayerSlot> = self
+            .competitors
+            .iter()
+            .filter(|(_, competitor)| {
+                competitor.controller == CrabController::Player
+            })
+            .filter_map(|(side, competitor)| {
+                competitor.player_slot.map(|slot| (*side, slot))
+            })
+            .collect();
+
+        for side in Side::iter() {
+            let Some(competitor) = self.competitors.get(&side) else {
+                continue;
+            };
+
+            if competitor.controller != CrabController::Player
+                || player_slots.contains_key(&side)
+            {
+                continue;
+            }
+
+            let free_slot = PlayerSlot::iter()
+                .find(|slot| !player_slots.values().any(|used| used == slot));
+
+            if let Some(slot) = free_slot {
+                player_slots.insert(side, slot);
+            }
+        }
+
+        player_slots
+    }
 }
 
-#[derive(Debug, Deserialize)]
+#[derive(Clone, Debug, Deserialize, Serialize)]
 pub struct CompetitorConfig {
     pub color: String,
     pub team: NonZeroUsize,
     pub controller: CrabController,
+
+    /// Which local multiplayer slot controls a [`CrabController::Player`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub player_slot: Option<PlayerSlot>,
     pub hit_points: NonZeroU8,
     pub max_speed: f32,
     pub seconds_to_max_speed: f32,
 }
 
-#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq)]
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
 pub enum CrabController {
     Player,
     AI,
+
+    /// Driven by an external bot process, or by AI while none is connected.
+    Remote,
 }
 
 #[derive(AssetCollection, Resource)]
@@ -80,8 +175,59 @@ pub struct GameAssets {
     #[asset(path = "fonts/FiraSans-Bold.ttf")]
     pub font_menu: Handle<Font>,
 
-    #[asset(path = "images/crab.png")]
-    pub image_crab: Handle<Image>,
+    #[asset(path = "models/crab.glb#Scene0")]
+    pub scene_crab: Handle<Scene>,
+
+    #[asset(path = "models/crab.glb#Animation0")]
+    pub animation_crab_idle: Handle<AnimationClip>,
+
+    #[asset(path = "models/crab.glb#Animation1")]
+    pub animation_crab_scuttle_left: Handle<AnimationClip>,
+
+    #[asset(path = "models/crab.glb#Animation2")]
+    pub animation_crab_scuttle_right: Handle<AnimationClip>,
+
+    #[asset(path = "models/crab.glb#Animation3")]
+    pub animation_crab_hit: Handle<AnimationClip>,
+
+    #[asset(path = "models/crab.glb#Animation4")]
+    pub animation_crab_defeat: Handle<AnimationClip>,
+
+    #[asset(path = "sounds/deflect.wav")]
+    pub sound_deflect: Handle<AudioSource>,
+
+    #[asset(path = "sounds/bounce.wav")]
+    pub sound_bounce: Handle<AudioSource>,
+
+    #[asset(path = "sounds/clack.wav")]
+    pub sound_clack: Handle<AudioSource>,
+
+    #[asset(path = "sounds/goal.wav")]
+    pub sound_goal: Handle<AudioSource>,
+
+    #[asset(path = "sounds/eliminated.wav")]
+    pub sound_eliminated: Handle<AudioSource>,
+
+    #[asset(path = "sounds/victory.wav")]
+    pub sound_victory: Handle<AudioSource>,
+
+    #[asset(path = "sounds/defeat.wav")]
+    pub sound_defeat: Handle<AudioSource>,
+
+    #[asset(path = "sounds/menu_move.wav")]
+    pub sound_menu_move: Handle<AudioSource>,
+
+    #[asset(path = "sounds/menu_select.wav")]
+    pub sound_menu_select: Handle<AudioSource>,
+
+    #[asset(path = "sounds/pause.wav")]
+    pub sound_pause: Handle<AudioSource>,
+
+    #[asset(path = "music/menu.wav")]
+    pub music_menu: Handle<AudioSource>,
+
+    #[asset(path = "music/match.wav")]
+    pub music_match: Handle<AudioSource>,
 
     #[asset(path = "images/sand.png")]
     pub image_sand: Handle<Image>,
@@ -95,27 +241,25 @@ pub struct GameAssets {
 #[derive(Debug, Resource)]
 pub struct CachedAssets {
     pub ball_mesh: Handle<Mesh>,
-    pub crab_mesh: Handle<Mesh>,
+    pub crab_animation_graph: Handle<AnimationGraph>,
+    pub crab_animations: HashMap<CrabAnimation, AnimationNodeIndex>,
     pub pole_mesh: Handle<Mesh>,
     pub pole_material: Handle<StandardMaterial>,
+    pub particle_mesh: Handle<Mesh>,
 }
 
 impl FromWorld for CachedAssets {
     fn from_world(world: &mut World) -> Self {
-        let (ball_mesh, crab_mesh, pole_mesh) = {
+        let (ball_mesh, pole_mesh, particle_mesh) = {
             let mut meshes = world.get_resource_mut::<Assets<Mesh>>().unwrap();
 
             (
                 meshes.add(Sphere { radius: 0.5 }),
-                // TODO: Replace with crab model.
-                meshes.add(Capsule3d {
-                    half_length: 0.25,
-                    radius: 0.5,
-                }),
                 meshes.add(Cylinder {
                     half_height: 0.5,
                     radius: 0.5,
                 }),
+                meshes.add(Cuboid::from_length(1.0)),
             )
         };
         let pole_material = {
@@ -125,12 +269,57 @@ impl FromWorld for CachedAssets {
 
             materials.add(Color::Srgba(Srgba::hex("00A400").unwrap()))
         };
+        let (crab_animation_graph, crab_animations) = {
+            let game_assets = world.get_resource::<GameAssets>().unwrap();
+
+            // In the same order as the CrabAnimation variants.
+            let clips = [
+                game_assets.animation_crab_idle.clone(),
+                game_assets.animation_crab_scuttle_left.clone(),
+                game_assets.animation_crab_scuttle_right.clone(),
+                game_assets.animation_crab_hit.clone(),
+                game_assets.animation_crab_defeat.clone(),
+            ];
+            let (graph, node_indices) = AnimationGraph::from_clips(clips);
+            let mut graphs =
+                world.get_resource_mut::<Assets<AnimationGraph>>().unwrap();
+
+            (
+                graphs.add(graph),
+                CrabAnimation::iter().zip(node_indices).collect(),
+            )
+        };
 
         Self {
             ball_mesh,
-            crab_mesh,
+            crab_animation_graph,
+            crab_animations,
             pole_mesh,
             pole_material,
+            particle_mesh,
         }
     }
 }
+
+/// Leaves game modes that couldn't be played out of the ones to choose from.
+fn reject_invalid_game_modes(
+    mut game_assets: ResMut<GameAssets>,
+    game_modes: Res<Assets<GameMode>>,
+) {
+    game_assets.game_modes.retain(|handle| {
+        let Some(game_mode) = game_modes.get(handle) else {
+            return false;
+        };
+        let duplicate_slot = game_mode.duplicate_player_slot();
+
+        if let Some(slot) = duplicate_slot {
+            warn!(
+                "Game Modes: Rejected {:?}, which gives {slot:?} to more than \
+                 one player",
+                game_mode.name
+            );
+        }
+
+        duplicate_slot.is_none()
+    });
+}
@@ -0,0 +1,417 @@
+use std::{
+    io::{ErrorKind, Read, Write},
+    net::{SocketAddr, TcpListener, TcpStream},
+    thread,
+    time::{Duration, Instant},
+};
+
+use bevy::{prelude::*, time::TimeUpdateStrategy};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    assets::CrabController,
+    components::{Force, RemoteCommands, Side},
+    gym::{CrabObservation, GymAction, observe_crabs},
+    states::GameState,
+    system_params::GameModes,
+};
+
+/// How long a lockstep tick waits for slow bots before moving on without
+/// them.
+const LOCKSTEP_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// How much game time passes each lockstep tick, however long the bots take
+/// to answer.
+const LOCKSTEP_TIMESTEP: Duration = Duration::from_nanos(16_666_667);
+
+/// How many bytes can wait to be sent before a slow bot is dropped.
+const MAX_UNSENT_BYTES: usize = 1 << 20;
+
+/// Lets external processes drive [`CrabController::Remote`] crabs over TCP,
+/// with one line of JSON per message.
+pub struct BotsPlugin(pub BotsConfig);
+
+impl Plugin for BotsPlugin {
+    fn build(&self, app: &mut App) {
+        let BotsConfig { address, lockstep } = self.0.clone();
+        let listener = match TcpListener::bind(address).and_then(|listener| {
+            listener.set_nonblocking(true).map(|_| listener)
+        }) {
+            Ok(listener) => listener,
+            Err(error) => {
+                error!("Bots: Failed to listen on {address}: {error}");
+                return;
+            },
+        };
+
+        info!("Bots: Listening on {address}");
+
+        if lockstep {
+            app.insert_resource(TimeUpdateStrategy::ManualDuration(
+                LOCKSTEP_TIMESTEP,
+            ));
+        }
+
+        app.insert_resource(BotServer {
+            listener,
+            bots: Vec::new(),
+            lockstep,
+            tick: 0,
+        })
+        .add_systems(
+            PreUpdate,
+            (accept_bots, receive_bot_commands, wait_for_lockstep_bots).chain(),
+        )
+        .add_systems(Last, send_observations_to_bots);
+    }
+}
+
+/// Bot options read from the command line.
+#[derive(Clone, Debug)]
+pub struct BotsConfig {
+    pub address: SocketAddr,
+
+    /// Waits every tick until each bot has answered the latest observation.
+    pub lockstep: bool,
+}
+
+impl BotsConfig {
+    /// Parses `--bots <address>` and an optional `--bot-lockstep` flag.
+    ///
+    /// Returns `None` if bots weren't requested or the address is invalid.
+    pub fn from_args(args: &[String]) -> Option<Self> {
+        let address = args
+            .iter()
+            .skip_while(|arg| *arg != "--bots")
+            .nth(1)?
+            .parse()
+            .ok()?;
+
+        Some(Self {
+            address,
+            lockstep: args.iter().any(|arg| arg == "--bot-lockstep"),
+        })
+    }
+}
+
+/// A message from a bot.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BotRequest {
+    /// Takes over a remote crab, or the first free one if `side` is missing.
+    Hello {
+        #[serde(default)]
+        side: Option<Side>,
+    },
+
+    /// Moves the bot's crab until the next command.
+    Act { tick: u32, action: GymAction },
+}
+
+/// A message to a bot.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BotMessage {
+    Welcome {
+        side: Side,
+    },
+    Rejected {
+        reason: String,
+    },
+
+    /// What the bot's crab sees, to be answered with a [`BotRequest::Act`]
+    /// for the same tick.
+    Observe {
+        tick: u32,
+        observation: CrabObservation,
+    },
+}
+
+/// Accepts bots and tracks which crab each one drives.
+#[derive(Resource)]
+pub struct BotServer {
+    listener: TcpListener,
+    bots: Vec<Bot>,
+    lockstep: bool,
+    tick: u32,
+}
+
+struct Bot {
+    address: SocketAddr,
+    stream: TcpStream,
+    buffer: Vec<u8>,
+
+    /// The bytes the socket couldn't take yet.
+    unsent: Vec<u8>,
+    side: Option<Side>,
+
+    /// The tick of the last observation this bot hasn't answered yet.
+    awaiting: Option<u32>,
+    connected: bool,
+}
+
+impl Bot {
+    fn send(&mut self, message: &BotMessage) {
+        let mut line = match serde_json::to_vec(message) {
+            Ok(line) => line,
+            Err(error) => {
+                warn!(
+                    "Bot({}): Failed to encode message: {error}",
+                    self.address
+                );
+                return;
+            },
+        };
+
+        line.push(b'\n');
+
+        if self.unsent.len() + line.len() > MAX_UNSENT_BYTES {
+            info!("Bot({}): Disconnected: Too slow", self.address);
+            self.connected = false;
+            return;
+        }
+
+        self.unsent.extend_from_slice(&line);
+        self.flush();
+    }
+
+    /// Writes as much as the socket takes without blocking, and keeps the rest
+    /// for later.
+    fn flush(&mut self) {
+        while self.connected && !self.unsent.is_empty() {
+            match self.stream.write(&self.unsent) {
+                Ok(0) => {
+                    info!("Bot({}): Disconnected", self.address);
+                    self.connected = false;
+                },
+                Ok(written) => {
+                    self.unsent.drain(..written);
+                },
+                Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+                Err(error) if error.kind() == ErrorKind::Interrupted => {},
+                Err(error) => {
+                    info!("Bot({}): Disconnected: {error}", self.address);
+                    self.connected = false;
+                },
+            }
+        }
+    }
+
+    /// Sends what's left to send, then reads every complete line waiting on
+    /// the socket.
+    fn receive(&mut self) -> Vec<BotRequest> {
+        let mut chunk = [0; 4096];
+
+        self.flush();
+
+        loop {
+            match self.stream.read(&mut chunk) {
+                Ok(0) => {
+                    info!("Bot({}): Disconnected", self.address);
+                    self.connected = false;
+                    break;
+                },
+                Ok(length) => self.buffer.extend_from_slice(&chunk[..length]),
+                Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+                Err(error) => {
+                    info!("Bot({}): Disconnected: {error}", self.address);
+                    self.connected = false;
+                    break;
+                },
+            }
+        }
+
+        let mut requests = Vec::new();
+
+        while let Some(end) = self.buffer.iter().position(|byte| *byte == b'\n')
+        {
+            let line: Vec<_> = self.buffer.drain(..=end).collect();
+
+            match serde_json::from_slice(&line) {
+                Ok(request) => requests.push(request),
+                Err(error) => {
+                    self.send(&BotMessage::Rejected {
+                        reason: error.to_string(),
+                    });
+                },
+            }
+        }
+
+        requests
+    }
+}
+
+fn accept_bots(mut server: ResMut<BotServer>) {
+    loop {
+        match server.listener.accept() {
+            Ok((stream, address)) => {
+                if let Err(error) = stream.set_nonblocking(true) {
+                    warn!("Bot({address}): Failed to connect: {error}");
+                    continue;
+                }
+
+                info!("Bot({address}): Connected");
+                server.bots.push(Bot {
+                    address,
+                    stream,
+                    buffer: Vec::new(),
+                    unsent: Vec::new(),
+                    side: None,
+                    awaiting: None,
+                    connected: true,
+                });
+            },
+            Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+            Err(error) => {
+                warn!("Bots: Failed to accept: {error}");
+                break;
+            },
+        }
+    }
+}
+
+fn receive_bot_commands(
+    mut server: ResMut<BotServer>,
+    mut remote_commands: ResMut<RemoteCommands>,
+    game_modes: GameModes,
+) {
+    let remote_sides = remote_sides(&game_modes);
+
+    receive_from_all_bots(&mut server, &mut remote_commands, &remote_sides);
+}
+
+fn wait_for_lockstep_bots(
+    mut server: ResMut<BotServer>,
+    mut remote_commands: ResMut<RemoteCommands>,
+    game_modes: GameModes,
+    game_state: Res<State<GameState>>,
+) {
+    if !server.lockstep || *game_state.get() != GameState::Playing {
+        return;
+    }
+
+    let remote_sides = remote_sides(&game_modes);
+    let deadline = Instant::now() + LOCKSTEP_TIMEOUT;
+
+    while server.bots.iter().any(|bot| bot.awaiting.is_some()) {
+        if Instant::now() > deadline {
+            warn!("Bots: Gave up waiting for tick {}", server.tick);
+            break;
+        }
+
+        thread::sleep(Duration::from_millis(1));
+        receive_from_all_bots(&mut server, &mut remote_commands, &remote_sides);
+    }
+}
+
+fn receive_from_all_bots(
+    server: &mut BotServer,
+    remote_commands: &mut RemoteCommands,
+    remote_sides: &[Side],
+) {
+    for index in 0..server.bots.len() {
+        for request in server.bots[index].receive() {
+            match request {
+                BotRequest::Hello { side } => {
+                    let taken: Vec<_> =
+                        server.bots.iter().filter_map(|bot| bot.side).collect();
+                    let free_side = remote_sides
+                        .iter()
+                        .copied()
+                        .filter(|free| !taken.contains(free))
+                        .find(|free| side.is_none_or(|side| side == *free));
+                    let bot = &mut server.bots[index];
+
+                    if bot.side.is_some() {
+                        bot.send(&BotMessage::Rejected {
+                            reason: "Already driving a crab".into(),
+                        });
+                    } else if let Some(free_side) = free_side {
+                        bot.side = Some(free_side);
+                        remote_commands.0.insert(free_side, None);
+                        bot.send(&BotMessage::Welcome { side: free_side });
+                        info!("Bot({}): Driving {free_side:?}", bot.address);
+                    } else {
+                        bot.send(&BotMessage::Rejected {
+                            reason: "No free remote crab".into(),
+                        });
+                    }
+                },
+                BotRequest::Act { tick, action } => {
+                    let bot = &mut server.bots[index];
+                    let Some(side) = bot.side else {
+                        continue;
+                    };
+
+                    if bot.awaiting.is_some_and(|awaiting| tick >= awaiting) {
+                        bot.awaiting = None;
+                    }
+
+                    let force = match action {
+                        GymAction::Left => Some(Force::Negative),
+                        GymAction::Right => Some(Force::Positive),
+                        GymAction::Stop => None,
+                    };
+
+                    remote_commands.0.insert(side, force);
+                },
+            }
+        }
+    }
+
+    // Hand crabs of disconnected bots back to the AI.
+    server.bots.retain(|bot| {
+        if !bot.connected {
+            if let Some(side) = bot.side {
+                remote_commands.0.remove(&side);
+            }
+        }
+
+        bot.connected
+    });
+}
+
+fn send_observations_to_bots(world: &mut World) {
+    if *world.resource::<State<GameState>>() != GameState::Playing {
+        return;
+    }
+
+    let observations = observe_crabs(world);
+    let mut server = world.resource_mut::<BotServer>();
+
+    server.tick += 1;
+
+    let tick = server.tick;
+
+    for bot in &mut server.bots {
+        let Some(observation) = observations
+            .iter()
+            .find(|observation| Some(observation.side) == bot.side)
+        else {
+            continue;
+        };
+
+        bot.awaiting = Some(tick);
+        bot.send(&BotMessage::Observe {
+            tick,
+            observation: observation.clone(),
+        });
+    }
+}
+
+/// Gets every side with a [`CrabController::Remote`] crab in the current game
+/// mode.
+fn remote_sides(game_modes: &GameModes) -> Vec<Side> {
+    let mut sides: Vec<_> = game_modes
+        .current()
+        .competitors
+        .iter()
+        .filter(|(_, competitor)| {
+            competitor.controller == CrabController::Remote
+        })
+        .map(|(side, _)| *side)
+        .collect();
+
+    sides.sort_by_key(|side| *side as usize);
+    sides
+}
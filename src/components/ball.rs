@@ -1,5 +1,12 @@
 use bevy::prelude::*;
 
+use super::Side;
+
 /// Marks a ball entity that can collide and score.
-#[derive(Component, Debug)]
+#[derive(Clone, Component, Debug)]
 pub struct Ball;
+
+/// The side of the crab that last deflected a [`Ball`] entity, which gets the
+/// credit if it scores.
+#[derive(Clone, Component, Copy, Debug)]
+pub struct LastTouchedBy(pub Side);
@@ -0,0 +1,270 @@
+use bevy::{prelude::*, render::camera::ScalingMode};
+use rand::prelude::*;
+
+use crate::{
+    components::{
+        Ball, GoalScoredEvent, Motion, Player, PlayerDevice, PlayerSlots,
+    },
+    settings::{CameraMode, Settings},
+    spawners::Beach,
+    system_params::GameModes,
+    system_sets::{ActiveAfterLoadingSet, SideEffectsSet},
+};
+
+pub(super) struct CameraRigPlugin;
+
+impl Plugin for CameraRigPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                start_camera_shake_on_goals,
+                switch_camera_projections,
+                move_cameras_for_their_camera_mode,
+            )
+                .chain()
+                .in_set(ActiveAfterLoadingSet)
+                .in_set(SideEffectsSet),
+        );
+    }
+}
+
+/// How far above the beach the top-down camera sits.
+const TOP_DOWN_HEIGHT: f32 = 3.0;
+
+/// How much wider than the beach the top-down view is.
+const TOP_DOWN_MARGIN: f32 = 1.3;
+
+/// How quickly a turning or following camera catches up, per second.
+const CAMERA_SMOOTHING: f32 = 3.0;
+
+/// How far the following camera stays from the balls, per unit of their
+/// spread, and the closest it gets.
+const FOLLOW_DISTANCE_PER_SPREAD: f32 = 3.0;
+const FOLLOW_MIN_DISTANCE: f32 = 1.2;
+
+const SHAKE_SECS: f32 = 0.3;
+const SHAKE_DISTANCE: f32 = 0.02;
+
+/// Marks a [`Camera3d`] entity that looks at the beach the way the camera
+/// mode, from the game mode or the settings, describes.
+///
+/// Its default view is from `starting_position`, focusing on `target`, which
+/// it sways `range` across at `speed` in [`CameraMode::Sway`].
+#[derive(Component, Debug)]
+#[require(Camera3d, Transform)]
+pub struct CameraRig {
+    pub target: Vec3,
+    pub starting_position: Vec3,
+    pub up_direction: Vec3,
+    pub range: f32,
+    pub speed: f32,
+}
+
+/// Shakes a [`CameraRig`] entity after a goal is scored.
+///
+/// The `offset` added to the camera's transform one frame is taken off again
+/// the next, so it never builds up in cameras that smoothly follow a view.
+#[derive(Component, Debug)]
+pub struct CameraShake {
+    pub timer: Timer,
+    pub offset: Vec3,
+}
+
+fn start_camera_shake_on_goals(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut goal_scored_events: EventReader<GoalScoredEvent>,
+    mut rigs_query: Query<(Entity, Option<&mut CameraShake>), With<CameraRig>>,
+) {
+    // Reduced motion keeps the camera steady.
+    if goal_scored_events.read().count() == 0 || !settings.camera_shakes() {
+        return;
+    }
+
+    for (entity, shake) in &mut rigs_query {
+        // Keep the current offset, so it's still taken off next frame.
+        match shake {
+            Some(mut shake) => shake.timer.reset(),
+            None => {
+                commands.entity(entity).insert(CameraShake {
+                    timer: Timer::from_seconds(SHAKE_SECS, TimerMode::Once),
+                    offset: Vec3::ZERO,
+                });
+            },
+        }
+    }
+}
+
+fn switch_camera_projections(
+    settings: Res<Settings>,
+    game_modes: GameModes,
+    beach: Res<Beach>,
+    mut rigs_query: Query<&mut Projection, With<CameraRig>>,
+) {
+    let camera_mode = camera_mode(&game_modes, &settings);
+
+    for mut projection in &mut rigs_query {
+        match (camera_mode, &*projection) {
+            (CameraMode::TopDown, Projection::Orthographic(_)) => {},
+            (CameraMode::TopDown, _) => {
+                let view_size = beach.width * TOP_DOWN_MARGIN;
+
+                *projection =
+                    Projection::Orthographic(OrthographicProjection {
+                        scaling_mode: ScalingMode::AutoMin {
+                            min_width: view_size,
+                            min_height: view_size,
+                        },
+                        ..OrthographicProjection::default_3d()
+                    });
+            },
+            (_, Projection::Perspective(_)) => {},
+            (_, _) => *projection = Projection::default(),
+        }
+    }
+}
+
+fn move_cameras_for_their_camera_mode(
+    mut commands: Commands,
+    time: Res<Time>,
+    settings: Res<Settings>,
+    game_modes: GameModes,
+    player_slots: Res<PlayerSlots>,
+    players_query: Query<(&Player, &Parent)>,
+    goals_query: Query<&Transform, Without<CameraRig>>,
+    balls_query: Query<&GlobalTransform, (With<Ball>, With<Motion>)>,
+    mut rigs_query: Query<(
+        Entity,
+        &CameraRig,
+        &mut Transform,
+        Option<&mut CameraShake>,
+    )>,
+) {
+    let camera_mode = camera_mode(&game_modes, &settings);
+    let smoothing = (CAMERA_SMOOTHING * time.delta_secs()).min(1.0);
+
+    for (entity, rig, mut transform, mut shake) in &mut rigs_query {
+        if let Some(shake) = &mut shake {
+            transform.translation -= shake.offset;
+            shake.offset = Vec3::ZERO;
+        }
+
+        match camera_mode {
+            CameraMode::Sway => {
+                // Hold still in the middle when swaying is turned off.
+                let x_offset = if settings.camera_sways() {
+                    (time.elapsed_secs() * rig.speed).sin() * (0.5 * rig.range)
+                } else {
+                    0.0
+                };
+
+                *transform = Transform::from_translation(
+                    rig.starting_position + Vec3::X * x_offset,
+                )
+                .looking_at(rig.target, rig.up_direction);
+            },
+            CameraMode::TopDown => {
+                // Screen up is away from the bottom goal, as in the other
+                // views.
+                *transform = Transform::from_translation(
+                    rig.target + Vec3::Y * TOP_DOWN_HEIGHT,
+                )
+                .looking_at(rig.target, Vec3::NEG_Z);
+            },
+            CameraMode::PlayerAtBottom => {
+                // Remote players' crabs are played on other machines.
+                let goal_rotation = players_query
+                    .iter()
+                    .filter(|(player, _)| {
+                        !matches!(
+                            player_slots.device(player.0),
+                            PlayerDevice::Network(_)
+                        )
+                    })
+                    .min_by_key(|(player, _)| player.0)
+                    .and_then(|(_, parent)| goals_query.get(parent.get()).ok())
+                    .map_or(Quat::IDENTITY, |goal_transform| {
+                        goal_transform.rotation
+                    });
+                let offset = rig.starting_position - rig.target;
+                let view = Transform::from_translation(
+                    rig.target + goal_rotation * offset,
+                )
+                .looking_at(rig.target, rig.up_direction);
+
+                smooth_toward(&mut transform, &view, smoothing);
+            },
+            CameraMode::FollowBalls => {
+                let positions: Vec<Vec3> = balls_query
+                    .iter()
+                    .map(|global_transform| {
+                        global_transform.translation().with_y(rig.target.y)
+                    })
+                    .collect();
+                let view = if positions.is_empty() {
+                    Transform::from_translation(rig.starting_position)
+                        .looking_at(rig.target, rig.up_direction)
+                } else {
+                    let center =
+                        positions.iter().sum::<Vec3>() / positions.len() as f32;
+                    let spread = positions
+                        .iter()
+                        .map(|position| position.distance(center))
+                        .fold(0.0, f32::max);
+                    let offset = rig.starting_position - rig.target;
+                    let distance = (spread * FOLLOW_DISTANCE_PER_SPREAD)
+                        .clamp(FOLLOW_MIN_DISTANCE, offset.length());
+
+                    Transform::from_translation(
+                        center + offset.normalize() * distance,
+                    )
+                    .looking_at(center, rig.up_direction)
+                };
+
+                smooth_toward(&mut transform, &view, smoothing);
+            },
+        }
+
+        let Some(mut shake) = shake else {
+            continue;
+        };
+
+        shake.timer.tick(time.delta());
+
+        if shake.timer.finished() {
+            commands.entity(entity).remove::<CameraShake>();
+            continue;
+        }
+
+        let mut rng = rand::rng();
+        let direction = Vec3::new(
+            rng.random_range(-1.0..=1.0),
+            rng.random_range(-1.0..=1.0),
+            0.0,
+        );
+        let rotation = transform.rotation;
+
+        shake.offset = rotation
+            * direction
+            * SHAKE_DISTANCE
+            * shake.timer.fraction_remaining();
+        transform.translation += shake.offset;
+    }
+}
+
+/// The camera mode of the game mode on the beach, or the one from the
+/// settings if it doesn't pick one.
+fn camera_mode(game_modes: &GameModes, settings: &Settings) -> CameraMode {
+    game_modes
+        .in_play()
+        .camera_mode
+        .unwrap_or(settings.general.camera_mode)
+}
+
+/// Moves part of the way from a camera's transform to another.
+fn smooth_toward(transform: &mut Transform, target: &Transform, amount: f32) {
+    transform.translation =
+        transform.translation.lerp(target.translation, amount);
+    transform.rotation = transform.rotation.slerp(target.rotation, amount);
+}
@@ -1,8 +1,8 @@
 mod anchored_ui_camera;
-mod swaying_camera;
+mod camera_rig;
 
 pub use anchored_ui_camera::*;
-pub use swaying_camera::*;
+pub use camera_rig::*;
 
 use bevy::prelude::*;
 
@@ -10,6 +10,6 @@ pub(super) struct CameraPlugin;
 
 impl Plugin for CameraPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((AnchoredUiCameraPlugin, SwayingCameraPlugin));
+        app.add_plugins((AnchoredUiCameraPlugin, CameraRigPlugin));
     }
 }
@@ -2,13 +2,13 @@ use bevy::prelude::*;
 
 use crate::system_sets::StopWhenPausedSet;
 
-use super::{Direction, Motion};
+use super::{Ball, Direction, Motion};
 
 pub(super) struct ColliderPlugin;
 
 impl Plugin for ColliderPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
+        app.add_event::<BallBouncedEvent>().add_systems(
             PostUpdate,
             circle_to_circle_collisions.in_set(StopWhenPausedSet),
         );
@@ -16,23 +16,40 @@ impl Plugin for ColliderPlugin {
 }
 
 /// Marks an entity as collidable.
-#[derive(Component, Debug, Default)]
+#[derive(Clone, Component, Debug, Default)]
 pub struct Collider;
 
 /// Adds a circular collider shape.
-#[derive(Component, Debug)]
+#[derive(Clone, Component, Debug)]
 pub struct CircleCollider {
     pub radius: f32,
 }
 
 /// Adds thickness to a entity in a goal.
-#[derive(Component, Debug)]
+#[derive(Clone, Component, Debug)]
 pub struct DepthCollider {
     pub depth: f32,
 }
 
+/// What a [`Ball`] entity bounced off.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Obstacle {
+    Ball,
+    Barrier,
+    Pole,
+}
+
+/// Signals that a [`Ball`] entity has bounced off something other than a
+/// crab.
+#[derive(Clone, Debug, Event)]
+pub struct BallBouncedEvent {
+    pub ball_entity: Entity,
+    pub obstacle: Obstacle,
+}
+
 fn circle_to_circle_collisions(
     mut commands: Commands,
+    mut ball_bounced_events: EventWriter<BallBouncedEvent>,
     balls_query: Query<
         (
             Entity,
@@ -40,13 +57,14 @@ fn circle_to_circle_collisions(
             &GlobalTransform,
             Option<&Direction>,
             Has<Motion>,
+            Has<Ball>,
         ),
         With<Collider>,
     >,
 ) {
     for [
-        (entity1, circle1, transform1, direction1, has_motion1),
-        (entity2, circle2, transform2, direction2, has_motion2),
+        (entity1, circle1, transform1, direction1, has_motion1, is_ball1),
+        (entity2, circle2, transform2, direction2, has_motion2, is_ball2),
     ] in balls_query.iter_combinations()
     {
         // Check that both circles are close enough to touch.
@@ -59,6 +77,7 @@ fn circle_to_circle_collisions(
         // Deflect both circles away from each other.
         let axis1 = Vec3::new(delta.x, 0.0, delta.z).normalize();
         let axis2 = -axis1;
+        let mut bounced_entity = None;
 
         if let Some(direction1) = direction1 {
             if has_motion1 && direction1.0.dot(axis1) > 0.0 {
@@ -69,6 +88,8 @@ fn circle_to_circle_collisions(
                 if has_motion2 {
                     commands.entity(entity2).insert(Direction::from(axis1));
                 }
+
+                bounced_entity = Some(entity1);
                 info!("Circle({entity1:?}): Deflected Circle({entity2:?})");
             }
         }
@@ -82,8 +103,26 @@ fn circle_to_circle_collisions(
                 if has_motion1 {
                     commands.entity(entity1).insert(Direction::from(axis2));
                 }
+
+                bounced_entity = bounced_entity.or(Some(entity2));
                 info!("Circle({entity2:?}): Deflected Circle({entity1:?})");
             }
         }
+
+        // One event per collision, even when both circles were deflected.
+        let Some(ball_entity) = bounced_entity else {
+            continue;
+        };
+
+        if is_ball1 || is_ball2 {
+            ball_bounced_events.send(BallBouncedEvent {
+                ball_entity,
+                obstacle: if is_ball1 && is_ball2 {
+                    Obstacle::Ball
+                } else {
+                    Obstacle::Barrier
+                },
+            });
+        }
     }
 }
@@ -3,7 +3,7 @@ use bevy::prelude::*;
 use crate::{
     components::{Ball, Collider, Force, Motion, StoppingDistance},
     system_params::Goals,
-    system_sets::ActiveDuringGameplaySet,
+    system_sets::ActiveDuringMatchSet,
 };
 
 use super::{Crab, CrabCollider};
@@ -17,13 +17,13 @@ impl Plugin for AiPlugin {
         app.add_systems(
             Update,
             make_ai_crabs_target_and_move_toward_the_ball_closest_to_their_goal
-                .in_set(ActiveDuringGameplaySet),
+                .in_set(ActiveDuringMatchSet),
         );
     }
 }
 
 /// Marks a [`Crab`] entity as being controlled by AI.
-#[derive(Component, Debug)]
+#[derive(Clone, Component, Debug)]
 #[require(Crab)]
 pub struct AI;
 
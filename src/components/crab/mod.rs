@@ -1,8 +1,14 @@
 mod ai;
+mod model;
 mod player;
+mod pointer;
+mod remote;
 
 pub use ai::*;
+pub use model::*;
 pub use player::*;
+pub use pointer::*;
+pub use remote::*;
 
 use bevy::prelude::*;
 
@@ -12,14 +18,23 @@ use crate::{
 };
 
 use super::{
-    Ball, CircleCollider, Collider, DepthCollider, Direction, Motion, Side,
+    Ball, CircleCollider, Collider, DepthCollider, Direction, LastTouchedBy,
+    Motion, Side,
 };
 
 pub(super) struct CrabPlugin;
 
 impl Plugin for CrabPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((AiPlugin, InputPlugin)).add_systems(
+        app.add_plugins((
+            AiPlugin,
+            InputPlugin,
+            ModelPlugin,
+            PointerPlugin,
+            RemotePlugin,
+        ))
+        .add_event::<BallDeflectedEvent>()
+        .add_systems(
             PostUpdate,
             crab_and_ball_collisions.in_set(StopWhenPausedSet),
         );
@@ -27,11 +42,11 @@ impl Plugin for CrabPlugin {
 }
 
 /// Makes a crab entity that can deflect balls and move sideways inside a goal.
-#[derive(Component, Debug, Default)]
+#[derive(Clone, Component, Debug, Default)]
 pub struct Crab;
 
 /// Physics and collision data for a [Crab] entity.
-#[derive(Component, Debug, Default)]
+#[derive(Clone, Component, Debug, Default)]
 pub struct CrabCollider {
     /// Width of the bounding shape.
     pub width: f32,
@@ -50,11 +65,26 @@ impl CrabCollider {
     }
 }
 
+/// Signals that a [`Crab`] entity has deflected a [`Ball`] entity.
+#[derive(Clone, Debug, Event)]
+pub struct BallDeflectedEvent {
+    pub crab_entity: Entity,
+    pub ball_entity: Entity,
+}
+
 fn crab_and_ball_collisions(
     mut commands: Commands,
+    mut ball_deflected_events: EventWriter<BallDeflectedEvent>,
     goals: Goals,
     crabs_query: Query<
-        (&Parent, &Side, &Transform, &CrabCollider, &DepthCollider),
+        (
+            Entity,
+            &Parent,
+            &Side,
+            &Transform,
+            &CrabCollider,
+            &DepthCollider,
+        ),
         (With<Crab>, With<Collider>),
     >,
     balls_query: Query<
@@ -62,8 +92,14 @@ fn crab_and_ball_collisions(
         (With<Ball>, With<Collider>, With<Motion>),
     >,
 ) {
-    for (parent, side, crab_transform, crab_collider, crab_depth_collider) in
-        &crabs_query
+    for (
+        crab_entity,
+        parent,
+        side,
+        crab_transform,
+        crab_collider,
+        crab_depth_collider,
+    ) in &crabs_query
     {
         let Ok(goal) = goals.get(parent.get()) else {
             continue;
@@ -97,9 +133,14 @@ fn crab_and_ball_collisions(
             // Deflect the ball.
             let new_ball_direction = crab_collider.deflect(&goal, ball_delta_x);
 
-            commands
-                .entity(ball_entity)
-                .insert(Direction::from(new_ball_direction));
+            commands.entity(ball_entity).insert((
+                Direction::from(new_ball_direction),
+                LastTouchedBy(*side),
+            ));
+            ball_deflected_events.send(BallDeflectedEvent {
+                crab_entity,
+                ball_entity,
+            });
             info!("Crab({side:?}): Deflected Ball({ball_entity:?})");
             break;
         }
@@ -0,0 +1,256 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, scene::SceneInstanceReady};
+use strum::EnumIter;
+
+use crate::{
+    assets::CachedAssets,
+    components::{Force, Goal, GoalEliminatedEvent, MaxSpeed, Speed},
+    states::GameState,
+    system_sets::{SideEffectsSet, StopWhenPausedSet},
+};
+
+use super::{BallDeflectedEvent, Crab};
+
+pub(super) struct ModelPlugin;
+
+impl Plugin for ModelPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Paused), pause_crab_animations)
+            .add_systems(OnExit(GameState::Paused), resume_crab_animations)
+            .add_systems(
+                Update,
+                (play_hit_and_defeat_animations, play_movement_animations)
+                    .chain()
+                    .in_set(StopWhenPausedSet)
+                    .in_set(SideEffectsSet),
+            )
+            .add_observer(set_up_crab_model);
+    }
+}
+
+/// How long it takes to blend from one animation into another.
+const BLEND_SECS: f32 = 0.15;
+
+/// How long it takes to blend into a reaction, which should feel immediate.
+const REACTION_BLEND_SECS: f32 = 0.05;
+
+/// The fraction of its max speed a coasting crab must be moving at to keep
+/// scuttling.
+const SCUTTLE_SPEED_FRACTION: f32 = 0.1;
+
+/// How fast the scuttle animations play when a crab starts moving, relative
+/// to when it's at max speed.
+const MIN_SCUTTLE_PLAYBACK_SPEED: f32 = 0.5;
+
+/// The animations of the crab model, in the order they're stored in it.
+#[derive(Clone, Copy, Debug, EnumIter, Eq, Hash, PartialEq)]
+pub enum CrabAnimation {
+    Idle,
+    ScuttleLeft,
+    ScuttleRight,
+
+    /// Played once when the crab deflects a ball.
+    Hit,
+
+    /// Played once when the crab's goal is eliminated, holding its last pose.
+    Defeat,
+}
+
+/// Links a [`Crab`] entity to the animation player in its model, once the
+/// model's scene has spawned.
+#[derive(Clone, Component, Debug)]
+pub struct CrabModel {
+    pub player_entity: Entity,
+    pub animation: CrabAnimation,
+}
+
+/// Gives every part of a crab's model the crab's own material, which is in
+/// its competitor's color and fades with the crab, and starts the model
+/// idling.
+fn set_up_crab_model(
+    trigger: Trigger<SceneInstanceReady>,
+    mut commands: Commands,
+    cached_assets: Res<CachedAssets>,
+    crabs_query: Query<&MeshMaterial3d<StandardMaterial>, With<Crab>>,
+    children_query: Query<&Children>,
+    mut parts_query: Query<(Has<Mesh3d>, Option<&mut AnimationPlayer>)>,
+) {
+    let crab_entity = trigger.entity();
+    let Ok(crab_material) = crabs_query.get(crab_entity) else {
+        return;
+    };
+
+    for part_entity in children_query.iter_descendants(crab_entity) {
+        let Ok((has_mesh, player)) = parts_query.get_mut(part_entity) else {
+            continue;
+        };
+
+        if has_mesh {
+            commands.entity(part_entity).insert(crab_material.clone());
+        }
+
+        let Some(mut player) = player else {
+            continue;
+        };
+        let mut transitions = AnimationTransitions::new();
+
+        transitions
+            .play(
+                &mut player,
+                cached_assets.crab_animations[&CrabAnimation::Idle],
+                Duration::ZERO,
+            )
+            .repeat();
+
+        commands.entity(part_entity).insert((
+            AnimationGraphHandle(cached_assets.crab_animation_graph.clone()),
+            transitions,
+        ));
+        commands.entity(crab_entity).insert(CrabModel {
+            player_entity: part_entity,
+            animation: CrabAnimation::Idle,
+        });
+    }
+}
+
+fn play_hit_and_defeat_animations(
+    mut ball_deflected_events: EventReader<BallDeflectedEvent>,
+    mut goal_eliminated_events: EventReader<GoalEliminatedEvent>,
+    cached_assets: Res<CachedAssets>,
+    goals_query: Query<&Children, With<Goal>>,
+    mut crabs_query: Query<&mut CrabModel>,
+    mut players_query: Query<(&mut AnimationPlayer, &mut AnimationTransitions)>,
+) {
+    let hits = ball_deflected_events
+        .read()
+        .map(|event| (event.crab_entity, CrabAnimation::Hit));
+    let defeats = goal_eliminated_events
+        .read()
+        .filter_map(|GoalEliminatedEvent(goal_entity)| {
+            goals_query.get(*goal_entity).ok()
+        })
+        .flatten()
+        .map(|crab_entity| (*crab_entity, CrabAnimation::Defeat));
+
+    for (crab_entity, animation) in hits.chain(defeats) {
+        let Ok(mut model) = crabs_query.get_mut(crab_entity) else {
+            continue;
+        };
+        let Ok((mut player, mut transitions)) =
+            players_query.get_mut(model.player_entity)
+        else {
+            continue;
+        };
+
+        // Defeated crabs stay down.
+        if model.animation == CrabAnimation::Defeat {
+            continue;
+        }
+
+        let node_index = cached_assets.crab_animations[&animation];
+
+        // Replaying in place, since blending an animation into itself would
+        // fade it out.
+        if model.animation == animation {
+            if let Some(active_animation) = player.animation_mut(node_index) {
+                active_animation.replay();
+            }
+
+            continue;
+        }
+
+        transitions.play(
+            &mut player,
+            node_index,
+            Duration::from_secs_f32(REACTION_BLEND_SECS),
+        );
+        model.animation = animation;
+    }
+}
+
+/// Scuttles crabs the way they're moving, faster the faster they go, and
+/// idles them when they're still.
+fn play_movement_animations(
+    cached_assets: Res<CachedAssets>,
+    mut crabs_query: Query<(&mut CrabModel, &Speed, &MaxSpeed, Option<&Force>)>,
+    mut players_query: Query<(&mut AnimationPlayer, &mut AnimationTransitions)>,
+) {
+    for (mut model, speed, max_speed, force) in &mut crabs_query {
+        let Ok((mut player, mut transitions)) =
+            players_query.get_mut(model.player_entity)
+        else {
+            continue;
+        };
+
+        // Let reactions finish before moving on.
+        match model.animation {
+            CrabAnimation::Defeat => continue,
+            CrabAnimation::Hit => {
+                let hit_index = cached_assets.crab_animations[&model.animation];
+
+                if player.animation(hit_index).is_some_and(|active_animation| {
+                    !active_animation.is_finished()
+                }) {
+                    continue;
+                }
+            },
+            _ => {},
+        }
+
+        let speed_fraction = speed.0 / max_speed.0.max(f32::EPSILON);
+        let animation = match force {
+            Some(Force::Positive) => CrabAnimation::ScuttleRight,
+            Some(Force::Negative) => CrabAnimation::ScuttleLeft,
+            None if speed_fraction > SCUTTLE_SPEED_FRACTION => {
+                CrabAnimation::ScuttleRight
+            },
+            None if speed_fraction < -SCUTTLE_SPEED_FRACTION => {
+                CrabAnimation::ScuttleLeft
+            },
+            None => CrabAnimation::Idle,
+        };
+        let node_index = cached_assets.crab_animations[&animation];
+
+        if model.animation != animation {
+            transitions
+                .play(
+                    &mut player,
+                    node_index,
+                    Duration::from_secs_f32(BLEND_SECS),
+                )
+                .repeat();
+            model.animation = animation;
+        }
+
+        if animation != CrabAnimation::Idle {
+            if let Some(active_animation) = player.animation_mut(node_index) {
+                active_animation.set_speed(
+                    speed_fraction.abs().clamp(MIN_SCUTTLE_PLAYBACK_SPEED, 1.0),
+                );
+            }
+        }
+    }
+}
+
+fn pause_crab_animations(
+    crabs_query: Query<&CrabModel>,
+    mut players_query: Query<&mut AnimationPlayer>,
+) {
+    for model in &crabs_query {
+        if let Ok(mut player) = players_query.get_mut(model.player_entity) {
+            player.pause_all();
+        }
+    }
+}
+
+fn resume_crab_animations(
+    crabs_query: Query<&CrabModel>,
+    mut players_query: Query<&mut AnimationPlayer>,
+) {
+    for model in &crabs_query {
+        if let Ok(mut player) = players_query.get_mut(model.player_entity) {
+            player.resume_all();
+        }
+    }
+}
@@ -1,18 +1,24 @@
-use bevy::prelude::*;
+use std::net::SocketAddr;
+
+use bevy::{prelude::*, utils::HashMap};
 use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::{EnumIter, IntoEnumIterator};
 
 use crate::{
-    components::{Force, Motion, Side},
+    components::{Force, MaxSpeed, Motion, TargetSpeed},
+    system_params::Goals,
     system_sets::ActiveDuringGameplaySet,
 };
 
-use super::Crab;
+use super::{Crab, PointerControlled};
 
 pub(super) struct InputPlugin;
 
 impl Plugin for InputPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(InputManagerPlugin::<CrabAction>::default())
+            .init_resource::<PlayerSlots>()
             .add_systems(
                 Update,
                 move_crabs_based_on_user_input.in_set(ActiveDuringGameplaySet),
@@ -21,83 +27,320 @@ impl Plugin for InputPlugin {
 }
 
 /// [`Player`] input actions that move [`Crab`] entities.
-#[derive(Actionlike, Clone, Copy, Debug, Eq, Hash, PartialEq, Reflect)]
+#[derive(
+    Actionlike,
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    EnumIter,
+    Eq,
+    Hash,
+    PartialEq,
+    Reflect,
+    Serialize,
+)]
 pub enum CrabAction {
     MoveUp,
     MoveDown,
     MoveLeft,
     MoveRight,
+
+    /// Analog stick movement, projected onto the crab's side.
+    #[actionlike(DualAxis)]
+    Move,
+
+    /// Trigger presses reserved for future crab abilities, read as buttons.
+    LeftTrigger,
+    RightTrigger,
 }
 
-/// Makes a Player [`Crab`] entity.
-#[derive(Component)]
-#[require(ActionState<CrabAction>, InputMap<CrabAction>(player_input_map), Crab)]
-pub struct Player;
+/// Makes a Player [`Crab`] entity that's controlled through a [`PlayerSlot`].
+#[derive(Clone, Component)]
+#[require(ActionState<CrabAction>, InputMap<CrabAction>, Crab)]
+pub struct Player(pub PlayerSlot);
 
-fn player_input_map() -> InputMap<CrabAction> {
-    use CrabAction::*;
+/// One of the local multiplayer slots a [`Player`] can occupy.
+#[derive(
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    EnumIter,
+    Eq,
+    Hash,
+    Ord,
+    PartialEq,
+    PartialOrd,
+    Serialize,
+)]
+pub enum PlayerSlot {
+    One,
+    Two,
+    Three,
+    Four,
+}
+
+/// A group of keys on a shared keyboard that can control one [`Player`].
+#[derive(
+    Clone, Copy, Debug, Deserialize, EnumIter, Eq, Hash, PartialEq, Serialize,
+)]
+pub enum KeyboardLayout {
+    Wasd,
+    Arrows,
+    Ijkl,
+    Numpad,
+}
+
+impl KeyboardLayout {
+    /// The keys bound to each [`CrabAction`] for this layout by default.
+    pub fn default_bindings(&self) -> [(CrabAction, KeyCode); 4] {
+        use CrabAction::*;
+        use KeyboardLayout::*;
+
+        match self {
+            Wasd => [
+                (MoveUp, KeyCode::KeyW),
+                (MoveDown, KeyCode::KeyS),
+                (MoveLeft, KeyCode::KeyA),
+                (MoveRight, KeyCode::KeyD),
+            ],
+            Arrows => [
+                (MoveUp, KeyCode::ArrowUp),
+                (MoveDown, KeyCode::ArrowDown),
+                (MoveLeft, KeyCode::ArrowLeft),
+                (MoveRight, KeyCode::ArrowRight),
+            ],
+            Ijkl => [
+                (MoveUp, KeyCode::KeyI),
+                (MoveDown, KeyCode::KeyK),
+                (MoveLeft, KeyCode::KeyJ),
+                (MoveRight, KeyCode::KeyL),
+            ],
+            Numpad => [
+                (MoveUp, KeyCode::Numpad8),
+                (MoveDown, KeyCode::Numpad2),
+                (MoveLeft, KeyCode::Numpad4),
+                (MoveRight, KeyCode::Numpad6),
+            ],
+        }
+    }
+}
+
+/// The device a [`PlayerSlot`] is controlled with.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum PlayerDevice {
+    Keyboard(KeyboardLayout),
+    Gamepad(Entity),
+
+    /// The mouse cursor or touch screen, via [`PointerControlled`].
+    Pointer,
+
+    /// A client connected over the network, whose inputs are sent to the host.
+    Network(SocketAddr),
+}
+
+/// Which device is assigned to each [`PlayerSlot`].
+///
+/// Every slot starts out with its own [`KeyboardLayout`], and gamepads or the
+/// pointer can take over a slot by joining from the start menu.
+#[derive(Debug, Resource)]
+pub struct PlayerSlots(pub HashMap<PlayerSlot, PlayerDevice>);
+
+impl Default for PlayerSlots {
+    fn default() -> Self {
+        Self(
+            PlayerSlot::iter()
+                .zip(KeyboardLayout::iter())
+                .map(|(slot, layout)| (slot, PlayerDevice::Keyboard(layout)))
+                .collect(),
+        )
+    }
+}
+
+impl PlayerSlots {
+    /// Gets the device assigned to a slot.
+    pub fn device(&self, slot: PlayerSlot) -> PlayerDevice {
+        self.0[&slot]
+    }
+
+    /// Assigns a gamepad to the first slot that only has a keyboard.
+    ///
+    /// Returns the slot it was assigned to, if any.
+    pub fn join_gamepad(&mut self, gamepad: Entity) -> Option<PlayerSlot> {
+        self.join(PlayerDevice::Gamepad(gamepad))
+    }
+
+    /// Returns a gamepad's slot to its default [`KeyboardLayout`].
+    ///
+    /// Returns the slot it was removed from, if any.
+    pub fn leave_gamepad(&mut self, gamepad: Entity) -> Option<PlayerSlot> {
+        self.leave(PlayerDevice::Gamepad(gamepad))
+    }
+
+    /// Moves the pointer to a slot, as long as it only has a keyboard, or
+    /// takes it away from every slot.
+    ///
+    /// Returns the slot it was assigned to, if any.
+    pub fn set_pointer_slot(
+        &mut self,
+        slot: Option<PlayerSlot>,
+    ) -> Option<PlayerSlot> {
+        if self.device_slot(PlayerDevice::Pointer) == slot {
+            return slot;
+        }
+
+        self.leave_pointer();
+
+        let slot = slot.filter(|slot| {
+            matches!(self.device(*slot), PlayerDevice::Keyboard(_))
+        })?;
+
+        self.0.insert(slot, PlayerDevice::Pointer);
+        Some(slot)
+    }
+
+    /// Returns the pointer's slot to its default [`KeyboardLayout`].
+    ///
+    /// Returns the slot it was removed from, if any.
+    pub fn leave_pointer(&mut self) -> Option<PlayerSlot> {
+        self.leave(PlayerDevice::Pointer)
+    }
+
+    /// Assigns a network client to the first of a game mode's player slots
+    /// that only has a keyboard, after the lowest one, which the host plays
+    /// in.
+    ///
+    /// Returns the slot it was assigned to, if any.
+    pub fn join_network(
+        &mut self,
+        address: SocketAddr,
+        mode_slots: impl IntoIterator<Item = PlayerSlot>,
+    ) -> Option<PlayerSlot> {
+        let device = PlayerDevice::Network(address);
+
+        if let Some(slot) = self.device_slot(device) {
+            return Some(slot);
+        }
+
+        let mut mode_slots: Vec<_> = mode_slots.into_iter().collect();
+
+        mode_slots.sort();
+        mode_slots.dedup();
+
+        let slot = mode_slots.into_iter().skip(1).find(|slot| {
+            matches!(self.device(*slot), PlayerDevice::Keyboard(_))
+        })?;
+
+        self.0.insert(slot, device);
+        Some(slot)
+    }
 
-    let input_map = InputMap::new([
-        (MoveUp, KeyCode::KeyW),
-        (MoveUp, KeyCode::ArrowUp),
-        (MoveDown, KeyCode::KeyS),
-        (MoveDown, KeyCode::ArrowDown),
-        (MoveLeft, KeyCode::KeyA),
-        (MoveLeft, KeyCode::ArrowLeft),
-        (MoveRight, KeyCode::KeyD),
-        (MoveRight, KeyCode::ArrowRight),
-    ])
-    .with_multiple([
-        (MoveUp, GamepadButton::DPadUp),
-        (MoveDown, GamepadButton::DPadDown),
-        (MoveLeft, GamepadButton::DPadLeft),
-        (MoveRight, GamepadButton::DPadRight),
-    ]);
-    // // TODO: Figure out why gamepad bindings keeps causing a panic!
-    // input_map.insert_axis(
-    //     MoveUp,
-    //     GamepadControlAxis::RIGHT_Y.with_deadzone_symmetric(0.4),
-    // );
-    // input_map.insert_axis(
-    //     MoveDown,
-    //     GamepadControlAxis::RIGHT_Y.with_deadzone_symmetric(-0.4),
-    // );
-    // input_map.insert_axis(
-    //     MoveLeft,
-    //     GamepadControlAxis::LEFT_X.with_deadzone_symmetric(-0.4),
-    // );
-    // input_map.insert_axis(
-    //     MoveRight,
-    //     GamepadControlAxis::LEFT_X.with_deadzone_symmetric(0.4),
-    // );
-
-    input_map
+    /// Returns a network client's slot to its default [`KeyboardLayout`].
+    ///
+    /// Returns the slot it was removed from, if any.
+    pub fn leave_network(&mut self, address: SocketAddr) -> Option<PlayerSlot> {
+        self.leave(PlayerDevice::Network(address))
+    }
+
+    /// Finds the slot a device is assigned to.
+    pub fn device_slot(&self, device: PlayerDevice) -> Option<PlayerSlot> {
+        PlayerSlot::iter().find(|slot| self.device(*slot) == device)
+    }
+
+    fn join(&mut self, device: PlayerDevice) -> Option<PlayerSlot> {
+        if let Some(slot) = self.device_slot(device) {
+            return Some(slot);
+        }
+
+        let slot = PlayerSlot::iter().find(|slot| {
+            matches!(self.device(*slot), PlayerDevice::Keyboard(_))
+        })?;
+
+        self.0.insert(slot, device);
+        Some(slot)
+    }
+
+    fn leave(&mut self, device: PlayerDevice) -> Option<PlayerSlot> {
+        let slot = self.device_slot(device)?;
+        let layout = KeyboardLayout::iter()
+            .nth(slot as usize)
+            .unwrap_or(KeyboardLayout::Wasd);
+
+        self.0.insert(slot, PlayerDevice::Keyboard(layout));
+        Some(slot)
+    }
 }
 
 fn move_crabs_based_on_user_input(
     mut commands: Commands,
+    goals: Goals,
     crabs_query: Query<
-        (Entity, &Side, &ActionState<CrabAction>),
-        (With<Crab>, With<Motion>),
+        (Entity, &Parent, &MaxSpeed, &ActionState<CrabAction>),
+        (With<Crab>, With<Motion>, Without<PointerControlled>),
     >,
 ) {
-    use CrabAction::*;
-    use Side::*;
-
-    for (entity, side, action_state) in &crabs_query {
-        let (move_crab_left, move_crab_right) = match *side {
-            Bottom => (MoveLeft, MoveRight),
-            Right => (MoveDown, MoveUp),
-            Top => (MoveRight, MoveLeft),
-            Left => (MoveUp, MoveDown),
+    for (entity, parent, max_speed, action_state) in &crabs_query {
+        let Ok(goal) = goals.get(parent.get()) else {
+            continue;
         };
+        let mut entity = commands.entity(entity);
+
+        match crab_drive(action_state, goal.right(), max_speed) {
+            (Some(force), _) => {
+                entity.remove::<TargetSpeed>().insert(force);
+            },
+            (None, Some(target_speed)) => {
+                entity.remove::<Force>().insert(target_speed);
+            },
+            (None, None) => {
+                entity.remove::<(Force, TargetSpeed)>();
+            },
+        }
+    }
+}
 
-        if action_state.pressed(&move_crab_left) {
-            commands.entity(entity).insert(Force::Negative);
-        } else if action_state.pressed(&move_crab_right) {
-            commands.entity(entity).insert(Force::Positive);
+/// The [`Force`] or [`TargetSpeed`] a [`Player`]'s inputs drive their crab
+/// with, along the x axis of a goal facing `goal_right`.
+pub fn crab_drive(
+    action_state: &ActionState<CrabAction>,
+    goal_right: Vec3,
+    max_speed: &MaxSpeed,
+) -> (Option<Force>, Option<TargetSpeed>) {
+    use CrabAction::*;
+
+    // Project inputs onto the goal's x axis as seen from above, where
+    // "up" on the stick points away from the camera.
+    let lateral_axis = Vec2::new(goal_right.x, -goal_right.z);
+
+    // Analog sticks pick a speed proportional to how far they're pushed.
+    let stick = action_state.clamped_axis_pair(&Move);
+
+    if stick != Vec2::ZERO {
+        let target_speed = stick.dot(lateral_axis) * max_speed.0;
+
+        return (None, Some(TargetSpeed(target_speed)));
+    }
+
+    // Digital inputs apply full force in one direction.
+    let pressed = |action| {
+        if action_state.pressed(&action) {
+            1.0
         } else {
-            commands.entity(entity).remove::<Force>();
+            0.0
         }
+    };
+    let direction = Vec2::new(
+        pressed(MoveRight) - pressed(MoveLeft),
+        pressed(MoveUp) - pressed(MoveDown),
+    );
+    let lateral_direction = direction.dot(lateral_axis);
+
+    if lateral_direction < -0.5 {
+        (Some(Force::Negative), None)
+    } else if lateral_direction > 0.5 {
+        (Some(Force::Positive), None)
+    } else {
+        (None, None)
     }
 }
@@ -0,0 +1,111 @@
+use bevy::{prelude::*, window::PrimaryWindow};
+
+use crate::{
+    components::{Acceleration, MaxSpeed, Motion, TargetSpeed},
+    spawners::LEVEL_CENTER_POINT,
+    system_params::Goals,
+    system_sets::ActiveDuringGameplaySet,
+};
+
+use super::{Crab, CrabCollider, IDEAL_HIT_AREA_PERCENTAGE};
+
+pub(super) struct PointerPlugin;
+
+impl Plugin for PointerPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<PointerTarget>().add_systems(
+            Update,
+            (
+                project_pointer_onto_beach,
+                move_pointer_controlled_crabs_toward_target,
+            )
+                .chain()
+                .in_set(ActiveDuringGameplaySet),
+        );
+    }
+}
+
+/// Makes a [`Player`](super::Player) [`Crab`] entity follow the mouse cursor
+/// or touch instead of input actions.
+#[derive(Clone, Component, Debug)]
+#[require(Crab)]
+pub struct PointerControlled;
+
+/// Where the cursor or touch is pointing on the beach, if anywhere.
+#[derive(Debug, Default, Resource)]
+pub struct PointerTarget(pub Option<Vec3>);
+
+fn project_pointer_onto_beach(
+    mut pointer_target: ResMut<PointerTarget>,
+    touches: Res<Touches>,
+    windows_query: Query<&Window, With<PrimaryWindow>>,
+    cameras_query: Query<(&Camera, &GlobalTransform), With<Camera3d>>,
+) {
+    // Touches take priority, since some platforms also move the cursor.
+    let viewport_position = touches.first_pressed_position().or_else(|| {
+        windows_query
+            .get_single()
+            .ok()
+            .and_then(|window| window.cursor_position())
+    });
+    let (Some(viewport_position), Ok((camera, camera_transform))) =
+        (viewport_position, cameras_query.get_single())
+    else {
+        return;
+    };
+    let Ok(ray) = camera.viewport_to_world(camera_transform, viewport_position)
+    else {
+        return;
+    };
+
+    pointer_target.0 = ray
+        .intersect_plane(LEVEL_CENTER_POINT, InfinitePlane3d::new(Vec3::Y))
+        .map(|distance| ray.get_point(distance));
+}
+
+fn move_pointer_controlled_crabs_toward_target(
+    mut commands: Commands,
+    goals: Goals,
+    pointer_target: Res<PointerTarget>,
+    crabs_query: Query<
+        (
+            Entity,
+            &Parent,
+            &Transform,
+            &Acceleration,
+            &MaxSpeed,
+            &CrabCollider,
+        ),
+        (With<PointerControlled>, With<Motion>),
+    >,
+) {
+    let Some(target) = pointer_target.0 else {
+        return;
+    };
+
+    for (entity, parent, transform, acceleration, max_speed, collider) in
+        &crabs_query
+    {
+        let Ok(goal) = goals.get(parent.get()) else {
+            continue;
+        };
+        let target_x =
+            goal.map_to_local_x(&GlobalTransform::from_translation(target));
+        let delta_x = target_x - transform.translation.x;
+
+        // Pick the fastest speed that can still stop on the target, so the
+        // crab eases in rather than overshooting.
+        let target_speed = if delta_x.abs()
+            < 0.5 * collider.width * (1.0 - IDEAL_HIT_AREA_PERCENTAGE)
+        {
+            0.0
+        } else {
+            (2.0 * acceleration.0 * delta_x.abs())
+                .sqrt()
+                .min(max_speed.0)
+                .copysign(delta_x)
+        };
+
+        commands.entity(entity).insert(TargetSpeed(target_speed));
+    }
+}
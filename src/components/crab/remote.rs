@@ -0,0 +1,63 @@
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::{
+    components::{Force, Side, TargetSpeed},
+    system_sets::ActiveDuringGameplaySet,
+};
+
+use super::{AI, Crab, Target};
+
+pub(super) struct RemotePlugin;
+
+impl Plugin for RemotePlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<RemoteCommands>().add_systems(
+            Update,
+            follow_remote_commands.in_set(ActiveDuringGameplaySet),
+        );
+    }
+}
+
+/// Makes a [`Crab`] entity follow an external bot while one is connected for
+/// its [`Side`], and fall back to [`AI`] otherwise.
+#[derive(Clone, Component, Debug)]
+#[require(Crab)]
+pub struct RemoteControlled;
+
+/// The latest command from each connected bot, keyed by [`Side`].
+///
+/// `None` means the bot wants its crab to stop.
+#[derive(Debug, Default, Resource)]
+pub struct RemoteCommands(pub HashMap<Side, Option<Force>>);
+
+fn follow_remote_commands(
+    mut commands: Commands,
+    remote_commands: Res<RemoteCommands>,
+    crabs_query: Query<(Entity, &Side, Has<AI>), With<RemoteControlled>>,
+) {
+    for (entity, side, has_ai) in &crabs_query {
+        let Some(force) = remote_commands.0.get(side) else {
+            if !has_ai {
+                commands.entity(entity).insert(AI);
+                info!("Crab({side:?}): Bot disconnected, AI took over");
+            }
+            continue;
+        };
+
+        if has_ai {
+            commands
+                .entity(entity)
+                .remove::<(AI, Target, TargetSpeed)>();
+            info!("Crab({side:?}): Bot took over");
+        }
+
+        match force {
+            Some(force) => {
+                commands.entity(entity).insert(*force);
+            },
+            None => {
+                commands.entity(entity).remove::<Force>();
+            },
+        }
+    }
+}
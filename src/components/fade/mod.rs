@@ -61,11 +61,13 @@ pub enum FadeEffect {
     ScaleAxisMask(Vec3),
 }
 
+/// Tracks how far along an entity is in its fade.
 #[derive(Clone, Component, Debug, Default, PartialEq)]
-struct FadeTimer(Timer);
+pub struct FadeTimer(pub Timer);
 
+/// What an entity is fading between.
 #[derive(Clone, Component, Copy, Debug, PartialEq)]
-enum FadeTransition {
+pub enum FadeTransition {
     Opacity(f32, f32, AlphaMode),
     Scale(Vec3, Vec3),
 }
@@ -1,7 +1,7 @@
 use bevy::prelude::*;
 use strum::IntoEnumIterator;
 
-use crate::states::GameState;
+use crate::states::{GameState, MenuScreen};
 
 use super::{Fade, FadeEffect, StartFading};
 
@@ -10,25 +10,43 @@ pub(super) struct ForStatesPlugin;
 impl Plugin for ForStatesPlugin {
     fn build(&self, app: &mut App) {
         for state in GameState::iter() {
-            app.add_systems(
-                OnEnter(state),
-                despawn_invalid_entities_for_state::<GameState>,
-            );
+            app.add_systems(OnEnter(state), despawn_invalid_entities);
+        }
+
+        for state in MenuScreen::iter() {
+            app.add_systems(OnEnter(state), despawn_invalid_entities);
         }
     }
 }
 
 /// Tags an entity to only exist in its associated game states.
+///
+/// Entities are also despawned if the state itself stops existing, like a
+/// sub-state after leaving its source state.
 #[derive(Clone, Component, Debug)]
 pub struct ForStates<S: States>(pub Vec<S>);
 
-fn despawn_invalid_entities_for_state<S: States>(
+/// Checks both kinds of states in one pass, so entities tagged for a game
+/// state and a menu screen are only despawned once.
+fn despawn_invalid_entities(
     mut commands: Commands,
-    game_state: Res<State<S>>,
-    query: Query<(Entity, &ForStates<S>, Has<FadeEffect>)>,
+    game_state: Option<Res<State<GameState>>>,
+    menu_screen: Option<Res<State<MenuScreen>>>,
+    query: Query<
+        (
+            Entity,
+            Option<&ForStates<GameState>>,
+            Option<&ForStates<MenuScreen>>,
+            Has<FadeEffect>,
+        ),
+        Or<(With<ForStates<GameState>>, With<ForStates<MenuScreen>>)>,
+    >,
 ) {
-    for (entity, for_states, has_fade_effect) in &query {
-        if !for_states.0.contains(game_state.get()) {
+    for (entity, game_states, menu_screens, has_fade_effect) in &query {
+        let is_valid = is_valid_for_states(game_states, game_state.as_deref())
+            && is_valid_for_states(menu_screens, menu_screen.as_deref());
+
+        if !is_valid {
             if has_fade_effect {
                 commands.trigger(StartFading(Fade::Out, entity));
             } else {
@@ -37,3 +55,13 @@ fn despawn_invalid_entities_for_state<S: States>(
         }
     }
 }
+
+/// Entities without the tag are valid in every state.
+fn is_valid_for_states<S: States>(
+    for_states: Option<&ForStates<S>>,
+    state: Option<&State<S>>,
+) -> bool {
+    for_states.is_none_or(|for_states| {
+        state.is_some_and(|state| for_states.0.contains(state.get()))
+    })
+}
@@ -2,7 +2,10 @@ use bevy::prelude::*;
 
 use crate::system_sets::StopWhenPausedSet;
 
-use super::{Crab, CrabCollider, Force, Goal, Motion, Speed, StoppingDistance};
+use super::{
+    Crab, CrabCollider, Force, Goal, Motion, Speed, StoppingDistance,
+    TargetSpeed,
+};
 
 pub(super) struct GoalMouthPlugin;
 
@@ -22,6 +25,13 @@ pub struct GoalMouth {
     pub width: f32,
 }
 
+impl GoalMouth {
+    /// How far a crab's center can move from the middle of the mouth.
+    pub fn crab_max_x(&self, crab_collider: &CrabCollider) -> f32 {
+        0.5 * (self.width - crab_collider.width)
+    }
+}
+
 fn restrict_crab_movement_to_goal_mouth(
     mut commands: Commands,
     mut crabs_query: Query<
@@ -49,14 +59,14 @@ fn restrict_crab_movement_to_goal_mouth(
         let Ok(goal_mouth) = goals_query.get(parent.get()) else {
             continue;
         };
-        let crab_max_x = 0.5 * (goal_mouth.width - crab_collider.width);
+        let crab_max_x = goal_mouth.crab_max_x(crab_collider);
 
         // Limit crab movement to the bounds of its own goal.
         if !(-crab_max_x..=crab_max_x).contains(&transform.translation.x) {
             transform.translation.x =
                 transform.translation.x.clamp(-crab_max_x, crab_max_x);
             speed.0 = 0.0;
-            commands.entity(entity).remove::<Force>();
+            commands.entity(entity).remove::<(Force, TargetSpeed)>();
         }
 
         // Also limit stopping distance to the bounds of the goal.
@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::{components::Side, system_sets::ActiveDuringGameplaySet};
+use crate::{components::Side, system_sets::ActiveDuringMatchSet};
 
 use super::{Goal, GoalEliminatedEvent, GoalScoredEvent};
 
@@ -10,22 +10,24 @@ impl Plugin for HitPointsPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             PostUpdate,
-            decrement_hp_and_eliminate_goals.in_set(ActiveDuringGameplaySet),
+            decrement_hp_and_eliminate_goals
+                .after(super::check_if_a_ball_has_scored_in_a_goal)
+                .in_set(ActiveDuringMatchSet),
         );
     }
 }
 
 /// How many balls a [`Goal`] can take before it's eliminated.
-#[derive(Component, Debug, Default)]
+#[derive(Clone, Component, Debug, Default)]
 #[require(Goal)]
 pub struct HitPoints(pub u8);
 
-fn decrement_hp_and_eliminate_goals(
+pub(super) fn decrement_hp_and_eliminate_goals(
     mut goal_scored_events: EventReader<GoalScoredEvent>,
     mut goal_eliminated_events: EventWriter<GoalEliminatedEvent>,
     mut hp_query: Query<(&Side, &mut HitPoints), With<Goal>>,
 ) {
-    for GoalScoredEvent(goal_entity) in goal_scored_events.read() {
+    for GoalScoredEvent { goal_entity, .. } in goal_scored_events.read() {
         let Ok((side, mut hp)) = hp_query.get_mut(*goal_entity) else {
             continue;
         };
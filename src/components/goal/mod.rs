@@ -12,12 +12,12 @@ use crate::{
     components::{Fade, StartFading},
     spawners::SpawnPole,
     system_params::Goals,
-    system_sets::ActiveDuringGameplaySet,
+    system_sets::{ActiveDuringMatchSet, AuthoritativeSet},
 };
 
 use super::{
-    Ball, CircleCollider, Collider, Crab, CrabCollider, Force, Motion, Side,
-    Speed, StoppingDistance,
+    Ball, CircleCollider, Collider, Crab, CrabCollider, Force, LastTouchedBy,
+    Motion, Side, Speed, StoppingDistance, TargetSpeed,
 };
 
 pub(super) struct GoalPlugin;
@@ -29,8 +29,15 @@ impl Plugin for GoalPlugin {
             .add_event::<GoalEliminatedEvent>()
             .add_systems(
                 PostUpdate,
-                (check_if_a_ball_has_scored_in_a_goal, block_eliminated_goals)
-                    .in_set(ActiveDuringGameplaySet),
+                (
+                    check_if_a_ball_has_scored_in_a_goal
+                        .in_set(AuthoritativeSet),
+                    // Goals are scored, counted, and blocked in the same
+                    // frame, so a rollback can clear their events afterward.
+                    block_eliminated_goals
+                        .after(hit_points::decrement_hp_and_eliminate_goals),
+                )
+                    .in_set(ActiveDuringMatchSet),
             );
     }
 }
@@ -42,11 +49,16 @@ pub struct Goal;
 
 /// Signal when a [`Goal`] entity has been scored by a ball.
 #[derive(Clone, Debug, Event)]
-pub(self) struct GoalScoredEvent(pub Entity);
+pub struct GoalScoredEvent {
+    pub goal_entity: Entity,
+
+    /// The side of the crab that last deflected the ball, if any did.
+    pub scored_by: Option<Side>,
+}
 
 /// Signals that a [`Goal`] has been eliminated from the game.
 #[derive(Clone, Debug, Event)]
-pub(self) struct GoalEliminatedEvent(pub Entity);
+pub struct GoalEliminatedEvent(pub Entity);
 
 fn check_if_a_ball_has_scored_in_a_goal(
     mut commands: Commands,
@@ -57,7 +69,12 @@ fn check_if_a_ball_has_scored_in_a_goal(
         (With<Crab>, With<Motion>, With<Collider>),
     >,
     balls_query: Query<
-        (Entity, &GlobalTransform, &CircleCollider),
+        (
+            Entity,
+            &GlobalTransform,
+            &CircleCollider,
+            Option<&LastTouchedBy>,
+        ),
         (With<Ball>, With<Motion>, With<Collider>),
     >,
 ) {
@@ -68,12 +85,18 @@ fn check_if_a_ball_has_scored_in_a_goal(
             continue;
         };
 
-        for (ball_entity, global_transform, collider) in &balls_query {
+        for (ball_entity, global_transform, collider, last_touched_by) in
+            &balls_query
+        {
             let ball_distance = goal.distance_to(global_transform);
 
             if ball_distance <= collider.radius {
                 commands.trigger(StartFading(Fade::Out, ball_entity));
-                goal_scored_events.send(GoalScoredEvent(goal_entity));
+                goal_scored_events.send(GoalScoredEvent {
+                    goal_entity,
+                    scored_by: last_touched_by
+                        .map(|last_touched_by| last_touched_by.0),
+                });
                 info!("Goal({side:?}): Scored by Ball({ball_entity:?})");
             }
         }
@@ -1,7 +1,9 @@
 use bevy::prelude::*;
 
 use crate::{
-    states::GameState, system_sets::ActiveDuringGameplaySet, ui::WinningTeam,
+    states::{GameState, MenuScreen},
+    system_sets::ActiveDuringGameplaySet,
+    ui::WinningTeam,
 };
 
 use super::{Goal, GoalEliminatedEvent, HitPoints};
@@ -12,7 +14,9 @@ impl Plugin for TeamPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             PostUpdate,
-            check_for_winning_team.in_set(ActiveDuringGameplaySet),
+            check_for_winning_team
+                .after(super::hit_points::decrement_hp_and_eliminate_goals)
+                .in_set(ActiveDuringGameplaySet),
         );
     }
 }
@@ -26,6 +30,7 @@ fn check_for_winning_team(
     mut commands: Commands,
     mut goal_eliminated_events: EventReader<GoalEliminatedEvent>,
     mut next_game_state: ResMut<NextState<GameState>>,
+    mut next_menu_screen: ResMut<NextState<MenuScreen>>,
     teams_query: Query<(&Team, &HitPoints), With<Goal>>,
 ) {
     for GoalEliminatedEvent(_) in goal_eliminated_events.read() {
@@ -48,6 +53,7 @@ fn check_for_winning_team(
         if let Some(winning_team) = winning_team {
             commands.insert_resource(WinningTeam(winning_team));
             next_game_state.set(GameState::StartMenu);
+            next_menu_screen.set(MenuScreen::Results);
             info!("Game Over: Team {winning_team:?} won!");
             break;
         }
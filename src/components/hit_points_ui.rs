@@ -1,8 +1,15 @@
 use bevy::prelude::*;
+use bevy_ui_anchor::{AnchorUiNode, HorizontalAnchor, VerticalAnchor};
 
-use crate::system_sets::ActiveAfterLoadingSet;
+use crate::{
+    settings::Settings,
+    system_params::GameModes,
+    system_sets::{ActiveAfterLoadingSet, SideEffectsSet},
+};
 
-use super::{Goal, HitPoints};
+use super::{
+    AnchoredUiCamera, Goal, GoalScoredEvent, HitPoints, Side, TeamSymbol,
+};
 
 pub(super) struct HitPointsUiPlugin;
 
@@ -10,28 +17,239 @@ impl Plugin for HitPointsUiPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             Update,
-            update_hit_points_ui.chain().in_set(ActiveAfterLoadingSet),
+            (
+                keep_hit_points_ui_outside_goals,
+                start_hit_feedback,
+                update_hit_points_ui,
+                animate_hit_feedback,
+            )
+                .chain()
+                .in_set(ActiveAfterLoadingSet)
+                .in_set(SideEffectsSet),
         );
     }
 }
 
-/// Marks a [`Text`] entity to display the value of an associated [`HitPoints`]
-/// entity.
+const PIP_WIDTH: f32 = 10.0;
+const PIP_HEIGHT: f32 = 16.0;
+const LOST_PIP_ALPHA: f32 = 0.2;
+const ELIMINATED_ALPHA: f32 = 0.1;
+const FEEDBACK_SECS: f32 = 0.4;
+const SHAKE_DISTANCE: f32 = 4.0;
+
+/// Shows the [`HitPoints`] of an associated [`Goal`] entity as a row of pips
+/// in the color of the goal's competitor, after its [`TeamSymbol`].
 #[derive(Component, Debug)]
-#[require(Text)]
+#[require(Node)]
 pub struct HitPointsUi {
     pub goal_entity: Entity,
 }
 
+impl HitPointsUi {
+    /// How far outside its goal the UI is anchored, in world units.
+    pub const OFFSET: f32 = 0.25;
+
+    /// Gets where to anchor the UI so it sits outside a goal, and which way
+    /// to lay out its pips so they run along the goal, given the direction on
+    /// screen from the goal away from the beach, with y pointing down.
+    pub fn layout(
+        outward: Vec2,
+    ) -> (HorizontalAnchor, VerticalAnchor, FlexDirection) {
+        if outward.x.abs() > outward.y.abs() {
+            let horizontal = if outward.x > 0.0 {
+                HorizontalAnchor::Left
+            } else {
+                HorizontalAnchor::Right
+            };
+
+            (horizontal, VerticalAnchor::Mid, FlexDirection::Column)
+        } else {
+            let vertical = if outward.y > 0.0 {
+                VerticalAnchor::Top
+            } else {
+                VerticalAnchor::Bottom
+            };
+
+            (HorizontalAnchor::Mid, vertical, FlexDirection::Row)
+        }
+    }
+}
+
+/// A single hit point in a [`HitPointsUi`].
+#[derive(Component, Debug)]
+#[require(Node, BackgroundColor)]
+pub struct HitPointPip;
+
+/// Flashes and shakes a [`HitPointsUi`] after its goal is scored on.
+#[derive(Component, Debug)]
+pub struct HitFeedback(pub Timer);
+
+/// Lays out each [`HitPointsUi`] by where its goal is on screen, since the
+/// camera can look at the beach from any side.
+fn keep_hit_points_ui_outside_goals(
+    cameras_query: Query<(&Camera, &GlobalTransform), With<AnchoredUiCamera>>,
+    goals_query: Query<&GlobalTransform, With<Goal>>,
+    mut hp_ui_query: Query<(&HitPointsUi, &mut AnchorUiNode, &mut Node)>,
+) {
+    let Ok((camera, camera_transform)) = cameras_query.get_single() else {
+        return;
+    };
+
+    for (hp_ui, mut anchor_ui_node, mut node) in &mut hp_ui_query {
+        let Ok(goal_transform) = goals_query.get(hp_ui.goal_entity) else {
+            continue;
+        };
+        let goal = goal_transform.translation();
+        let outward = HitPointsUi::OFFSET * goal.normalize_or_zero();
+        let (Ok(from), Ok(to)) = (
+            camera.world_to_viewport(camera_transform, goal),
+            camera.world_to_viewport(camera_transform, goal + outward),
+        ) else {
+            continue;
+        };
+        let (anchorwidth, anchorheight, flex_direction) =
+            HitPointsUi::layout(to - from);
+
+        if anchor_ui_node.anchorwidth != anchorwidth
+            || anchor_ui_node.anchorheight != anchorheight
+        {
+            anchor_ui_node.anchorwidth = anchorwidth;
+            anchor_ui_node.anchorheight = anchorheight;
+        }
+
+        if node.flex_direction != flex_direction {
+            node.flex_direction = flex_direction;
+        }
+    }
+}
+
+fn start_hit_feedback(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut goal_scored_events: EventReader<GoalScoredEvent>,
+    hp_ui_query: Query<(Entity, &HitPointsUi)>,
+) {
+    // Reduced motion skips the shaking and flashing.
+    if settings.accessibility.reduced_motion {
+        goal_scored_events.clear();
+        return;
+    }
+
+    for GoalScoredEvent { goal_entity, .. } in goal_scored_events.read() {
+        for (entity, hp_ui) in &hp_ui_query {
+            if hp_ui.goal_entity == *goal_entity {
+                commands.entity(entity).insert(HitFeedback(
+                    Timer::from_seconds(FEEDBACK_SECS, TimerMode::Once),
+                ));
+            }
+        }
+    }
+}
+
 fn update_hit_points_ui(
-    hp_query: Query<&HitPoints, With<Goal>>,
-    mut hp_ui_query: Query<(&mut Text, &HitPointsUi)>,
+    mut commands: Commands,
+    settings: Res<Settings>,
+    game_modes: GameModes,
+    hp_query: Query<(&HitPoints, &Side), With<Goal>>,
+    hp_ui_query: Query<(Entity, &HitPointsUi, Option<&Children>)>,
+    mut pips_query: Query<&mut BackgroundColor, With<HitPointPip>>,
 ) {
-    for (mut text, source) in &mut hp_ui_query {
-        let Ok(hp) = hp_query.get(source.goal_entity) else {
+    for (entity, source, children) in &hp_ui_query {
+        let Ok((hp, side)) = hp_query.get(source.goal_entity) else {
             continue;
         };
+        let Some(competitor) = game_modes.in_play().competitors.get(side)
+        else {
+            continue;
+        };
+        let max_hp = competitor.hit_points.get().max(hp.0) as usize;
+        let color = settings.competitor_color(*side, competitor);
+        let pips: Vec<Entity> = children
+            .map(|children| {
+                children
+                    .iter()
+                    .copied()
+                    .filter(|child| pips_query.contains(*child))
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        // Match the number of pips to the mode's hit points.
+        if pips.len() != max_hp {
+            commands.entity(entity).despawn_descendants().with_children(
+                |parent| {
+                    parent.spawn((
+                        TeamSymbol(*side),
+                        Node {
+                            margin: UiRect::all(Val::Px(2.0)),
+                            ..default()
+                        },
+                    ));
+
+                    for _ in 0..max_hp {
+                        parent.spawn((
+                            HitPointPip,
+                            Node {
+                                width: Val::Px(PIP_WIDTH),
+                                height: Val::Px(PIP_HEIGHT),
+                                margin: UiRect::all(Val::Px(1.0)),
+                                border: UiRect::all(Val::Px(1.0)),
+                                ..default()
+                            },
+                            BorderColor(Color::WHITE),
+                        ));
+                    }
+                },
+            );
+            continue;
+        }
+
+        for (i, pip) in pips.into_iter().enumerate() {
+            let Ok(mut background_color) = pips_query.get_mut(pip) else {
+                continue;
+            };
+            let alpha = if hp.0 == 0 {
+                ELIMINATED_ALPHA
+            } else if i < hp.0 as usize {
+                1.0
+            } else {
+                LOST_PIP_ALPHA
+            };
+
+            background_color
+                .set_if_neq(BackgroundColor(color.with_alpha(alpha)));
+        }
+    }
+}
+
+fn animate_hit_feedback(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut hp_ui_query: Query<(Entity, &mut HitFeedback, &mut Node, &Children)>,
+    mut pips_query: Query<&mut BackgroundColor, With<HitPointPip>>,
+) {
+    for (entity, mut hit_feedback, mut node, children) in &mut hp_ui_query {
+        hit_feedback.0.tick(time.delta());
+
+        if hit_feedback.0.finished() {
+            node.margin = UiRect::default();
+            commands.entity(entity).remove::<HitFeedback>();
+            continue;
+        }
+
+        // Shake side to side, fading out over the feedback's duration.
+        let progress = hit_feedback.0.fraction();
+        let shake = (progress * 40.0).sin() * SHAKE_DISTANCE * (1.0 - progress);
+
+        node.margin = UiRect::left(Val::Px(shake));
 
-        text.0 = hp.0.to_string();
+        // Flash every pip white a few times.
+        if (progress * 8.0) as u32 % 2 == 0 {
+            for child in children {
+                if let Ok(mut background_color) = pips_query.get_mut(*child) {
+                    background_color.0 = Color::WHITE;
+                }
+            }
+        }
     }
 }
@@ -7,9 +7,11 @@ mod for_states;
 mod goal;
 mod hit_points_ui;
 mod motion;
+mod particle;
 mod pole;
 mod scrolling_texture;
 mod side;
+mod team_symbol;
 
 pub use ball::*;
 pub use camera::*;
@@ -20,9 +22,11 @@ pub use for_states::*;
 pub use goal::*;
 pub use hit_points_ui::*;
 pub use motion::*;
+pub use particle::*;
 pub use pole::*;
 pub use scrolling_texture::*;
 pub use side::*;
+pub use team_symbol::*;
 
 use bevy::prelude::*;
 
@@ -39,8 +43,10 @@ impl Plugin for ComponentsPlugin {
             GoalPlugin,
             HitPointsUiPlugin,
             MotionPlugin,
+            ParticlePlugin,
             PolePlugin,
             ScrollingTexturePlugin,
+            TeamSymbolPlugin,
         ));
     }
 }
@@ -11,7 +11,12 @@ impl Plugin for AccelerationPlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             Update,
-            (acceleration, deceleration, stopping_distance)
+            (
+                acceleration,
+                acceleration_toward_target_speed,
+                deceleration,
+                stopping_distance,
+            )
                 .chain()
                 .in_set(StopWhenPausedSet),
         );
@@ -41,6 +46,40 @@ pub enum Force {
     Negative,
 }
 
+/// A speed the entity accelerates toward, as an analog alternative to
+/// [`Force`].
+///
+/// Ignored while the entity also has a [`Force`].
+#[derive(Clone, Component, Copy, Debug, Default, PartialEq)]
+#[component(storage = "SparseSet")]
+pub struct TargetSpeed(pub f32);
+
+/// The speed an entity reaches after accelerating for `delta_secs` under a
+/// [`Force`] or toward a [`TargetSpeed`], or after decelerating with neither.
+pub fn next_speed(
+    speed: f32,
+    acceleration: &Acceleration,
+    max_speed: &MaxSpeed,
+    force: Option<Force>,
+    target_speed: Option<TargetSpeed>,
+    delta_secs: f32,
+) -> f32 {
+    let delta_speed = acceleration.0 * delta_secs;
+
+    match (force, target_speed) {
+        (Some(force), _) => {
+            accelerate_speed(speed, delta_speed, max_speed.0, force)
+        },
+        (None, Some(target_speed)) => accelerate_speed_toward(
+            speed,
+            delta_speed,
+            max_speed.0,
+            target_speed.0,
+        ),
+        (None, None) => decelerate_speed(speed, delta_speed),
+    }
+}
+
 fn acceleration(
     time: Res<Time>,
     mut query: Query<
@@ -50,15 +89,25 @@ fn acceleration(
 ) {
     for (mut speed, acceleration, max_speed, force) in &mut query {
         let delta_speed = acceleration.0 * time.delta_secs();
+        speed.0 = accelerate_speed(speed.0, delta_speed, max_speed.0, *force);
+    }
+}
 
-        speed.0 = speed
-            .0
-            .add(if *force == Force::Positive {
-                delta_speed
-            } else {
-                -delta_speed
-            })
-            .clamp(-max_speed.0, max_speed.0);
+fn acceleration_toward_target_speed(
+    time: Res<Time>,
+    mut query: Query<
+        (&mut Speed, &Acceleration, &MaxSpeed, &TargetSpeed),
+        (With<Motion>, Without<Force>),
+    >,
+) {
+    for (mut speed, acceleration, max_speed, target_speed) in &mut query {
+        let delta_speed = acceleration.0 * time.delta_secs();
+        speed.0 = accelerate_speed_toward(
+            speed.0,
+            delta_speed,
+            max_speed.0,
+            target_speed.0,
+        );
     }
 }
 
@@ -66,7 +115,7 @@ fn deceleration(
     time: Res<Time>,
     mut query: Query<
         (&mut Speed, &Acceleration),
-        (With<Motion>, Without<Force>),
+        (With<Motion>, Without<Force>, Without<TargetSpeed>),
     >,
 ) {
     for (mut speed, acceleration) in &mut query {
@@ -95,6 +144,32 @@ fn stopping_distance(
     }
 }
 
+fn accelerate_speed(
+    speed: f32,
+    delta_speed: f32,
+    max_speed: f32,
+    force: Force,
+) -> f32 {
+    speed
+        .add(if force == Force::Positive {
+            delta_speed
+        } else {
+            -delta_speed
+        })
+        .clamp(-max_speed, max_speed)
+}
+
+fn accelerate_speed_toward(
+    speed: f32,
+    delta_speed: f32,
+    max_speed: f32,
+    target_speed: f32,
+) -> f32 {
+    let target = target_speed.clamp(-max_speed, max_speed);
+
+    speed + (target - speed).clamp(-delta_speed, delta_speed)
+}
+
 fn decelerate_speed(speed: f32, delta_speed: f32) -> f32 {
     speed.abs().sub(delta_speed).max(0.).copysign(speed)
 }
@@ -17,5 +17,5 @@ impl Plugin for MotionPlugin {
 }
 
 /// Marks an entity as in-motion and moving.
-#[derive(Component, Default)]
+#[derive(Clone, Component, Default)]
 pub struct Motion;
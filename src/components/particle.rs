@@ -0,0 +1,336 @@
+use bevy::{prelude::*, utils::HashMap};
+use rand::prelude::*;
+
+use crate::{
+    assets::{CachedAssets, GameAssets, GameConfig},
+    net::{RollbackSession, is_resimulating},
+    settings::{GraphicsQuality, Settings},
+    spawners::{Beach, LEVEL_CENTER_POINT},
+    system_params::GameModes,
+    system_sets::{ActiveAfterLoadingSet, SideEffectsSet, StopWhenPausedSet},
+    ui::WinningTeam,
+};
+
+use super::{BallDeflectedEvent, Goal, GoalScoredEvent};
+
+pub(super) struct ParticlePlugin;
+
+impl Plugin for ParticlePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                (
+                    spawn_sand_puffs_and_splashes,
+                    spawn_confetti_for_winning_team
+                        .run_if(resource_exists_and_changed::<WinningTeam>),
+                )
+                    .in_set(ActiveAfterLoadingSet),
+                move_particles_and_despawn_expired_ones
+                    .in_set(StopWhenPausedSet),
+            )
+                .in_set(SideEffectsSet),
+        )
+        .init_resource::<ParticleMaterials>()
+        .add_observer(spawn_particles);
+    }
+}
+
+const SAND_COLOR: Color = Color::srgb(0.93, 0.84, 0.65);
+const FOAM_COLOR: Color = Color::srgb(0.9, 0.97, 1.0);
+const WATER_COLOR: Color = Color::srgb(0.45, 0.75, 0.95);
+
+/// A short-lived speck that flies off, falls and shrinks away.
+#[derive(Clone, Component, Debug)]
+pub struct Particle {
+    pub velocity: Vec3,
+    pub gravity: f32,
+
+    /// How quickly the particle slows down in the air.
+    pub drag: f32,
+
+    /// How quickly the particle turns, in radians per second.
+    pub spin: f32,
+    pub scale: Vec3,
+    pub lifetime: Timer,
+}
+
+/// One unlit material per particle color, shared by every burst.
+#[derive(Debug, Default, Resource)]
+struct ParticleMaterials(HashMap<[u8; 4], Handle<StandardMaterial>>);
+
+/// The kinds of particle bursts, each with its own look and motion.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum ParticleEffect {
+    /// Kicked up when a crab deflects a ball.
+    SandPuff,
+
+    /// Thrown up when a ball is scored.
+    Splash,
+
+    /// Rains down in the winning team's colors.
+    Confetti,
+
+    /// Bursts out of a pole as it fades in on an eliminated goal.
+    PoleBurst,
+}
+
+impl ParticleEffect {
+    /// How many particles a burst has and how long they last, in seconds.
+    fn count_and_lifetime(&self, game_config: &GameConfig) -> (u32, f32) {
+        match self {
+            Self::SandPuff => (
+                game_config.sand_puff_particle_count,
+                game_config.sand_puff_lifetime_in_secs,
+            ),
+            Self::Splash => (
+                game_config.splash_particle_count,
+                game_config.splash_lifetime_in_secs,
+            ),
+            Self::Confetti => (
+                game_config.confetti_particle_count,
+                game_config.confetti_lifetime_in_secs,
+            ),
+            Self::PoleBurst => (
+                game_config.pole_burst_particle_count,
+                game_config.pole_burst_lifetime_in_secs,
+            ),
+        }
+    }
+
+    /// A random starting velocity for one particle.
+    fn velocity(&self, rng: &mut impl Rng) -> Vec3 {
+        let angle = rng.random_range(0.0..std::f32::consts::TAU);
+        let (speed, rise) = match self {
+            Self::SandPuff => (rng.random_range(0.1..0.3), 0.2),
+            Self::Splash => (rng.random_range(0.1..0.25), 0.8),
+            Self::Confetti => (rng.random_range(0.0..0.1), 0.0),
+            Self::PoleBurst => (rng.random_range(0.2..0.5), 0.4),
+        };
+
+        Vec3::new(
+            angle.cos() * speed,
+            rise * rng.random::<f32>(),
+            angle.sin() * speed,
+        )
+    }
+
+    fn gravity(&self) -> f32 {
+        match self {
+            Self::SandPuff => 0.8,
+            Self::Splash => 3.0,
+            Self::Confetti => 0.15,
+            Self::PoleBurst => 1.5,
+        }
+    }
+
+    fn drag(&self) -> f32 {
+        match self {
+            Self::SandPuff => 4.0,
+            Self::Splash => 0.5,
+            Self::Confetti => 1.5,
+            Self::PoleBurst => 2.0,
+        }
+    }
+
+    /// The shape of a particle, relative to the configured particle size.
+    fn shape(&self) -> Vec3 {
+        match self {
+            Self::Confetti => Vec3::new(1.5, 0.2, 1.0),
+            _ => Vec3::ONE,
+        }
+    }
+}
+
+/// An event fired to spawn a burst of [`Particle`] entities.
+#[derive(Clone, Debug, Event)]
+pub struct SpawnParticles {
+    pub effect: ParticleEffect,
+    pub position: Vec3,
+
+    /// How far from `position`, along each axis, particles can start.
+    pub spread: Vec3,
+
+    /// Colors picked at random for each particle.
+    pub colors: Vec<Color>,
+}
+
+fn spawn_particles(
+    trigger: Trigger<SpawnParticles>,
+    mut commands: Commands,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    mut particle_materials: ResMut<ParticleMaterials>,
+    cached_assets: Res<CachedAssets>,
+    settings: Res<Settings>,
+    graphics_quality: Res<GraphicsQuality>,
+    game_modes: GameModes,
+    game_assets: Res<GameAssets>,
+    game_configs: Res<Assets<GameConfig>>,
+    rollback_session: Option<Res<RollbackSession>>,
+) {
+    // The burst was already seen the first time the tick played out.
+    if is_resimulating(rollback_session) {
+        return;
+    }
+
+    let SpawnParticles {
+        effect,
+        position,
+        spread,
+        colors,
+    } = trigger.event();
+    let game_config = game_configs.get(&game_assets.game_config).unwrap();
+    let (count, lifetime) = effect.count_and_lifetime(game_config);
+    let mut count =
+        (count as f32 * graphics_quality.0.particle_density()).ceil() as u32;
+
+    if settings.graphics.low_effects || game_modes.in_play().low_effects {
+        count = (count as f32 * game_config.low_effects_particle_fraction)
+            .ceil() as u32;
+    }
+
+    if count == 0 || colors.is_empty() {
+        return;
+    }
+
+    let color_materials: Vec<_> = colors
+        .iter()
+        .map(|color| {
+            particle_materials
+                .0
+                .entry(color.to_srgba().to_u8_array())
+                .or_insert_with(|| {
+                    materials.add(StandardMaterial {
+                        base_color: *color,
+                        unlit: true,
+                        ..default()
+                    })
+                })
+                .clone()
+        })
+        .collect();
+    let scale = effect.shape() * game_config.particle_size;
+    let mut rng = rand::rng();
+
+    for _ in 0..count {
+        let offset = Vec3::new(
+            rng.random_range(-1.0..=1.0),
+            rng.random_range(-1.0..=1.0),
+            rng.random_range(-1.0..=1.0),
+        ) * *spread;
+
+        commands.spawn((
+            Particle {
+                velocity: effect.velocity(&mut rng),
+                gravity: effect.gravity(),
+                drag: effect.drag(),
+                spin: rng.random_range(-8.0..8.0),
+                scale,
+                lifetime: Timer::from_seconds(
+                    lifetime * rng.random_range(0.7..=1.0),
+                    TimerMode::Once,
+                ),
+            },
+            Mesh3d(cached_assets.particle_mesh.clone()),
+            MeshMaterial3d(color_materials.choose(&mut rng).unwrap().clone()),
+            Transform::from_translation(*position + offset).with_scale(scale),
+        ));
+    }
+}
+
+fn spawn_sand_puffs_and_splashes(
+    mut commands: Commands,
+    mut ball_deflected_events: EventReader<BallDeflectedEvent>,
+    mut goal_scored_events: EventReader<GoalScoredEvent>,
+    beach: Res<Beach>,
+    balls_query: Query<&GlobalTransform>,
+    goals_query: Query<&GlobalTransform, With<Goal>>,
+) {
+    for BallDeflectedEvent { ball_entity, .. } in ball_deflected_events.read() {
+        let Ok(global_transform) = balls_query.get(*ball_entity) else {
+            continue;
+        };
+
+        commands.trigger(SpawnParticles {
+            effect: ParticleEffect::SandPuff,
+            position: global_transform.translation().with_y(0.0),
+            spread: Vec3::splat(0.01),
+            colors: vec![SAND_COLOR],
+        });
+    }
+
+    for GoalScoredEvent { goal_entity, .. } in goal_scored_events.read() {
+        let Ok(global_transform) = goals_query.get(*goal_entity) else {
+            continue;
+        };
+
+        // Along the water's edge behind the goal.
+        commands.trigger(SpawnParticles {
+            effect: ParticleEffect::Splash,
+            position: global_transform.translation(),
+            spread: global_transform.right().abs() * 0.4 * beach.width,
+            colors: vec![FOAM_COLOR, WATER_COLOR],
+        });
+    }
+}
+
+fn spawn_confetti_for_winning_team(
+    mut commands: Commands,
+    winning_team: Res<WinningTeam>,
+    game_modes: GameModes,
+    settings: Res<Settings>,
+    beach: Res<Beach>,
+) {
+    let colors: Vec<Color> = game_modes
+        .current()
+        .competitors
+        .iter()
+        .filter(|(_, competitor)| competitor.team.get() == winning_team.0)
+        .map(|(side, competitor)| settings.competitor_color(*side, competitor))
+        .collect();
+
+    // Nobody celebrates a draw.
+    if colors.is_empty() {
+        return;
+    }
+
+    commands.trigger(SpawnParticles {
+        effect: ParticleEffect::Confetti,
+        position: LEVEL_CENTER_POINT.with_y(0.6),
+        spread: Vec3::new(0.5 * beach.width, 0.1, 0.5 * beach.width),
+        colors,
+    });
+}
+
+fn move_particles_and_despawn_expired_ones(
+    mut commands: Commands,
+    time: Res<Time>,
+    mut particles_query: Query<(Entity, &mut Particle, &mut Transform)>,
+) {
+    let delta_secs = time.delta_secs();
+
+    for (entity, mut particle, mut transform) in &mut particles_query {
+        particle.lifetime.tick(time.delta());
+
+        if particle.lifetime.finished() {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        let drag = 1.0 / (1.0 + particle.drag * delta_secs);
+
+        particle.velocity *= drag;
+        particle.velocity.y -= particle.gravity * delta_secs;
+        transform.translation += particle.velocity * delta_secs;
+
+        // Settle on the sand instead of sinking through it.
+        if transform.translation.y < 0.0 {
+            transform.translation.y = 0.0;
+            particle.velocity = Vec3::ZERO;
+        }
+
+        transform.rotate_local_x(particle.spin * delta_secs);
+        transform.scale =
+            particle.scale * particle.lifetime.fraction_remaining();
+    }
+}
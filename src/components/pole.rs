@@ -3,7 +3,8 @@ use bevy::prelude::*;
 use crate::{system_params::Goals, system_sets::StopWhenPausedSet};
 
 use super::{
-    Ball, CircleCollider, Collider, DepthCollider, Direction, Motion, Side,
+    Ball, BallBouncedEvent, CircleCollider, Collider, DepthCollider, Direction,
+    Motion, Obstacle, Side,
 };
 
 pub(super) struct PolePlugin;
@@ -18,11 +19,12 @@ impl Plugin for PolePlugin {
 }
 
 /// Makes an entity a pole that deflects all balls away from a side.
-#[derive(Component, Debug)]
+#[derive(Clone, Component, Debug)]
 pub struct Pole;
 
 fn pole_and_ball_collisions(
     mut commands: Commands,
+    mut ball_bounced_events: EventWriter<BallBouncedEvent>,
     goals: Goals,
     poles_query: Query<
         (&Parent, &Side, &DepthCollider),
@@ -53,6 +55,10 @@ fn pole_and_ball_collisions(
             commands
                 .entity(ball_entity)
                 .insert(Direction::reflect(direction, -goal.forward()));
+            ball_bounced_events.send(BallBouncedEvent {
+                ball_entity,
+                obstacle: Obstacle::Pole,
+            });
 
             info!("Pole({side:?}): Deflected Ball({ball_entity:?})");
             break;
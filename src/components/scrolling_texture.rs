@@ -1,6 +1,6 @@
 use bevy::prelude::*;
 
-use crate::system_sets::ActiveAfterLoadingSet;
+use crate::{settings::GraphicsQuality, system_sets::ActiveAfterLoadingSet};
 
 pub(super) struct ScrollingTexturePlugin;
 
@@ -8,7 +8,9 @@ impl Plugin for ScrollingTexturePlugin {
     fn build(&self, app: &mut App) {
         app.add_systems(
             Update,
-            scrolling_texture_effect.in_set(ActiveAfterLoadingSet),
+            scrolling_texture_effect
+                .in_set(ActiveAfterLoadingSet)
+                .run_if(textures_scroll),
         );
     }
 }
@@ -20,6 +22,11 @@ pub struct ScrollingTexture {
     pub velocity: Vec2,
 }
 
+/// Run condition that's true when the graphics quality has textures scroll.
+fn textures_scroll(graphics_quality: Res<GraphicsQuality>) -> bool {
+    graphics_quality.0.scrolls_ocean()
+}
+
 fn scrolling_texture_effect(
     time: Res<Time>,
     query: Query<(&ScrollingTexture, &MeshMaterial3d<StandardMaterial>)>,
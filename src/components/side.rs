@@ -1,10 +1,19 @@
 use bevy::prelude::*;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use strum::EnumIter;
 
 /// Assigns an entity to a given side of the beach.
 #[derive(
-    Clone, Component, Copy, Debug, Deserialize, EnumIter, Eq, Hash, PartialEq,
+    Clone,
+    Component,
+    Copy,
+    Debug,
+    Deserialize,
+    EnumIter,
+    Eq,
+    Hash,
+    PartialEq,
+    Serialize,
 )]
 pub enum Side {
     Bottom = 0,
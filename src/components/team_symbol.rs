@@ -0,0 +1,116 @@
+use bevy::prelude::*;
+
+use crate::{
+    settings::Settings, system_params::GameModes,
+    system_sets::ActiveAfterLoadingSet,
+};
+
+use super::Side;
+
+pub(super) struct TeamSymbolPlugin;
+
+impl Plugin for TeamSymbolPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            style_team_symbols.in_set(ActiveAfterLoadingSet),
+        );
+    }
+}
+
+const SYMBOL_SIZE: f32 = 14.0;
+const HOLLOW_BORDER: f32 = 3.0;
+
+/// A shape that tells teams apart without relying on color.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Shape {
+    Circle,
+    Square,
+    Ring,
+    Frame,
+    Bar,
+    Pillar,
+}
+
+const SHAPES: [Shape; 6] = [
+    Shape::Circle,
+    Shape::Square,
+    Shape::Ring,
+    Shape::Frame,
+    Shape::Bar,
+    Shape::Pillar,
+];
+
+/// Shows the shape of a side's team, in its color, while team symbols are
+/// turned on in the accessibility settings.
+#[derive(Component, Debug)]
+#[require(Node, BackgroundColor, BorderColor, BorderRadius)]
+pub struct TeamSymbol(pub Side);
+
+fn style_team_symbols(
+    settings: Res<Settings>,
+    game_modes: GameModes,
+    mut symbols_query: Query<(
+        &TeamSymbol,
+        &mut Node,
+        &mut BackgroundColor,
+        &mut BorderColor,
+        &mut BorderRadius,
+    )>,
+) {
+    for (
+        symbol,
+        mut node,
+        mut background_color,
+        mut border_color,
+        mut border_radius,
+    ) in &mut symbols_query
+    {
+        let competitor = game_modes.in_play().competitors.get(&symbol.0);
+        let Some(competitor) =
+            competitor.filter(|_| settings.accessibility.team_symbols)
+        else {
+            if node.display != Display::None {
+                node.display = Display::None;
+            }
+            continue;
+        };
+        let color = settings.competitor_color(symbol.0, competitor);
+        let shape = SHAPES[(competitor.team.get() - 1) % SHAPES.len()];
+        let (width, height) = match shape {
+            Shape::Bar => (SYMBOL_SIZE, SYMBOL_SIZE / 3.0),
+            Shape::Pillar => (SYMBOL_SIZE / 3.0, SYMBOL_SIZE),
+            _ => (SYMBOL_SIZE, SYMBOL_SIZE),
+        };
+        let is_hollow = matches!(shape, Shape::Ring | Shape::Frame);
+        let is_round = matches!(shape, Shape::Circle | Shape::Ring);
+        let styled_node = Node {
+            display: Display::Flex,
+            width: Val::Px(width),
+            height: Val::Px(height),
+            border: UiRect::all(Val::Px(if is_hollow {
+                HOLLOW_BORDER
+            } else {
+                1.0
+            })),
+            ..node.clone()
+        };
+
+        node.set_if_neq(styled_node);
+
+        // Hollow shapes are drawn by their border alone.
+        if is_hollow {
+            background_color.set_if_neq(BackgroundColor(Color::NONE));
+            border_color.set_if_neq(BorderColor(color));
+        } else {
+            background_color.set_if_neq(BackgroundColor(color));
+            border_color.set_if_neq(BorderColor(Color::WHITE));
+        }
+
+        border_radius.set_if_neq(if is_round {
+            BorderRadius::MAX
+        } else {
+            BorderRadius::ZERO
+        });
+    }
+}
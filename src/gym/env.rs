@@ -0,0 +1,307 @@
+use std::time::Duration;
+
+use bevy::{
+    ecs::{event::EventCursor, system::SystemState},
+    prelude::*,
+    utils::HashMap,
+};
+use leafwing_input_manager::prelude::*;
+use rand::prelude::*;
+use serde::Serialize;
+use strum::IntoEnumIterator;
+
+use crate::{
+    components::{
+        Ball, BallDeflectedEvent, Crab, CrabAction, Goal, GoalScoredEvent,
+        Motion, Player, PointerControlled, Side, Team,
+    },
+    headless::build_headless_app,
+    spawners::SpawnerRng,
+    states::GameState,
+    system_params::GameModes,
+    ui::WinningTeam,
+};
+
+use super::{
+    Agent, AgentActions, CrabObservation, GymAction, GymPlugin, observe_crabs,
+};
+
+/// Simulated time that passes for each app update.
+pub const GYM_TIMESTEP: Duration = Duration::from_nanos(16_666_667);
+
+/// How many app updates happen for each environment step.
+pub const GYM_UPDATES_PER_STEP: u32 = 4;
+
+/// Reward for deflecting a ball away from your own goal.
+pub const DEFLECTION_REWARD: f32 = 0.1;
+
+/// Reward for a ball getting past an opposing team's crab.
+pub const GOAL_SCORED_REWARD: f32 = 1.0;
+
+/// Reward for a ball getting past your own crab.
+pub const GOAL_CONCEDED_REWARD: f32 = -1.0;
+
+/// The most app updates [`GymEnv::reset`] waits for crabs to fade in.
+pub const MAX_RESET_UPDATES: u32 = 600;
+
+/// The result of advancing a [`GymEnv`] by one step.
+#[derive(Clone, Debug, Serialize)]
+pub struct GymStep {
+    pub observations: Vec<CrabObservation>,
+    pub rewards: HashMap<Side, f32>,
+    pub done: bool,
+}
+
+/// A step-based reinforcement learning environment running a headless game.
+///
+/// Every [`Player`] crab in the chosen game mode is handed over to an
+/// [`Agent`], while any AI crabs keep their usual behavior. Simulated time is
+/// advanced manually, so it runs as fast as the machine allows.
+pub struct GymEnv {
+    app: App,
+    max_balls: usize,
+    deflected_cursor: EventCursor<BallDeflectedEvent>,
+    scored_cursor: EventCursor<GoalScoredEvent>,
+}
+
+impl GymEnv {
+    /// Creates a headless game and waits for all its assets to load.
+    pub fn new() -> Self {
+        let app = build_headless_app(GYM_TIMESTEP, |app| {
+            app.add_plugins(GymPlugin);
+        });
+
+        Self {
+            app,
+            max_balls: 0,
+            deflected_cursor: default(),
+            scored_cursor: default(),
+        }
+    }
+
+    /// Starts a new round of the named game mode.
+    ///
+    /// The `seed` makes ball spawning reproducible between episodes.
+    pub fn reset(&mut self, seed: u64, mode: &str) -> Vec<CrabObservation> {
+        let world = self.app.world_mut();
+        let mut system_state = SystemState::<GameModes>::new(world);
+        let mut game_modes = system_state.get_mut(world);
+
+        if !game_modes.select(mode) {
+            warn!("Gym: Unknown game mode {mode:?}");
+        }
+
+        self.max_balls = u8::from(game_modes.current().ball_count).into();
+        world.insert_resource(SpawnerRng(SmallRng::seed_from_u64(seed)));
+        world.insert_resource(AgentActions::default());
+        world.remove_resource::<WinningTeam>();
+
+        // Get rid of balls from the previous episode immediately.
+        let balls: Vec<_> = world
+            .query_filtered::<Entity, With<Ball>>()
+            .iter(world)
+            .collect();
+
+        for ball in balls {
+            world.entity_mut(ball).despawn_recursive();
+        }
+
+        // Going through the start menu spawns a fresh set of crabs.
+        if *world.resource::<State<GameState>>() != GameState::StartMenu {
+            self.set_state(GameState::StartMenu);
+        }
+
+        self.set_state(GameState::Playing);
+        self.hand_players_over_to_agents();
+
+        // Wait for every crab to finish fading in before handing back control.
+        let mut updates = 0;
+
+        while self.moving_crabs_count() < Side::iter().len() {
+            if updates == MAX_RESET_UPDATES {
+                warn!("Gym: Gave up waiting for crabs to fade in");
+                break;
+            }
+
+            self.app.update();
+            updates += 1;
+        }
+
+        self.deflected_cursor = self
+            .app
+            .world()
+            .resource::<Events<BallDeflectedEvent>>()
+            .get_cursor_current();
+        self.scored_cursor = self
+            .app
+            .world()
+            .resource::<Events<GoalScoredEvent>>()
+            .get_cursor_current();
+
+        observe_crabs(self.app.world_mut())
+    }
+
+    /// Applies one action per [`Agent`] crab, then advances the simulation.
+    ///
+    /// Sides that are missing from `actions` stop moving.
+    pub fn step(&mut self, actions: HashMap<Side, GymAction>) -> GymStep {
+        self.app.world_mut().resource_mut::<AgentActions>().0 = actions;
+
+        let mut rewards: HashMap<Side, f32> =
+            Side::iter().map(|side| (side, 0.0)).collect();
+
+        for _ in 0..GYM_UPDATES_PER_STEP {
+            self.app.update();
+            self.collect_rewards(&mut rewards);
+
+            if self.is_done() {
+                break;
+            }
+        }
+
+        let done = self.is_done();
+
+        GymStep {
+            observations: observe_crabs(self.app.world_mut()),
+            rewards,
+            done,
+        }
+    }
+
+    /// The most balls that can be in play at once for the current mode.
+    pub fn max_balls(&self) -> usize {
+        self.max_balls
+    }
+
+    fn set_state(&mut self, state: GameState) {
+        self.app
+            .world_mut()
+            .resource_mut::<NextState<GameState>>()
+            .set(state);
+        self.app.update();
+    }
+
+    fn hand_players_over_to_agents(&mut self) {
+        let world = self.app.world_mut();
+        let players: Vec<_> = world
+            .query_filtered::<Entity, With<Player>>()
+            .iter(world)
+            .collect();
+
+        for player in players {
+            world
+                .entity_mut(player)
+                .remove::<(
+                    Player,
+                    PointerControlled,
+                    ActionState<CrabAction>,
+                    InputMap<CrabAction>,
+                )>()
+                .insert(Agent);
+        }
+    }
+
+    fn moving_crabs_count(&mut self) -> usize {
+        let world = self.app.world_mut();
+
+        world
+            .query_filtered::<(), (With<Crab>, With<Motion>)>()
+            .iter(world)
+            .count()
+    }
+
+    fn is_done(&self) -> bool {
+        self.app.world().contains_resource::<WinningTeam>()
+    }
+
+    fn collect_rewards(&mut self, rewards: &mut HashMap<Side, f32>) {
+        let world = self.app.world_mut();
+        let mut system_state: SystemState<(
+            Res<Events<BallDeflectedEvent>>,
+            Res<Events<GoalScoredEvent>>,
+            Query<&Side, With<Crab>>,
+            Query<(&Side, &Team), With<Goal>>,
+        )> = SystemState::new(world);
+        let (deflected_events, scored_events, crabs_query, goals_query) =
+            system_state.get(world);
+
+        for event in self.deflected_cursor.read(&deflected_events) {
+            if let Ok(side) = crabs_query.get(event.crab_entity) {
+                *rewards.entry(*side).or_default() += DEFLECTION_REWARD;
+            }
+        }
+
+        for GoalScoredEvent { goal_entity, .. } in
+            self.scored_cursor.read(&scored_events)
+        {
+            let Ok((scored_side, scored_team)) = goals_query.get(*goal_entity)
+            else {
+                continue;
+            };
+
+            for (side, team) in &goals_query {
+                let reward = if side == scored_side {
+                    GOAL_CONCEDED_REWARD
+                } else if team.0 != scored_team.0 {
+                    GOAL_SCORED_REWARD
+                } else {
+                    continue;
+                };
+
+                *rewards.entry(*side).or_default() += reward;
+            }
+        }
+    }
+}
+
+impl Default for GymEnv {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn steps_observe_every_crab_and_reward_goals_and_deflections() {
+        let mut env = GymEnv::new();
+        let observations = env.reset(7, "Classic (Player)");
+
+        assert_eq!(observations.len(), Side::iter().len());
+        assert_eq!(env.max_balls(), 2);
+
+        let mut rewards: HashMap<Side, f32> = HashMap::default();
+        let mut has_seen_balls = false;
+
+        for _ in 0..1_000 {
+            // The agent never moves, so it concedes while the AI deflects.
+            let step =
+                env.step(HashMap::from([(Side::Bottom, GymAction::Stop)]));
+
+            assert_eq!(step.observations.len(), Side::iter().len());
+            assert_eq!(step.rewards.len(), Side::iter().len());
+            has_seen_balls |= step
+                .observations
+                .iter()
+                .any(|observation| !observation.balls.is_empty());
+
+            for (side, reward) in step.rewards {
+                *rewards.entry(side).or_default() += reward;
+            }
+
+            if step.done {
+                break;
+            }
+        }
+
+        assert!(has_seen_balls);
+        assert!(rewards[&Side::Bottom] < 0.0);
+        assert!(
+            rewards
+                .iter()
+                .any(|(side, reward)| *side != Side::Bottom && *reward > 0.0)
+        );
+    }
+}
@@ -0,0 +1,66 @@
+mod env;
+mod observation;
+mod server;
+
+pub use env::*;
+pub use observation::*;
+pub use server::*;
+
+use bevy::{prelude::*, utils::HashMap};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    components::{Crab, Force, Motion, Side},
+    system_sets::ActiveDuringGameplaySet,
+};
+
+pub(super) struct GymPlugin;
+
+impl Plugin for GymPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<AgentActions>().add_systems(
+            Update,
+            apply_agent_actions_to_crabs.in_set(ActiveDuringGameplaySet),
+        );
+    }
+}
+
+/// Marks a [`Crab`] entity as being driven by a training agent.
+#[derive(Component, Debug)]
+#[require(Crab)]
+pub struct Agent;
+
+/// An action an [`Agent`] can take during a single environment step.
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, Hash, PartialEq, Serialize,
+)]
+pub enum GymAction {
+    Left,
+    Right,
+    #[default]
+    Stop,
+}
+
+/// The latest actions for each [`Agent`] [`Crab`], keyed by [`Side`].
+#[derive(Debug, Default, Resource)]
+pub struct AgentActions(pub HashMap<Side, GymAction>);
+
+fn apply_agent_actions_to_crabs(
+    mut commands: Commands,
+    agent_actions: Res<AgentActions>,
+    crabs_query: Query<(Entity, &Side), (With<Agent>, With<Motion>)>,
+) {
+    for (entity, side) in &crabs_query {
+        match agent_actions.0.get(side).copied().unwrap_or_default() {
+            GymAction::Left => {
+                commands.entity(entity).insert(Force::Negative);
+            },
+            GymAction::Right => {
+                commands.entity(entity).insert(Force::Positive);
+            },
+            GymAction::Stop => {
+                commands.entity(entity).remove::<Force>();
+            },
+        }
+    }
+}
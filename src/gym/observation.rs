@@ -0,0 +1,115 @@
+use bevy::{ecs::system::SystemState, prelude::*};
+use serde::Serialize;
+
+use crate::{
+    components::{
+        Ball, Collider, Crab, Direction, Goal, HitPoints, Motion, Side, Speed,
+    },
+    system_params::Goals,
+};
+
+/// Number of values in a [`CrabObservation`] vector before the balls.
+pub const CRAB_OBSERVATION_LEN: usize = 3;
+
+/// Number of values each ball adds to a [`CrabObservation`] vector.
+pub const BALL_OBSERVATION_LEN: usize = 5;
+
+/// What a single [`Crab`] can observe about the round, relative to its goal.
+#[derive(Clone, Debug, Serialize)]
+pub struct CrabObservation {
+    pub side: Side,
+    pub crab_x: f32,
+    pub crab_speed: f32,
+    pub hit_points: u8,
+
+    /// Active balls, sorted from closest to farthest from the goal.
+    pub balls: Vec<BallObservation>,
+}
+
+/// A [`Ball`] as seen from a [`Goal`].
+#[derive(Clone, Debug, Serialize)]
+pub struct BallObservation {
+    /// The ball's x position in the goal's local coordinate space.
+    pub x: f32,
+
+    /// The perpendicular distance from the goal to the ball.
+    pub distance: f32,
+
+    /// How much the ball is moving along the goal's local x axis.
+    pub direction_x: f32,
+
+    /// How much the ball is moving toward the goal.
+    pub direction_to_goal: f32,
+}
+
+impl CrabObservation {
+    /// Flattens the observation into a fixed-length vector.
+    ///
+    /// Only the closest `max_balls` balls are included, and any missing balls
+    /// are zero-filled with their presence flag cleared.
+    pub fn to_vector(&self, max_balls: usize) -> Vec<f32> {
+        let mut vector = Vec::with_capacity(
+            CRAB_OBSERVATION_LEN + max_balls * BALL_OBSERVATION_LEN,
+        );
+
+        vector.extend([self.crab_x, self.crab_speed, self.hit_points as f32]);
+
+        for i in 0..max_balls {
+            match self.balls.get(i) {
+                Some(ball) => vector.extend([
+                    1.0,
+                    ball.x,
+                    ball.distance,
+                    ball.direction_x,
+                    ball.direction_to_goal,
+                ]),
+                None => vector.extend([0.0; BALL_OBSERVATION_LEN]),
+            }
+        }
+
+        vector
+    }
+}
+
+/// Collects a [`CrabObservation`] for every active [`Crab`] on the beach.
+pub fn observe_crabs(world: &mut World) -> Vec<CrabObservation> {
+    let mut system_state: SystemState<(
+        Goals,
+        Query<(&Parent, &Side, &Transform, &Speed), (With<Crab>, With<Motion>)>,
+        Query<&HitPoints, With<Goal>>,
+        Query<
+            (&GlobalTransform, &Direction),
+            (With<Ball>, With<Motion>, With<Collider>),
+        >,
+    )> = SystemState::new(world);
+    let (goals, crabs_query, hp_query, balls_query) = system_state.get(world);
+    let mut observations = Vec::new();
+
+    for (parent, side, transform, speed) in &crabs_query {
+        let Ok(goal) = goals.get(parent.get()) else {
+            continue;
+        };
+        let hit_points = hp_query.get(parent.get()).map_or(0, |hp| hp.0);
+        let mut balls: Vec<_> = balls_query
+            .iter()
+            .map(|(global_transform, direction)| BallObservation {
+                x: goal.map_to_local_x(global_transform),
+                distance: goal.distance_to(global_transform),
+                direction_x: direction.0.dot(goal.right()),
+                direction_to_goal: -direction.0.dot(goal.forward()),
+            })
+            .collect();
+
+        balls.sort_by(|a, b| a.distance.total_cmp(&b.distance));
+        observations.push(CrabObservation {
+            side: *side,
+            crab_x: transform.translation.x,
+            crab_speed: speed.0,
+            hit_points,
+            balls,
+        });
+    }
+
+    observations.sort_by_key(|observation| observation.side as usize);
+    observations
+}
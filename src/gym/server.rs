@@ -0,0 +1,118 @@
+use std::{
+    io::{BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+};
+
+use bevy::utils::HashMap;
+use serde::{Deserialize, Serialize};
+
+use crate::components::Side;
+
+use super::{CrabObservation, GymAction, GymEnv};
+
+/// A request sent to the gym server as a single line of JSON.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GymRequest {
+    Reset { seed: u64, mode: String },
+    Step { actions: HashMap<Side, GymAction> },
+}
+
+/// The gym server's reply to a [`GymRequest`], sent as a line of JSON.
+#[derive(Debug, Serialize)]
+pub struct GymResponse {
+    pub observations: Vec<CrabObservation>,
+    pub vectors: HashMap<Side, Vec<f32>>,
+    pub rewards: HashMap<Side, f32>,
+    pub done: bool,
+    pub error: Option<String>,
+}
+
+/// Runs a [`GymEnv`] that's driven by line-delimited JSON over TCP.
+///
+/// Clients are served one at a time, and each connection shares the same
+/// environment.
+pub fn serve(address: &str) {
+    let listener = match TcpListener::bind(address) {
+        Ok(listener) => listener,
+        Err(error) => {
+            eprintln!("Gym: Failed to bind {address}: {error}");
+            return;
+        },
+    };
+    let mut env = GymEnv::new();
+
+    println!("Gym: Listening on {address}");
+
+    for stream in listener.incoming() {
+        match stream {
+            Ok(stream) => {
+                if let Err(error) = handle_client(&mut env, stream) {
+                    eprintln!("Gym: Client disconnected: {error}");
+                }
+            },
+            Err(error) => eprintln!("Gym: Failed to accept client: {error}"),
+        }
+    }
+}
+
+fn handle_client(env: &mut GymEnv, stream: TcpStream) -> std::io::Result<()> {
+    let mut writer = stream.try_clone()?;
+    let reader = BufReader::new(stream);
+
+    for line in reader.lines() {
+        let line = line?;
+
+        if line.trim().is_empty() {
+            continue;
+        }
+
+        let response = match serde_json::from_str::<GymRequest>(&line) {
+            Ok(GymRequest::Reset { seed, mode }) => {
+                let observations = env.reset(seed, &mode);
+
+                make_response(env, observations, HashMap::default(), false)
+            },
+            Ok(GymRequest::Step { actions }) => {
+                let step = env.step(actions);
+
+                make_response(env, step.observations, step.rewards, step.done)
+            },
+            Err(error) => GymResponse {
+                observations: Vec::new(),
+                vectors: HashMap::default(),
+                rewards: HashMap::default(),
+                done: false,
+                error: Some(error.to_string()),
+            },
+        };
+
+        serde_json::to_writer(&mut writer, &response)?;
+        writer.write_all(b"\n")?;
+        writer.flush()?;
+    }
+
+    Ok(())
+}
+
+fn make_response(
+    env: &GymEnv,
+    observations: Vec<CrabObservation>,
+    rewards: HashMap<Side, f32>,
+    done: bool,
+) -> GymResponse {
+    let vectors = observations
+        .iter()
+        .map(|observation| {
+            (observation.side, observation.to_vector(env.max_balls()))
+        })
+        .collect();
+
+    GymResponse {
+        observations,
+        vectors,
+        rewards,
+        done,
+        error: None,
+    }
+}
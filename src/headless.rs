@@ -0,0 +1,67 @@
+use std::time::Duration;
+
+use bevy::{
+    app::PluginsState,
+    log::{Level, LogPlugin},
+    prelude::*,
+    render::{RenderPlugin, settings::WgpuSettings},
+    tasks::tick_global_task_pools_on_main_thread,
+    time::TimeUpdateStrategy,
+    window::ExitCondition,
+    winit::WinitPlugin,
+};
+
+use crate::{GamePlugin, settings::Settings, states::GameState};
+
+/// Builds a game without a window or renderer, where every update advances
+/// simulated time by a fixed `timestep`.
+///
+/// It plays with default settings, leaving the user's settings file alone.
+/// Extra plugins are added by `add_plugins` before the app is finished, and
+/// the app is returned once all of its assets have loaded.
+pub fn build_headless_app(
+    timestep: Duration,
+    add_plugins: impl FnOnce(&mut App),
+) -> App {
+    let mut app = App::new();
+
+    app.add_plugins(
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: None,
+                exit_condition: ExitCondition::DontExit,
+                close_when_requested: false,
+            })
+            .set(RenderPlugin {
+                render_creation: WgpuSettings {
+                    backends: None,
+                    ..default()
+                }
+                .into(),
+                ..default()
+            })
+            .set(LogPlugin {
+                level: Level::WARN,
+                ..default()
+            })
+            .disable::<WinitPlugin>(),
+    )
+    .insert_resource(Settings::default())
+    .add_plugins(GamePlugin)
+    .insert_resource(TimeUpdateStrategy::ManualDuration(timestep));
+
+    add_plugins(&mut app);
+
+    while app.plugins_state() == PluginsState::Adding {
+        tick_global_task_pools_on_main_thread();
+    }
+
+    app.finish();
+    app.cleanup();
+
+    while *app.world().resource::<State<GameState>>() == GameState::Loading {
+        app.update();
+    }
+
+    app
+}
@@ -0,0 +1,97 @@
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    fs,
+    path::{Path, PathBuf},
+};
+
+use serde_yaml::{Mapping, Value};
+
+/// The translations, as they're compiled into the game.
+const LOCALES_YAML: &str = include_str!("../locales/ui.yaml");
+
+/// What every translation key starts with.
+const KEY_PREFIX: &str = "ui";
+
+/// Finds translation keys that would be shown raw or in the fallback language,
+/// either because a locale doesn't translate them or because the source uses
+/// a key that isn't in the locales file at all.
+pub fn check_locales() -> Vec<String> {
+    let translations: Mapping = match serde_yaml::from_str(LOCALES_YAML) {
+        Ok(translations) => translations,
+        Err(error) => return vec![format!("locales/ui.yaml: {error}")],
+    };
+    let mut locales = rust_i18n::available_locales!();
+    let mut keys = BTreeSet::new();
+    let mut problems = Vec::new();
+
+    locales.sort();
+
+    for (key, values) in &translations {
+        let Some(key) = key.as_str().filter(|key| !key.starts_with('_')) else {
+            continue;
+        };
+
+        keys.insert(key.to_string());
+
+        for locale in &locales {
+            let is_translated = values
+                .get(*locale)
+                .and_then(Value::as_str)
+                .is_some_and(|value| !value.trim().is_empty());
+
+            if !is_translated {
+                problems.push(format!("{key}: Missing {locale} translation"));
+            }
+        }
+    }
+
+    let mut used_keys = BTreeMap::new();
+
+    find_used_keys(
+        Path::new(concat!(env!("CARGO_MANIFEST_DIR"), "/src")),
+        &mut used_keys,
+    );
+
+    for (key, path) in used_keys {
+        if !keys.contains(&key) {
+            problems.push(format!("{key}: Not in locales, used in {path:?}"));
+        }
+    }
+
+    problems
+}
+
+/// Collects the translation keys in the source files under `dir`, with the
+/// first file each one appears in.
+fn find_used_keys(dir: &Path, used_keys: &mut BTreeMap<String, PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    let pattern = format!("\"{KEY_PREFIX}.");
+
+    for path in entries.flatten().map(|entry| entry.path()) {
+        if path.is_dir() {
+            find_used_keys(&path, used_keys);
+            continue;
+        }
+
+        if path.extension().is_none_or(|extension| extension != "rs") {
+            continue;
+        }
+
+        let Ok(source) = fs::read_to_string(&path) else {
+            continue;
+        };
+
+        for (start, _) in source.match_indices(&pattern) {
+            let key: String = source[start + 1..]
+                .chars()
+                .take_while(|c| c.is_ascii_alphanumeric() || "_.".contains(*c))
+                .collect();
+
+            if !key.ends_with('.') {
+                used_keys.entry(key).or_insert_with(|| path.clone());
+            }
+        }
+    }
+}
@@ -1,72 +1,150 @@
 #![allow(clippy::too_many_arguments, clippy::type_complexity)]
 
 mod assets;
+mod bots;
 mod components;
+mod gym;
+mod headless;
+mod locales;
+mod net;
+mod settings;
+mod sound;
 mod spawners;
+mod spectator;
 mod states;
+mod stats;
 mod system_params;
 mod system_sets;
 mod ui;
 
 use bevy::{
-    core_pipeline::experimental::taa::TemporalAntiAliasPlugin,
-    pbr::DefaultOpaqueRendererMethod,
-    prelude::*,
-    window::{PresentMode, WindowResolution},
+    core_pipeline::experimental::taa::TemporalAntiAliasPlugin, prelude::*,
 };
 use rust_i18n::*;
 
 i18n!("locales", fallback = "en");
 
 fn main() {
-    App::new()
-        .insert_resource(DefaultOpaqueRendererMethod::deferred())
-        .add_plugins((
-            DefaultPlugins
-                .set(WindowPlugin {
-                    primary_window: Some(Window {
-                        title: t!("ui.window.title").to_string(),
-                        present_mode: PresentMode::AutoVsync,
-                        position: WindowPosition::Centered(
-                            MonitorSelection::Primary,
-                        ),
-                        resolution: WindowResolution::new(640.0, 640.0),
-                        resize_constraints: WindowResizeConstraints {
-                            min_height: 640.0,
-                            min_width: 640.0,
-                            ..default()
-                        },
-                        // resizable: false,
-                        // enabled_buttons: EnabledButtons {
-                        //     maximize: false,
-                        //     ..default()
-                        // },
-                        fit_canvas_to_parent: true,
-                        // #[cfg(not(debug_assertions))]
-                        // canvas: Some("#pico-td".into()),
-                        // window_theme: Some(WindowTheme::Dark),
-                        // #[cfg(not(target_os = "windows"))]
-                        // visible: false,
-                        ..default()
-                    }),
-                    ..default()
-                })
-                .set(AssetPlugin {
-                    ..Default::default()
-                }),
-            TemporalAntiAliasPlugin,
-            // ScreenSpaceAmbientOcclusionPlugin,
-            // ScreenSpaceReflectionsPlugin,
-        ))
-        .add_plugins((
+    // Run as a headless training environment instead of a windowed game.
+    if let Some(address) =
+        std::env::args().skip_while(|arg| arg != "--gym").nth(1)
+    {
+        gym::serve(&address);
+        return;
+    }
+
+    let args: Vec<String> = std::env::args().collect();
+
+    // Report untranslated text, then exit.
+    if args.iter().any(|arg| arg == "--check-locales") {
+        let problems = locales::check_locales();
+
+        for problem in &problems {
+            eprintln!("{problem}");
+        }
+
+        if !problems.is_empty() {
+            std::process::exit(1);
+        }
+
+        println!("All text is translated.");
+        return;
+    }
+
+    if let Some(position) =
+        args.iter().position(|arg| arg == "--rollback-harness")
+    {
+        let ticks = args
+            .get(position + 1)
+            .and_then(|ticks| ticks.parse().ok())
+            .unwrap_or(3_600);
+        let conditions = net::LinkConditions::from_args(&args);
+
+        if !net::run_rollback_harness(ticks, conditions) {
+            std::process::exit(1);
+        }
+
+        return;
+    }
+
+    // Open the window the way it was left last time.
+    let settings = settings::Settings::load();
+
+    rust_i18n::set_locale(settings.locale());
+
+    // Render at a quality preset, whatever the settings say.
+    let quality_override = args
+        .iter()
+        .skip_while(|arg| *arg != "--quality")
+        .nth(1)
+        .and_then(|name| {
+            let preset = settings::QualityPreset::from_name(name);
+
+            if preset.is_none() {
+                eprintln!("Unknown quality preset {name:?}, ignoring it.");
+            }
+
+            preset
+        });
+
+    let mut app = App::new();
+
+    if let Some(preset) = quality_override {
+        app.insert_resource(settings::QualityOverride(preset));
+    }
+
+    app.add_plugins((
+        DefaultPlugins
+            .set(WindowPlugin {
+                primary_window: Some(settings.primary_window()),
+                ..default()
+            })
+            .set(AssetPlugin {
+                ..Default::default()
+            }),
+        TemporalAntiAliasPlugin,
+        // ScreenSpaceAmbientOcclusionPlugin,
+        // ScreenSpaceReflectionsPlugin,
+    ))
+    .insert_resource(settings)
+    .add_plugins(GamePlugin)
+    .insert_resource(ClearColor(Color::srgba(0.7, 0.9, 1.0, 1.0)));
+
+    // Host, join, or roll back a game over the network.
+    if let Some(net_config) = net::NetConfig::from_args(&args) {
+        app.add_plugins(net::NetPlugin(net_config));
+    }
+
+    // Stream the game's state to overlays and dashboards.
+    if let Some(spectator_config) = spectator::SpectatorConfig::from_args(&args)
+    {
+        app.add_plugins(spectator::SpectatorPlugin(spectator_config));
+    }
+
+    // Let external bots drive remote crabs.
+    if let Some(bots_config) = bots::BotsConfig::from_args(&args) {
+        app.add_plugins(bots::BotsPlugin(bots_config));
+    }
+
+    app.run();
+}
+
+/// All of the game's logic, independent of how it's windowed or rendered.
+pub struct GamePlugin;
+
+impl Plugin for GamePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins((
             assets::AssetsPlugin,
             components::ComponentsPlugin,
+            settings::SettingsPlugin,
+            sound::SoundPlugin,
             spawners::SpawnersPlugin,
             states::StatesPlugin,
+            stats::StatsPlugin,
             system_params::SystemParamsPlugin,
             system_sets::SystemSetsPlugin,
             ui::UiPlugin,
-        ))
-        .insert_resource(ClearColor(Color::srgba(0.7, 0.9, 1.0, 1.0)))
-        .run();
+        ));
+    }
 }
@@ -0,0 +1,600 @@
+use std::{collections::VecDeque, net::SocketAddr, time::Duration};
+
+use bevy::{prelude::*, utils::HashMap};
+use leafwing_input_manager::prelude::*;
+
+use crate::{
+    assets::{CachedAssets, GameAssets, GameConfig},
+    components::{
+        AI, Acceleration, Ball, Crab, CrabAction, CrabCollider, Fade,
+        FadeDuration, FadeEffect, ForStates, Force, Goal, GoalMouth,
+        GoalScoredEvent, HitPoints, MaxSpeed, Player, PlayerSlot, PlayerSlots,
+        PointerControlled, Side, Speed, StartFading, TargetSpeed, crab_drive,
+        next_speed,
+    },
+    settings::Settings,
+    states::GameState,
+    system_params::{GameModes, Goals},
+    system_sets::{ActiveAfterLoadingSet, ActiveDuringGameplaySet},
+};
+
+use super::{InputFrame, NetLink, NetMessage, Snapshot, TIMEOUT_SECS};
+
+/// How far behind the newest [`Snapshot`] other entities are shown, so
+/// there's usually a later snapshot to interpolate toward.
+pub const INTERPOLATION_DELAY_SECS: f32 = 0.1;
+
+/// How far the predicted crab can drift from the host before it's corrected.
+pub const CORRECTION_THRESHOLD: f32 = 0.02;
+
+/// How often a client asks to join until the host replies.
+const HELLO_INTERVAL_SECS: f32 = 0.5;
+
+/// How many unacknowledged inputs are kept for reconciliation.
+const MAX_SENT_INPUTS: usize = 256;
+
+pub(super) struct ClientPlugin;
+
+impl Plugin for ClientPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(PreUpdate, receive_host_messages)
+            .add_systems(
+                Update,
+                (
+                    send_hello_until_welcomed,
+                    follow_host_game_state,
+                    turn_other_crabs_into_proxies,
+                )
+                    .in_set(ActiveAfterLoadingSet),
+            )
+            .add_systems(
+                Update,
+                (
+                    sync_hit_points_with_host,
+                    reconcile_predicted_crab,
+                    spawn_and_despawn_proxy_balls,
+                    interpolate_proxies,
+                )
+                    .chain()
+                    .in_set(ActiveDuringGameplaySet),
+            )
+            .add_systems(
+                PostUpdate,
+                send_player_inputs.in_set(ActiveDuringGameplaySet),
+            )
+            .add_systems(
+                Last,
+                (say_goodbye_to_host_on_exit, flush_client_link).chain(),
+            );
+    }
+}
+
+/// Mirrors a game hosted on another machine.
+///
+/// The client's own crab is simulated locally for responsiveness. When the
+/// host disagrees, it's rewound to the host's state and the inputs the host
+/// hasn't seen yet are replayed on top, while everything else follows the
+/// host's snapshots.
+#[derive(Resource)]
+pub struct NetClient {
+    link: NetLink,
+    host: SocketAddr,
+    slot: Option<PlayerSlot>,
+    last_heard_secs: f32,
+    hello_timer: Timer,
+    sequence: u32,
+    sent_inputs: VecDeque<SentInput>,
+    snapshots: VecDeque<(f32, Snapshot)>,
+    reconciled_tick: u32,
+    balls: HashMap<u64, Entity>,
+}
+
+impl NetClient {
+    pub fn new(link: NetLink, host: SocketAddr) -> Self {
+        Self {
+            link,
+            host,
+            slot: None,
+            last_heard_secs: 0.0,
+            hello_timer: Timer::from_seconds(
+                HELLO_INTERVAL_SECS,
+                TimerMode::Repeating,
+            ),
+            sequence: 0,
+            sent_inputs: VecDeque::new(),
+            snapshots: VecDeque::new(),
+            reconciled_tick: 0,
+            balls: HashMap::default(),
+        }
+    }
+
+    /// The player slot the host gave this client, once it's joined.
+    pub fn slot(&self) -> Option<PlayerSlot> {
+        self.slot
+    }
+
+    fn latest_snapshot(&self) -> Option<&Snapshot> {
+        self.snapshots.back().map(|(_, snapshot)| snapshot)
+    }
+}
+
+/// An input the client sent and already moved its own crab with.
+struct SentInput {
+    input: InputFrame,
+
+    /// How long the frame the input was sampled on lasted.
+    delta_secs: f32,
+}
+
+/// Marks an entity on a client whose state is copied from the host.
+#[derive(Component, Debug)]
+pub struct NetworkProxy;
+
+fn receive_host_messages(
+    mut net_client: ResMut<NetClient>,
+    time: Res<Time<Real>>,
+) {
+    let net_client = &mut *net_client;
+    let now = time.elapsed_secs();
+
+    for (from, message) in net_client.link.receive() {
+        if from != net_client.host {
+            continue;
+        }
+
+        net_client.last_heard_secs = now;
+
+        match message {
+            NetMessage::Welcome { slot } if net_client.slot != Some(slot) => {
+                net_client.slot = Some(slot);
+                info!("Net: Joined as Player {slot:?}");
+            },
+            NetMessage::Full => warn!("Net: Host has no free player slots"),
+            NetMessage::Snapshot(snapshot) => {
+                // Datagrams can arrive out of order, so drop stale ones.
+                let is_newer = net_client
+                    .latest_snapshot()
+                    .is_none_or(|latest| snapshot.tick > latest.tick);
+
+                if is_newer {
+                    net_client.snapshots.push_back((now, snapshot));
+                }
+            },
+            NetMessage::Goodbye => {
+                net_client.slot = None;
+                warn!("Net: Host closed the session");
+            },
+            _ => {},
+        }
+    }
+
+    // Keep enough history to interpolate through a few lost snapshots.
+    while net_client.snapshots.len() > 2
+        && net_client.snapshots[1].0 < now - 1.0
+    {
+        net_client.snapshots.pop_front();
+    }
+}
+
+fn send_hello_until_welcomed(
+    mut net_client: ResMut<NetClient>,
+    time: Res<Time<Real>>,
+) {
+    let net_client = &mut *net_client;
+
+    if net_client.slot.is_some()
+        && time.elapsed_secs() - net_client.last_heard_secs > TIMEOUT_SECS
+    {
+        net_client.slot = None;
+        warn!("Net: Lost connection to host");
+    }
+
+    if net_client.slot.is_none()
+        && net_client.hello_timer.tick(time.delta()).just_finished()
+    {
+        let host = net_client.host;
+
+        net_client.link.send(host, &NetMessage::Hello);
+    }
+}
+
+fn follow_host_game_state(
+    net_client: Res<NetClient>,
+    game_state: Res<State<GameState>>,
+    mut game_modes: GameModes,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    let Some(snapshot) = net_client.latest_snapshot() else {
+        return;
+    };
+    let host_game_state = snapshot.game_state;
+
+    if host_game_state == *game_state.get()
+        || host_game_state == GameState::Loading
+    {
+        return;
+    }
+
+    if *game_state.get() == GameState::StartMenu
+        && !game_modes.select(&snapshot.game_mode)
+    {
+        warn!("Net: Missing game mode {:?}", snapshot.game_mode);
+    }
+
+    next_game_state.set(host_game_state);
+}
+
+fn turn_other_crabs_into_proxies(
+    mut commands: Commands,
+    net_client: Res<NetClient>,
+    game_modes: GameModes,
+    player_slots: Res<PlayerSlots>,
+    settings: Res<Settings>,
+    game_assets: Res<GameAssets>,
+    game_configs: Res<Assets<GameConfig>>,
+    crabs_query: Query<(Entity, &Side), Added<Crab>>,
+) {
+    let game_config = game_configs.get(&game_assets.game_config).unwrap();
+    let own_side = net_client.slot().and_then(|own_slot| {
+        game_modes
+            .current()
+            .player_slots()
+            .into_iter()
+            .find_map(|(side, slot)| (slot == own_slot).then_some(side))
+    });
+
+    for (entity, side) in &crabs_query {
+        // The local player controls their crab with the first slot's device,
+        // whichever slot the host gave them.
+        if Some(*side) == own_side {
+            commands
+                .entity(entity)
+                .insert(settings.controls.crab_input_map(
+                    player_slots.device(PlayerSlot::One),
+                    true,
+                    game_config.gamepad_stick_deadzone,
+                ));
+            continue;
+        }
+
+        commands
+            .entity(entity)
+            .remove::<(
+                AI,
+                Player,
+                PointerControlled,
+                ActionState<CrabAction>,
+                InputMap<CrabAction>,
+                Force,
+                TargetSpeed,
+            )>()
+            .insert(NetworkProxy);
+    }
+}
+
+fn sync_hit_points_with_host(
+    net_client: Res<NetClient>,
+    mut goal_scored_events: EventWriter<GoalScoredEvent>,
+    goals_query: Query<(Entity, &Side, &HitPoints), With<Goal>>,
+) {
+    let Some(snapshot) = net_client.latest_snapshot() else {
+        return;
+    };
+
+    // Snapshots from outside a round still have the previous round's HP.
+    if snapshot.game_state != GameState::Playing {
+        return;
+    }
+
+    // Replay goals as local events, so eliminations and wins play out the
+    // same way they do on the host.
+    for (entity, side, hit_points) in &goals_query {
+        let Some(goal) = snapshot.goals.iter().find(|goal| goal.side == *side)
+        else {
+            continue;
+        };
+
+        // The goals that haven't been replayed yet are the host's latest.
+        let missed = hit_points.0.saturating_sub(goal.hit_points) as usize;
+
+        for index in 0..missed {
+            let scored_by = (goal.scored_by.len() + index)
+                .checked_sub(missed)
+                .and_then(|index| goal.scored_by[index]);
+
+            goal_scored_events.send(GoalScoredEvent {
+                goal_entity: entity,
+                scored_by,
+            });
+        }
+    }
+}
+
+fn reconcile_predicted_crab(
+    mut net_client: ResMut<NetClient>,
+    goals: Goals,
+    goal_mouths_query: Query<&GoalMouth>,
+    mut crabs_query: Query<
+        (
+            &Side,
+            &Parent,
+            &CrabCollider,
+            &Acceleration,
+            &MaxSpeed,
+            &mut Transform,
+            &mut Speed,
+        ),
+        (With<Player>, With<Crab>),
+    >,
+) {
+    let net_client = &mut *net_client;
+    let Some((_, snapshot)) = net_client.snapshots.back() else {
+        return;
+    };
+
+    if snapshot.tick == net_client.reconciled_tick {
+        return;
+    }
+
+    net_client.reconciled_tick = snapshot.tick;
+
+    let Ok((
+        side,
+        parent,
+        crab_collider,
+        acceleration,
+        max_speed,
+        mut transform,
+        mut speed,
+    )) = crabs_query.get_single_mut()
+    else {
+        return;
+    };
+    let Some(crab) = snapshot.crabs.iter().find(|crab| crab.side == *side)
+    else {
+        return;
+    };
+    let (Ok(goal), Ok(goal_mouth)) =
+        (goals.get(parent.get()), goal_mouths_query.get(parent.get()))
+    else {
+        return;
+    };
+
+    // The host's state already includes every input it acknowledged.
+    while net_client
+        .sent_inputs
+        .front()
+        .is_some_and(|sent_input| sent_input.input.sequence <= snapshot.ack)
+    {
+        net_client.sent_inputs.pop_front();
+    }
+
+    // Inputs that fell out of the history can't be replayed.
+    if net_client
+        .sent_inputs
+        .front()
+        .is_some_and(|sent_input| sent_input.input.sequence > snapshot.ack + 1)
+    {
+        return;
+    }
+
+    // Rewind to the host's state and replay the inputs it hasn't seen yet,
+    // the same way the crab moved when they were first sampled.
+    let crab_max_x = goal_mouth.crab_max_x(crab_collider);
+    let mut action_state = ActionState::<CrabAction>::default();
+    let mut replayed_x = crab.x;
+    let mut replayed_speed = crab.speed;
+
+    for sent_input in &net_client.sent_inputs {
+        sent_input.input.apply(&mut action_state);
+
+        let (force, target_speed) =
+            crab_drive(&action_state, goal.right(), max_speed);
+
+        replayed_speed = next_speed(
+            replayed_speed,
+            acceleration,
+            max_speed,
+            force,
+            target_speed,
+            sent_input.delta_secs,
+        );
+        replayed_x += replayed_speed * sent_input.delta_secs;
+
+        if !(-crab_max_x..=crab_max_x).contains(&replayed_x) {
+            replayed_x = replayed_x.clamp(-crab_max_x, crab_max_x);
+            replayed_speed = 0.0;
+        }
+    }
+
+    let error = replayed_x - transform.translation.x;
+
+    if error.abs() > CORRECTION_THRESHOLD {
+        transform.translation.x = replayed_x;
+        speed.0 = replayed_speed;
+
+        debug!("Net: Corrected predicted crab by {error}");
+    }
+}
+
+fn spawn_and_despawn_proxy_balls(
+    mut commands: Commands,
+    mut net_client: ResMut<NetClient>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    cached_assets: Res<CachedAssets>,
+    game_modes: GameModes,
+    game_assets: Res<GameAssets>,
+    game_configs: Res<Assets<GameConfig>>,
+    balls_query: Query<(), With<Ball>>,
+) {
+    let net_client = &mut *net_client;
+    let Some((_, snapshot)) = net_client.snapshots.back() else {
+        return;
+    };
+
+    // Forget balls that were despawned by leaving a round.
+    net_client
+        .balls
+        .retain(|_, entity| balls_query.contains(*entity));
+
+    // Fade out balls the host no longer has.
+    net_client.balls.retain(|id, entity| {
+        let exists = snapshot.balls.iter().any(|ball| ball.id == *id);
+
+        if !exists {
+            commands.trigger(StartFading(Fade::Out, *entity));
+        }
+
+        exists
+    });
+
+    let game_config = game_configs.get(&game_assets.game_config).unwrap();
+    let game_mode = game_modes.current();
+
+    for ball in &snapshot.balls {
+        if net_client.balls.contains_key(&ball.id) {
+            continue;
+        }
+
+        let ball_entity = commands
+            .spawn((
+                Ball,
+                NetworkProxy,
+                FadeEffect::Opacity,
+                FadeDuration(Duration::from_secs_f32(
+                    game_config.ball_fade_time_in_secs,
+                )),
+                ForStates(vec![GameState::Playing, GameState::Paused]),
+                Mesh3d(cached_assets.ball_mesh.clone()),
+                MeshMaterial3d(materials.add(Color::WHITE)),
+                Transform::from_matrix(Mat4::from_scale_rotation_translation(
+                    Vec3::splat(
+                        game_mode.ball_scale * game_config.ball_diameter,
+                    ),
+                    Quat::IDENTITY,
+                    ball.translation,
+                )),
+            ))
+            .id();
+
+        commands.trigger(StartFading(Fade::In, ball_entity));
+        net_client.balls.insert(ball.id, ball_entity);
+    }
+}
+
+fn interpolate_proxies(
+    net_client: Res<NetClient>,
+    time: Res<Time<Real>>,
+    mut crabs_query: Query<
+        (&Side, &mut Transform),
+        (With<Crab>, With<NetworkProxy>),
+    >,
+    mut balls_query: Query<
+        &mut Transform,
+        (With<Ball>, With<NetworkProxy>, Without<Crab>),
+    >,
+) {
+    let render_time = time.elapsed_secs() - INTERPOLATION_DELAY_SECS;
+    let snapshots = &net_client.snapshots;
+
+    // Find the snapshots on either side of the render time, or hold the
+    // newest one if nothing later has arrived yet.
+    let Some(to_index) = snapshots
+        .iter()
+        .position(|(received, _)| *received >= render_time)
+        .or(snapshots.len().checked_sub(1))
+    else {
+        return;
+    };
+    let from_index = to_index.saturating_sub(1);
+    let (from_time, from) = &snapshots[from_index];
+    let (to_time, to) = &snapshots[to_index];
+    let t = if to_time > from_time {
+        ((render_time - from_time) / (to_time - from_time)).clamp(0.0, 1.0)
+    } else {
+        1.0
+    };
+
+    for (side, mut transform) in &mut crabs_query {
+        let find = |snapshot: &Snapshot| {
+            snapshot
+                .crabs
+                .iter()
+                .find(|crab| crab.side == *side)
+                .map(|crab| crab.x)
+        };
+
+        if let Some(to_x) = find(to) {
+            let from_x = find(from).unwrap_or(to_x);
+
+            transform.translation.x = from_x.lerp(to_x, t);
+        }
+    }
+
+    for (id, entity) in &net_client.balls {
+        let Ok(mut transform) = balls_query.get_mut(*entity) else {
+            continue;
+        };
+        let find = |snapshot: &Snapshot| {
+            snapshot
+                .balls
+                .iter()
+                .find(|ball| ball.id == *id)
+                .map(|ball| ball.translation)
+        };
+
+        if let Some(to_translation) = find(to) {
+            let from_translation = find(from).unwrap_or(to_translation);
+
+            transform.translation = from_translation.lerp(to_translation, t);
+        }
+    }
+}
+
+fn send_player_inputs(
+    mut net_client: ResMut<NetClient>,
+    time: Res<Time>,
+    crabs_query: Query<&ActionState<CrabAction>, (With<Player>, With<Crab>)>,
+) {
+    let net_client = &mut *net_client;
+
+    if net_client.slot.is_none() {
+        return;
+    }
+
+    let Ok(action_state) = crabs_query.get_single() else {
+        return;
+    };
+
+    net_client.sequence += 1;
+
+    let input = InputFrame::sample(net_client.sequence, action_state);
+    let host = net_client.host;
+
+    net_client
+        .link
+        .send(host, &NetMessage::Input(input.clone()));
+    net_client.sent_inputs.push_back(SentInput {
+        input,
+        delta_secs: time.delta_secs(),
+    });
+
+    if net_client.sent_inputs.len() > MAX_SENT_INPUTS {
+        net_client.sent_inputs.pop_front();
+    }
+}
+
+fn say_goodbye_to_host_on_exit(
+    mut net_client: ResMut<NetClient>,
+    mut app_exit_events: EventReader<AppExit>,
+) {
+    if app_exit_events.read().last().is_some() && net_client.slot.is_some() {
+        let host = net_client.host;
+
+        net_client.link.send(host, &NetMessage::Goodbye);
+    }
+}
+
+fn flush_client_link(mut net_client: ResMut<NetClient>) {
+    net_client.link.flush();
+}
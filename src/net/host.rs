@@ -0,0 +1,283 @@
+use std::net::SocketAddr;
+
+use bevy::{prelude::*, utils::HashMap};
+use leafwing_input_manager::{plugin::InputManagerSystem, prelude::*};
+
+use crate::{
+    components::{
+        Ball, Crab, CrabAction, Goal, GoalScoredEvent, HitPoints, Motion,
+        Player, PlayerDevice, PlayerSlot, PlayerSlots, Side, Speed,
+    },
+    states::GameState,
+    system_params::GameModes,
+    system_sets::ActiveAfterLoadingSet,
+};
+
+use super::{
+    BallSnapshot, CrabSnapshot, GoalSnapshot, InputFrame, NetLink, NetMessage,
+    SNAPSHOT_INTERVAL_SECS, Snapshot, TIMEOUT_SECS,
+};
+
+pub(super) struct HostPlugin;
+
+impl Plugin for HostPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            PreUpdate,
+            (receive_client_messages, apply_client_inputs_to_crabs)
+                .chain()
+                .after(InputManagerSystem::Update)
+                .run_if(not(in_state(GameState::Loading))),
+        )
+        .add_systems(OnEnter(GameState::StartMenu), forget_goal_scorers)
+        .add_systems(
+            Update,
+            drop_timed_out_clients.in_set(ActiveAfterLoadingSet),
+        )
+        .add_systems(
+            PostUpdate,
+            (record_goal_scorers, send_snapshots_to_clients)
+                .chain()
+                .in_set(ActiveAfterLoadingSet),
+        )
+        .add_systems(
+            Last,
+            (say_goodbye_to_clients_on_exit, flush_host_link).chain(),
+        );
+    }
+}
+
+/// Runs the authoritative game for clients that join over the network.
+#[derive(Resource)]
+pub struct NetHost {
+    link: NetLink,
+    clients: HashMap<SocketAddr, RemoteClient>,
+    tick: u32,
+    snapshot_timer: Timer,
+
+    /// Who scored each goal of the round on each side, for
+    /// [`GoalSnapshot::scored_by`].
+    scored_by: HashMap<Side, Vec<Option<Side>>>,
+}
+
+impl NetHost {
+    pub fn new(link: NetLink) -> Self {
+        Self {
+            link,
+            clients: HashMap::default(),
+            tick: 0,
+            snapshot_timer: Timer::from_seconds(
+                SNAPSHOT_INTERVAL_SECS,
+                TimerMode::Repeating,
+            ),
+            scored_by: HashMap::default(),
+        }
+    }
+}
+
+/// A client that has joined the host.
+#[derive(Debug)]
+pub struct RemoteClient {
+    pub slot: PlayerSlot,
+    pub input: InputFrame,
+    pub last_heard_secs: f32,
+}
+
+fn receive_client_messages(
+    mut net_host: ResMut<NetHost>,
+    mut player_slots: ResMut<PlayerSlots>,
+    game_modes: GameModes,
+    time: Res<Time<Real>>,
+) {
+    let net_host = &mut *net_host;
+    let now = time.elapsed_secs();
+    let mode_slots: Vec<_> =
+        game_modes.current().player_slots().into_values().collect();
+
+    for (from, message) in net_host.link.receive() {
+        match message {
+            NetMessage::Hello => {
+                let reply =
+                    if let Some(client) = net_host.clients.get_mut(&from) {
+                        client.last_heard_secs = now;
+                        NetMessage::Welcome { slot: client.slot }
+                    } else if let Some(slot) = player_slots
+                        .join_network(from, mode_slots.iter().copied())
+                    {
+                        net_host.clients.insert(
+                            from,
+                            RemoteClient {
+                                slot,
+                                input: InputFrame::default(),
+                                last_heard_secs: now,
+                            },
+                        );
+                        info!("Net({from}): Joined as Player {slot:?}");
+                        NetMessage::Welcome { slot }
+                    } else {
+                        NetMessage::Full
+                    };
+
+                net_host.link.send(from, &reply);
+            },
+            NetMessage::Input(input) => {
+                let Some(client) = net_host.clients.get_mut(&from) else {
+                    continue;
+                };
+
+                client.last_heard_secs = now;
+
+                // Datagrams can arrive out of order, so keep the newest.
+                if input.sequence > client.input.sequence {
+                    client.input = input;
+                }
+            },
+            NetMessage::Goodbye if net_host.clients.remove(&from).is_some() => {
+                player_slots.leave_network(from);
+                info!("Net({from}): Left");
+            },
+            _ => {},
+        }
+    }
+}
+
+fn apply_client_inputs_to_crabs(
+    net_host: Res<NetHost>,
+    player_slots: Res<PlayerSlots>,
+    mut crabs_query: Query<(&Player, &mut ActionState<CrabAction>)>,
+) {
+    for (player, mut action_state) in &mut crabs_query {
+        let PlayerDevice::Network(address) = player_slots.device(player.0)
+        else {
+            continue;
+        };
+        let Some(client) = net_host.clients.get(&address) else {
+            continue;
+        };
+
+        client.input.apply(&mut action_state);
+    }
+}
+
+fn drop_timed_out_clients(
+    mut net_host: ResMut<NetHost>,
+    mut player_slots: ResMut<PlayerSlots>,
+    time: Res<Time<Real>>,
+) {
+    let now = time.elapsed_secs();
+
+    net_host.clients.retain(|address, client| {
+        let is_connected = now - client.last_heard_secs < TIMEOUT_SECS;
+
+        if !is_connected {
+            player_slots.leave_network(*address);
+            info!("Net({address}): Timed out");
+        }
+
+        is_connected
+    });
+}
+
+fn forget_goal_scorers(mut net_host: ResMut<NetHost>) {
+    net_host.scored_by.clear();
+}
+
+fn record_goal_scorers(
+    mut net_host: ResMut<NetHost>,
+    mut goal_scored_events: EventReader<GoalScoredEvent>,
+    goals_query: Query<&Side, With<Goal>>,
+) {
+    for GoalScoredEvent {
+        goal_entity,
+        scored_by,
+    } in goal_scored_events.read()
+    {
+        if let Ok(side) = goals_query.get(*goal_entity) {
+            net_host
+                .scored_by
+                .entry(*side)
+                .or_default()
+                .push(*scored_by);
+        }
+    }
+}
+
+fn send_snapshots_to_clients(
+    mut net_host: ResMut<NetHost>,
+    time: Res<Time<Real>>,
+    game_state: Res<State<GameState>>,
+    game_modes: GameModes,
+    goals_query: Query<(&Side, &HitPoints), With<Goal>>,
+    crabs_query: Query<(&Side, &Transform, &Speed), (With<Crab>, With<Motion>)>,
+    balls_query: Query<(Entity, &GlobalTransform), With<Ball>>,
+) {
+    let net_host = &mut *net_host;
+
+    if !net_host.snapshot_timer.tick(time.delta()).just_finished()
+        || net_host.clients.is_empty()
+    {
+        return;
+    }
+
+    net_host.tick += 1;
+
+    let mut snapshot = Snapshot {
+        tick: net_host.tick,
+        ack: 0,
+        game_state: *game_state.get(),
+        game_mode: game_modes.current().name.clone(),
+        goals: goals_query
+            .iter()
+            .map(|(side, hit_points)| GoalSnapshot {
+                side: *side,
+                hit_points: hit_points.0,
+                scored_by: net_host
+                    .scored_by
+                    .get(side)
+                    .cloned()
+                    .unwrap_or_default(),
+            })
+            .collect(),
+        crabs: crabs_query
+            .iter()
+            .map(|(side, transform, speed)| CrabSnapshot {
+                side: *side,
+                x: transform.translation.x,
+                speed: speed.0,
+            })
+            .collect(),
+        balls: balls_query
+            .iter()
+            .map(|(entity, global_transform)| BallSnapshot {
+                id: entity.to_bits(),
+                translation: global_transform.translation(),
+            })
+            .collect(),
+    };
+
+    for (address, client) in &net_host.clients {
+        snapshot.ack = client.input.sequence;
+        net_host
+            .link
+            .send(*address, &NetMessage::Snapshot(snapshot.clone()));
+    }
+}
+
+fn say_goodbye_to_clients_on_exit(
+    mut net_host: ResMut<NetHost>,
+    mut app_exit_events: EventReader<AppExit>,
+) {
+    let net_host = &mut *net_host;
+
+    if app_exit_events.read().last().is_none() {
+        return;
+    }
+
+    for address in net_host.clients.keys() {
+        net_host.link.send(*address, &NetMessage::Goodbye);
+    }
+}
+
+fn flush_host_link(mut net_host: ResMut<NetHost>) {
+    net_host.link.flush();
+}
@@ -0,0 +1,162 @@
+use std::{
+    io::{self, ErrorKind},
+    net::{SocketAddr, ToSocketAddrs, UdpSocket},
+    time::{Duration, Instant},
+};
+
+use bevy::prelude::*;
+use rand::prelude::*;
+
+use super::NetMessage;
+
+/// Largest datagram that will be read from the socket.
+const MAX_DATAGRAM_SIZE: usize = 65_507;
+
+/// Simulated network conditions applied to outgoing datagrams, for testing
+/// over loopback.
+#[derive(Clone, Debug, Default)]
+pub struct LinkConditions {
+    /// Fixed delay added before each datagram is sent.
+    pub latency: Duration,
+
+    /// Extra random delay of up to this much, which can reorder datagrams.
+    pub jitter: Duration,
+
+    /// Chance from `0.0` to `1.0` that a datagram is dropped.
+    pub packet_loss: f32,
+}
+
+impl LinkConditions {
+    /// Parses optional `--latency <ms>`, `--jitter <ms>`, and
+    /// `--packet-loss <0..1>` flags.
+    pub fn from_args(args: &[String]) -> Self {
+        let value_of = |flag: &str| {
+            args.iter()
+                .skip_while(|arg| *arg != flag)
+                .nth(1)
+                .map(String::as_str)
+        };
+        let millis_of = |flag: &str| {
+            Duration::from_millis(
+                value_of(flag).and_then(|v| v.parse().ok()).unwrap_or(0),
+            )
+        };
+
+        Self {
+            latency: millis_of("--latency"),
+            jitter: millis_of("--jitter"),
+            packet_loss: value_of("--packet-loss")
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0.0),
+        }
+    }
+}
+
+/// A non-blocking UDP socket that exchanges [`NetMessage`] datagrams.
+pub struct NetLink {
+    socket: UdpSocket,
+    conditions: LinkConditions,
+    rng: SmallRng,
+    delayed: Vec<(Instant, SocketAddr, Vec<u8>)>,
+}
+
+impl NetLink {
+    /// Binds a socket to a local address.
+    pub fn bind(
+        address: impl ToSocketAddrs,
+        conditions: LinkConditions,
+    ) -> io::Result<Self> {
+        let socket = UdpSocket::bind(address)?;
+
+        socket.set_nonblocking(true)?;
+
+        Ok(Self {
+            socket,
+            conditions,
+            rng: SmallRng::from_os_rng(),
+            delayed: Vec::new(),
+        })
+    }
+
+    /// The address the socket is bound to.
+    pub fn local_addr(&self) -> io::Result<SocketAddr> {
+        self.socket.local_addr()
+    }
+
+    /// Sends a message, subject to the simulated [`LinkConditions`].
+    pub fn send(&mut self, to: SocketAddr, message: &NetMessage) {
+        if self.rng.random::<f32>() < self.conditions.packet_loss {
+            return;
+        }
+
+        let bytes = match serde_json::to_vec(message) {
+            Ok(bytes) => bytes,
+            Err(error) => {
+                warn!("Net: Failed to encode message: {error}");
+                return;
+            },
+        };
+        let jitter = self
+            .conditions
+            .jitter
+            .mul_f32(self.rng.random_range(0.0..=1.0));
+        let delay = self.conditions.latency + jitter;
+
+        if delay.is_zero() {
+            self.send_now(to, &bytes);
+        } else {
+            self.delayed.push((Instant::now() + delay, to, bytes));
+        }
+    }
+
+    /// Sends any delayed messages that are due.
+    pub fn flush(&mut self) {
+        let now = Instant::now();
+        let (due, delayed) = self
+            .delayed
+            .drain(..)
+            .partition::<Vec<_>, _>(|(send_at, ..)| *send_at <= now);
+
+        self.delayed = delayed;
+
+        for (_, to, bytes) in due {
+            self.send_now(to, &bytes);
+        }
+    }
+
+    /// Reads every message waiting on the socket.
+    pub fn receive(&mut self) -> Vec<(SocketAddr, NetMessage)> {
+        let mut messages = Vec::new();
+        let mut buffer = [0; MAX_DATAGRAM_SIZE];
+
+        loop {
+            match self.socket.recv_from(&mut buffer) {
+                Ok((length, from)) => {
+                    match serde_json::from_slice(&buffer[..length]) {
+                        Ok(message) => messages.push((from, message)),
+                        Err(error) => {
+                            warn!("Net: Bad message from {from}: {error}");
+                        },
+                    }
+                },
+                Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+                // Windows reports unreachable peers as receive errors.
+                Err(error) if error.kind() == ErrorKind::ConnectionReset => {},
+                Err(error) => {
+                    warn!("Net: Failed to receive: {error}");
+                    break;
+                },
+            }
+        }
+
+        messages
+    }
+
+    fn send_now(&self, to: SocketAddr, bytes: &[u8]) {
+        if let Err(error) = self.socket.send_to(bytes, to) {
+            if error.kind() != ErrorKind::WouldBlock {
+                warn!("Net: Failed to send to {to}: {error}");
+            }
+        }
+    }
+}
@@ -0,0 +1,228 @@
+mod client;
+mod host;
+mod link;
+mod protocol;
+mod rollback;
+
+pub use client::*;
+pub use host::*;
+pub use link::*;
+pub use protocol::*;
+pub use rollback::*;
+
+use bevy::prelude::*;
+
+/// How often the host sends a [`Snapshot`] to each client.
+pub const SNAPSHOT_INTERVAL_SECS: f32 = 1.0 / 30.0;
+
+/// How long a peer can go silent before it's considered disconnected.
+pub const TIMEOUT_SECS: f32 = 5.0;
+
+/// Adds host, client, or rollback networking, depending on the [`NetConfig`].
+pub struct NetPlugin(pub NetConfig);
+
+impl Plugin for NetPlugin {
+    fn build(&self, app: &mut App) {
+        let NetConfig { role, conditions } = self.0.clone();
+
+        match role {
+            NetRole::Host(address) => {
+                match NetLink::bind(address, conditions) {
+                    Ok(link) => {
+                        app.insert_resource(NetHost::new(link))
+                            .add_plugins(HostPlugin);
+                        info!("Net: Hosting on {address}");
+                    },
+                    Err(error) => {
+                        error!("Net: Failed to host on {address}: {error}")
+                    },
+                }
+            },
+            NetRole::Join(address) => {
+                match NetLink::bind("0.0.0.0:0", conditions) {
+                    Ok(link) => {
+                        app.insert_resource(NetClient::new(link, address))
+                            .add_plugins(ClientPlugin);
+                        info!("Net: Joining {address}");
+                    },
+                    Err(error) => {
+                        error!("Net: Failed to join {address}: {error}")
+                    },
+                }
+            },
+            NetRole::Rollback { address, peer } => {
+                match NetLink::bind(address, conditions) {
+                    Ok(link) => {
+                        app.insert_resource(RollbackSession::new(link, peer))
+                            .add_plugins(RollbackPlugin);
+                        info!("Net: Rolling back with {peer} from {address}");
+                    },
+                    Err(error) => {
+                        error!("Net: Failed to bind {address}: {error}")
+                    },
+                }
+            },
+        }
+    }
+}
+
+/// Whether this instance hosts a game, joins one, or plays peer-to-peer.
+#[derive(Clone, Debug)]
+pub enum NetRole {
+    Host(std::net::SocketAddr),
+    Join(std::net::SocketAddr),
+
+    /// Simulates the game alongside a single peer with rollback.
+    Rollback {
+        address: std::net::SocketAddr,
+        peer: std::net::SocketAddr,
+    },
+}
+
+/// Networking options read from the command line.
+#[derive(Clone, Debug)]
+pub struct NetConfig {
+    pub role: NetRole,
+    pub conditions: LinkConditions,
+}
+
+impl NetConfig {
+    /// Parses `--host <address>`, `--join <address>`, or
+    /// `--rollback <address> --peer <address>`, along with optional
+    /// `--latency <ms>`, `--jitter <ms>`, and `--packet-loss <0..1>` flags for
+    /// simulating a worse network.
+    ///
+    /// Returns `None` if neither role was requested or an address is invalid.
+    pub fn from_args(args: &[String]) -> Option<Self> {
+        let value_of = |flag: &str| {
+            args.iter()
+                .skip_while(|arg| *arg != flag)
+                .nth(1)
+                .map(String::as_str)
+        };
+        let role = if let Some(address) = value_of("--host") {
+            NetRole::Host(address.parse().ok()?)
+        } else if let Some(address) = value_of("--join") {
+            NetRole::Join(address.parse().ok()?)
+        } else if let Some(address) = value_of("--rollback") {
+            NetRole::Rollback {
+                address: address.parse().ok()?,
+                peer: value_of("--peer")?.parse().ok()?,
+            }
+        } else {
+            return None;
+        };
+
+        Some(Self {
+            role,
+            conditions: LinkConditions::from_args(args),
+        })
+    }
+}
+
+/// Checks that the game isn't hosting, joining, or rolling back a network
+/// session.
+pub fn is_offline(
+    host: Option<Res<NetHost>>,
+    client: Option<Res<NetClient>>,
+    rollback_session: Option<Res<RollbackSession>>,
+) -> bool {
+    host.is_none() && client.is_none() && rollback_session.is_none()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use bevy::ecs::system::SystemState;
+
+    use crate::{
+        components::{
+            Ball, Crab, Player, PlayerDevice, PlayerSlot, PlayerSlots,
+        },
+        headless::build_headless_app,
+        states::GameState,
+        system_params::GameModes,
+    };
+
+    use super::*;
+
+    const TIMESTEP: Duration = Duration::from_nanos(16_666_667);
+    const MAX_UPDATES: u32 = 3_000;
+
+    fn update_until(
+        host: &mut App,
+        client: &mut App,
+        mut is_done: impl FnMut(&mut App, &mut App) -> bool,
+    ) -> bool {
+        for _ in 0..MAX_UPDATES {
+            host.update();
+            client.update();
+
+            if is_done(host, client) {
+                return true;
+            }
+
+            std::thread::sleep(Duration::from_millis(1));
+        }
+
+        false
+    }
+
+    fn count<F: bevy::ecs::query::QueryFilter>(app: &mut App) -> usize {
+        let world = app.world_mut();
+
+        world.query_filtered::<(), F>().iter(world).count()
+    }
+
+    #[test]
+    fn client_joins_host_over_loopback_and_mirrors_its_round() {
+        let host_link =
+            NetLink::bind("127.0.0.1:0", LinkConditions::default()).unwrap();
+        let host_address = host_link.local_addr().unwrap();
+        let client_link =
+            NetLink::bind("127.0.0.1:0", LinkConditions::default()).unwrap();
+        let client_address = client_link.local_addr().unwrap();
+        let mut host = build_headless_app(TIMESTEP, |app| {
+            app.insert_resource(NetHost::new(host_link))
+                .add_plugins(HostPlugin);
+        });
+        let mut client = build_headless_app(TIMESTEP, |app| {
+            app.insert_resource(NetClient::new(client_link, host_address))
+                .add_plugins(ClientPlugin);
+        });
+
+        let world = host.world_mut();
+        let mut system_state = SystemState::<GameModes>::new(world);
+
+        assert!(system_state.get_mut(world).select("Red vs Blue (Player)"));
+
+        // The host keeps the mode's first player slot for itself.
+        assert!(update_until(&mut host, &mut client, |_, client| {
+            client.world().resource::<NetClient>().slot().is_some()
+        }));
+        assert_eq!(
+            client.world().resource::<NetClient>().slot(),
+            Some(PlayerSlot::Two)
+        );
+        assert_eq!(
+            host.world()
+                .resource::<PlayerSlots>()
+                .device(PlayerSlot::Two),
+            PlayerDevice::Network(client_address)
+        );
+
+        host.world_mut()
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::Playing);
+
+        assert!(update_until(&mut host, &mut client, |_, client| {
+            *client.world().resource::<State<GameState>>() == GameState::Playing
+                && count::<(With<Ball>, With<NetworkProxy>)>(client) > 0
+        }));
+
+        // Only the client's own crab is still played locally.
+        assert_eq!(count::<(With<Crab>, With<Player>)>(&mut client), 1);
+        assert_eq!(count::<(With<Crab>, With<NetworkProxy>)>(&mut client), 3);
+    }
+}
@@ -0,0 +1,145 @@
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::{
+    components::{CrabAction, PlayerSlot, Side},
+    states::GameState,
+};
+
+/// A single datagram sent between a host and its clients.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NetMessage {
+    /// Sent by a client until the host replies with [`NetMessage::Welcome`].
+    Hello,
+
+    /// The host accepted a client into a [`PlayerSlot`].
+    Welcome { slot: PlayerSlot },
+
+    /// The host has no free slots left.
+    Full,
+
+    /// A client's latest crab inputs.
+    Input(InputFrame),
+
+    /// The host's authoritative state.
+    Snapshot(Snapshot),
+
+    /// Either side is leaving the session.
+    Goodbye,
+
+    /// A rollback peer is ready to start a round.
+    Ready(RoundReady),
+
+    /// A rollback peer's inputs for consecutive ticks, starting at
+    /// `first_tick`.
+    Inputs {
+        round: u32,
+        first_tick: u32,
+
+        /// How many of the receiver's ticks the sender already has.
+        ack: u32,
+        inputs: Vec<InputFrame>,
+    },
+
+    /// A rollback peer's checksums for ticks that can no longer roll back.
+    Checksums {
+        round: u32,
+        checksums: Vec<(u32, u64)>,
+    },
+}
+
+/// How both rollback peers should start a round, as decided by the leader.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct RoundReady {
+    pub round: u32,
+    pub game_mode: String,
+
+    /// Seeds the [`SpawnerRng`](crate::spawners::SpawnerRng) on both peers.
+    pub seed: u64,
+}
+
+/// The [`CrabAction`] inputs a client sampled on one frame.
+#[derive(Clone, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct InputFrame {
+    /// Increases by one for every frame the client sends.
+    pub sequence: u32,
+    pub pressed: Vec<CrabAction>,
+    pub stick: Vec2,
+}
+
+impl InputFrame {
+    /// Samples the actions currently held in an [`ActionState`].
+    pub fn sample(
+        sequence: u32,
+        action_state: &ActionState<CrabAction>,
+    ) -> Self {
+        Self {
+            sequence,
+            pressed: CrabAction::iter()
+                .filter(|action| {
+                    *action != CrabAction::Move && action_state.pressed(action)
+                })
+                .collect(),
+            stick: action_state.axis_pair(&CrabAction::Move),
+        }
+    }
+
+    /// Makes an [`ActionState`] hold exactly these actions.
+    pub fn apply(&self, action_state: &mut ActionState<CrabAction>) {
+        for action in CrabAction::iter().filter(|a| *a != CrabAction::Move) {
+            if self.pressed.contains(&action) {
+                action_state.press(&action);
+            } else {
+                action_state.release(&action);
+            }
+        }
+
+        action_state.set_axis_pair(&CrabAction::Move, self.stick);
+    }
+}
+
+/// Everything a client needs to mirror the host's game.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Snapshot {
+    /// Increases by one for every snapshot the host sends.
+    pub tick: u32,
+
+    /// The last [`InputFrame::sequence`] the host applied for this client.
+    pub ack: u32,
+    pub game_state: GameState,
+    pub game_mode: String,
+    pub goals: Vec<GoalSnapshot>,
+    pub crabs: Vec<CrabSnapshot>,
+    pub balls: Vec<BallSnapshot>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct GoalSnapshot {
+    pub side: Side,
+    pub hit_points: u8,
+
+    /// Who got the credit for each goal scored on this side this round, in
+    /// order.
+    pub scored_by: Vec<Option<Side>>,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct CrabSnapshot {
+    pub side: Side,
+
+    /// Position along the goal's x axis.
+    pub x: f32,
+
+    /// Speed along the goal's x axis.
+    pub speed: f32,
+}
+
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct BallSnapshot {
+    /// Identifies the ball across snapshots.
+    pub id: u64,
+    pub translation: Vec3,
+}
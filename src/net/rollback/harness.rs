@@ -0,0 +1,162 @@
+use std::{
+    thread,
+    time::{Duration, Instant},
+};
+
+use bevy::{ecs::system::SystemState, prelude::*};
+use rand::prelude::*;
+
+use crate::{
+    components::CrabAction, headless::build_headless_app,
+    system_params::GameModes,
+};
+
+use super::{
+    super::{InputFrame, LinkConditions, NetLink},
+    ROLLBACK_TIMESTEP, RollbackPlugin, RollbackSession, RollbackStats,
+};
+
+/// The game mode both harness peers play, since it has two player crabs.
+const HARNESS_GAME_MODE: &str = "Red vs Blue (Player)";
+
+/// Replaces a peer's local input with random but reproducible presses, so a
+/// [`RollbackSession`] can be exercised without anyone playing.
+#[derive(Debug, Resource)]
+pub struct ScriptedInputs {
+    rng: SmallRng,
+    held: InputFrame,
+    ticks_left: u32,
+}
+
+impl ScriptedInputs {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            rng: SmallRng::seed_from_u64(seed),
+            held: InputFrame::default(),
+            ticks_left: 0,
+        }
+    }
+
+    /// Gets the input for the next tick, which changes every so often.
+    pub fn next(&mut self, sequence: u32) -> InputFrame {
+        if self.ticks_left == 0 {
+            self.ticks_left = self.rng.random_range(5..40);
+            self.held.pressed = match self.rng.random_range(0..3) {
+                0 => vec![CrabAction::MoveLeft],
+                1 => vec![CrabAction::MoveRight],
+                _ => vec![],
+            };
+        }
+
+        self.ticks_left -= 1;
+
+        InputFrame {
+            sequence,
+            ..self.held.clone()
+        }
+    }
+}
+
+/// Plays two headless rollback peers against each other over loopback with
+/// scripted inputs, then reports whether they stayed in sync.
+///
+/// Returns `true` if every tick was played without any desyncs.
+pub fn run_rollback_harness(ticks: u32, conditions: LinkConditions) -> bool {
+    let (link_a, link_b) = match (
+        NetLink::bind("127.0.0.1:0", conditions.clone()),
+        NetLink::bind("127.0.0.1:0", conditions),
+    ) {
+        (Ok(link_a), Ok(link_b)) => (link_a, link_b),
+        (Err(error), _) | (_, Err(error)) => {
+            eprintln!("Rollback Harness: Failed to bind: {error}");
+            return false;
+        },
+    };
+    let (Ok(address_a), Ok(address_b)) =
+        (link_a.local_addr(), link_b.local_addr())
+    else {
+        eprintln!("Rollback Harness: Failed to get local addresses");
+        return false;
+    };
+    let mut peers = [
+        build_harness_peer(RollbackSession::new(link_a, address_b), 1),
+        build_harness_peer(RollbackSession::new(link_b, address_a), 2),
+    ];
+
+    println!("Rollback Harness: Playing {ticks} ticks");
+
+    // Give up if the peers make no progress for a while.
+    let timeout = ROLLBACK_TIMESTEP * ticks * 4 + Duration::from_secs(10);
+    let started_at = Instant::now();
+    let mut in_sync = true;
+
+    while peers.iter().any(|app| stats(app).final_ticks < ticks) {
+        if started_at.elapsed() > timeout {
+            eprintln!("Rollback Harness: Timed out");
+            in_sync = false;
+            break;
+        }
+
+        for app in &mut peers {
+            app.update();
+        }
+
+        thread::sleep(ROLLBACK_TIMESTEP);
+    }
+
+    for (name, app) in ["A", "B"].iter().zip(&peers) {
+        let stats = stats(app);
+
+        println!(
+            "Rollback Harness: Peer {name} finalized {} ticks, rolled back {} \
+             times ({} ticks re-simulated), stalled {} frames, last checksum \
+             {:016x}, desyncs {:?}",
+            stats.final_ticks,
+            stats.rollbacks,
+            stats.resimulated_ticks,
+            stats.stalls,
+            stats.last_checksum.unwrap_or_default(),
+            stats.desyncs,
+        );
+
+        in_sync &= stats.desyncs.is_empty();
+    }
+
+    in_sync
+}
+
+fn build_harness_peer(session: RollbackSession, input_seed: u64) -> App {
+    let mut app = build_headless_app(ROLLBACK_TIMESTEP, |app| {
+        app.insert_resource(session)
+            .insert_resource(ScriptedInputs::new(input_seed))
+            .add_plugins(RollbackPlugin);
+    });
+    let world = app.world_mut();
+    let mut system_state = SystemState::<GameModes>::new(world);
+
+    if !system_state.get_mut(world).select(HARNESS_GAME_MODE) {
+        warn!("Rollback Harness: Missing game mode {HARNESS_GAME_MODE:?}");
+    }
+
+    app
+}
+
+fn stats(app: &App) -> &RollbackStats {
+    app.world().resource::<RollbackSession>().stats()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn peers_stay_in_sync_through_rollbacks_on_a_bad_network() {
+        let conditions = LinkConditions {
+            latency: Duration::from_millis(40),
+            jitter: Duration::from_millis(30),
+            packet_loss: 0.05,
+        };
+
+        assert!(run_rollback_harness(300, conditions));
+    }
+}
@@ -0,0 +1,787 @@
+mod harness;
+mod snapshot;
+
+pub use harness::*;
+pub use snapshot::*;
+
+use std::{collections::BTreeMap, net::SocketAddr, time::Duration};
+
+use bevy::{
+    app::MainScheduleOrder, ecs::schedule::ScheduleLabel, prelude::*,
+    time::TimeUpdateStrategy,
+};
+use leafwing_input_manager::{plugin::InputManagerSystem, prelude::*};
+use rand::prelude::*;
+
+use crate::{
+    assets::{GameAssets, GameConfig},
+    components::{
+        AI, Acceleration, Ball, BallBouncedEvent, BallDeflectedEvent,
+        CircleCollider, Collider, Crab, CrabAction, CrabCollider,
+        DepthCollider, Direction, Fade, FadeDuration, FadeEffect, FadeTimer,
+        FadeTransition, ForStates, Force, Goal, GoalEliminatedEvent,
+        GoalScoredEvent, HitPoints, InsertAfterFadeIn, LastTouchedBy, MaxSpeed,
+        Motion, Player, PlayerDevice, PlayerSlot, PlayerSlots,
+        PointerControlled, Pole, RemoveBeforeFadeOut, Side, Speed,
+        StoppingDistance, TargetSpeed,
+    },
+    settings::Settings,
+    spawners::SpawnerRng,
+    states::GameState,
+    stats::MatchStats,
+    system_params::GameModes,
+    system_sets::ActiveAfterLoadingSet,
+};
+
+use super::{InputFrame, NetLink, NetMessage, RoundReady, TIMEOUT_SECS};
+
+/// Simulated time that passes for each rollback tick.
+pub const ROLLBACK_TIMESTEP: Duration = Duration::from_nanos(16_666_667);
+
+/// Ticks between sampling local input and applying it, which hides that much
+/// latency without needing to roll back.
+pub const INPUT_DELAY_TICKS: u32 = 2;
+
+/// How far the simulation can run ahead of the peer's confirmed inputs
+/// before it waits for them.
+pub const MAX_PREDICTION_TICKS: u32 = 8;
+
+/// How often a peer announces it's ready while waiting in the start menu.
+const READY_INTERVAL_SECS: f32 = 0.25;
+
+/// Most inputs or checksums sent in a single message.
+const MAX_FRAMES_PER_MESSAGE: usize = 32;
+
+/// How many final checksums are kept for comparing against late ones.
+const MAX_KEPT_CHECKSUMS: u32 = 600;
+
+/// Runs after [`Last`] to check the tick that was just simulated.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, ScheduleLabel)]
+pub struct RollbackCheck;
+
+pub(super) struct RollbackPlugin;
+
+impl Plugin for RollbackPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_schedule(RollbackCheck)
+            .world_mut()
+            .resource_mut::<MainScheduleOrder>()
+            .insert_after(Last, RollbackCheck);
+
+        app.insert_resource(TimeUpdateStrategy::ManualDuration(
+            ROLLBACK_TIMESTEP,
+        ))
+        .init_resource::<RollbackIds>()
+        .rollback_resource::<RollbackIds>()
+        .rollback_resource::<SpawnerRng>()
+        .rollback_resource::<MatchStats>()
+        // Markers go first, so their required components get overwritten.
+        .rollback_component::<Ball>()
+        .rollback_component::<Crab>()
+        .rollback_component::<Pole>()
+        .rollback_component::<AI>()
+        .rollback_component::<Player>()
+        .rollback_component::<Side>()
+        .rollback_component::<Transform>()
+        // AI crabs aim at where balls were last propagated to.
+        .rollback_component::<GlobalTransform>()
+        .rollback_component::<Motion>()
+        .rollback_component::<Speed>()
+        .rollback_component::<Direction>()
+        .rollback_component::<Acceleration>()
+        .rollback_component::<MaxSpeed>()
+        .rollback_component::<StoppingDistance>()
+        .rollback_component::<TargetSpeed>()
+        .rollback_component::<Force>()
+        .rollback_component::<HitPoints>()
+        .rollback_component::<LastTouchedBy>()
+        .rollback_component::<Collider>()
+        .rollback_component::<CircleCollider>()
+        .rollback_component::<DepthCollider>()
+        .rollback_component::<CrabCollider>()
+        .rollback_component::<Fade>()
+        .rollback_component::<FadeDuration>()
+        .rollback_component::<FadeEffect>()
+        .rollback_component::<FadeTimer>()
+        .rollback_component::<FadeTransition>()
+        .rollback_component::<InsertAfterFadeIn<Motion>>()
+        .rollback_component::<InsertAfterFadeIn<Collider>>()
+        .rollback_component::<RemoveBeforeFadeOut<Motion>>()
+        .rollback_component::<RemoveBeforeFadeOut<Collider>>()
+        .rollback_component::<ForStates<GameState>>()
+        .rollback_component::<Mesh3d>()
+        .rollback_component::<MeshMaterial3d<StandardMaterial>>()
+        .rollback_respawned_component::<InputMap<CrabAction>>()
+        .rollback_respawned_component::<SceneRoot>()
+        .add_observer(assign_rollback_id::<Goal>)
+        .add_observer(assign_rollback_id::<Pole>)
+        .add_observer(assign_rollback_id::<Crab>)
+        .add_observer(assign_rollback_id::<Ball>)
+        .add_systems(OnEnter(GameState::StartMenu), prepare_next_round)
+        .add_systems(
+            PreUpdate,
+            (
+                receive_peer_messages,
+                advance_rollback_session
+                    .run_if(in_state(GameState::Playing)),
+            )
+                .chain()
+                .after(InputManagerSystem::Update),
+        )
+        .add_systems(
+            Update,
+            (
+                ready_up_for_next_round
+                    .run_if(in_state(GameState::StartMenu)),
+                hand_rollback_crabs_to_their_peers,
+            )
+                .in_set(ActiveAfterLoadingSet),
+        )
+        .add_systems(Last, say_goodbye_to_peer_on_exit)
+        .add_systems(
+            RollbackCheck,
+            (
+                undo_stalled_frame,
+                record_checksum_of_simulated_tick,
+                send_inputs_and_checksums,
+                flush_rollback_link,
+            )
+                .chain(),
+        );
+    }
+}
+
+/// A peer-to-peer session where both peers simulate the whole game from each
+/// other's inputs.
+///
+/// Inputs from the peer that haven't arrived yet are predicted. When they do
+/// arrive and differ, the world is restored to a [`WorldSnapshot`] from
+/// before the misprediction and simulated again up to the current tick.
+#[derive(Resource)]
+pub struct RollbackSession {
+    link: NetLink,
+    peer: SocketAddr,
+
+    /// The peer with the lower address decides the game mode and seed.
+    is_leader: bool,
+    seed: u64,
+    round: u32,
+    in_round: bool,
+    ready: Option<RoundReady>,
+    ready_timer: Timer,
+    peer_ready_round: Option<u32>,
+    local_device: Option<PlayerDevice>,
+    last_heard_secs: f32,
+
+    /// The next tick to simulate.
+    tick: u32,
+
+    /// The tick being simulated this frame, if any.
+    simulating: Option<u32>,
+
+    /// Ticks that already played out are being simulated again.
+    resimulating: bool,
+
+    /// This frame's simulation will be undone because it had to wait.
+    stalled: Option<WorldSnapshot>,
+    local_inputs: Vec<InputFrame>,
+    remote_inputs: Vec<InputFrame>,
+    predicted_inputs: BTreeMap<u32, InputFrame>,
+    rollback_to: Option<u32>,
+    peer_ack: u32,
+    snapshots: BTreeMap<u32, WorldSnapshot>,
+    checksums: BTreeMap<u32, u64>,
+    peer_checksums: BTreeMap<u32, u64>,
+    checksums_sent: u32,
+    final_tick: u32,
+    stats: RollbackStats,
+}
+
+/// How a [`RollbackSession`] has gone so far, across every round.
+#[derive(Clone, Debug, Default)]
+pub struct RollbackStats {
+    /// Ticks simulated with both peers' real inputs.
+    pub final_ticks: u32,
+    pub rollbacks: u32,
+    pub resimulated_ticks: u32,
+    pub stalls: u32,
+
+    /// Ticks where the peers' checksums didn't match.
+    pub desyncs: Vec<u32>,
+
+    /// The checksum of the latest final tick.
+    pub last_checksum: Option<u64>,
+}
+
+impl RollbackSession {
+    pub fn new(link: NetLink, peer: SocketAddr) -> Self {
+        let is_leader = link
+            .local_addr()
+            .is_ok_and(|local_address| local_address < peer);
+
+        Self {
+            link,
+            peer,
+            is_leader,
+            seed: rand::rng().random(),
+            round: 0,
+            in_round: false,
+            ready: None,
+            ready_timer: Timer::from_seconds(
+                READY_INTERVAL_SECS,
+                TimerMode::Repeating,
+            ),
+            peer_ready_round: None,
+            local_device: None,
+            last_heard_secs: 0.0,
+            tick: 0,
+            simulating: None,
+            resimulating: false,
+            stalled: None,
+            local_inputs: Vec::new(),
+            remote_inputs: Vec::new(),
+            predicted_inputs: BTreeMap::new(),
+            rollback_to: None,
+            peer_ack: 0,
+            snapshots: BTreeMap::new(),
+            checksums: BTreeMap::new(),
+            peer_checksums: BTreeMap::new(),
+            checksums_sent: 0,
+            final_tick: 0,
+            stats: RollbackStats::default(),
+        }
+    }
+
+    pub fn stats(&self) -> &RollbackStats {
+        &self.stats
+    }
+
+    /// The leader plays as [`PlayerSlot::One`], and the other peer as
+    /// [`PlayerSlot::Two`].
+    fn local_slot(&self) -> PlayerSlot {
+        if self.is_leader {
+            PlayerSlot::One
+        } else {
+            PlayerSlot::Two
+        }
+    }
+
+    fn remote_slot(&self) -> PlayerSlot {
+        if self.is_leader {
+            PlayerSlot::Two
+        } else {
+            PlayerSlot::One
+        }
+    }
+
+    /// The peer's input for a tick, or a prediction that it's still holding
+    /// whatever it held last.
+    fn remote_input(&mut self, tick: u32) -> InputFrame {
+        if let Some(input) = self.remote_inputs.get(tick as usize) {
+            return input.clone();
+        }
+
+        let predicted = InputFrame {
+            sequence: tick,
+            ..self.remote_inputs.last().cloned().unwrap_or_default()
+        };
+
+        self.predicted_inputs.insert(tick, predicted.clone());
+        predicted
+    }
+
+    /// Ticks before this were simulated with real inputs from both peers and
+    /// will never roll back.
+    fn final_tick(&self) -> u32 {
+        (self.remote_inputs.len() as u32)
+            .min(self.tick)
+            .min(self.rollback_to.unwrap_or(u32::MAX))
+    }
+}
+
+/// Checks that a [`RollbackSession`] is simulating ticks again after a
+/// misprediction.
+pub fn is_resimulating(session: Option<Res<RollbackSession>>) -> bool {
+    session.is_some_and(|session| session.resimulating)
+}
+
+fn prepare_next_round(mut session: ResMut<RollbackSession>) {
+    let session = &mut *session;
+
+    session.round += 1;
+    session.in_round = false;
+    session.ready = None;
+    session.seed = rand::rng().random();
+    session.tick = 0;
+    session.simulating = None;
+    session.stalled = None;
+    session.local_inputs = (0..INPUT_DELAY_TICKS)
+        .map(|tick| InputFrame {
+            sequence: tick,
+            ..default()
+        })
+        .collect();
+    session.remote_inputs.clear();
+    session.predicted_inputs.clear();
+    session.rollback_to = None;
+    session.peer_ack = 0;
+    session.snapshots.clear();
+    session.checksums.clear();
+    session.peer_checksums.clear();
+    session.checksums_sent = 0;
+    session.final_tick = 0;
+}
+
+fn receive_peer_messages(
+    mut session: ResMut<RollbackSession>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    time: Res<Time<Real>>,
+) {
+    let session = &mut *session;
+    let now = time.elapsed_secs();
+
+    for (from, message) in session.link.receive() {
+        if from != session.peer {
+            continue;
+        }
+
+        session.last_heard_secs = now;
+
+        match message {
+            NetMessage::Ready(ready) => {
+                session.peer_ready_round = Some(ready.round);
+
+                // The peer might have missed the ready that started this
+                // round, so answer it.
+                if session.in_round && ready.round == session.round {
+                    if let Some(own_ready) = session.ready.clone() {
+                        session.link.send(from, &NetMessage::Ready(own_ready));
+                    }
+                }
+
+                if !session.is_leader && ready.round == session.round {
+                    session.ready.get_or_insert(ready);
+                }
+            },
+            NetMessage::Inputs {
+                round,
+                first_tick,
+                ack,
+                inputs,
+            } if round == session.round => {
+                session.peer_ready_round = Some(round);
+                session.peer_ack = session.peer_ack.max(ack);
+
+                for (tick, input) in (first_tick..).zip(inputs) {
+                    if tick as usize != session.remote_inputs.len() {
+                        continue;
+                    }
+
+                    let mispredicted = session
+                        .predicted_inputs
+                        .remove(&tick)
+                        .is_some_and(|predicted| predicted != input);
+
+                    if mispredicted {
+                        session.rollback_to = Some(
+                            session.rollback_to.map_or(tick, |t| t.min(tick)),
+                        );
+                    }
+
+                    session.remote_inputs.push(input);
+                }
+            },
+            NetMessage::Checksums { round, checksums }
+                if round == session.round =>
+            {
+                session.peer_checksums.extend(checksums);
+            },
+            NetMessage::Goodbye if session.in_round => {
+                info!("Rollback({from}): Left");
+                next_game_state.set(GameState::StartMenu);
+            },
+            _ => {},
+        }
+    }
+
+    if session.in_round && now - session.last_heard_secs > TIMEOUT_SECS {
+        warn!("Rollback({}): Timed out", session.peer);
+        session.last_heard_secs = now;
+        next_game_state.set(GameState::StartMenu);
+    }
+}
+
+fn ready_up_for_next_round(
+    mut commands: Commands,
+    mut session: ResMut<RollbackSession>,
+    mut game_modes: GameModes,
+    mut player_slots: ResMut<PlayerSlots>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    time: Res<Time<Real>>,
+) {
+    let session = &mut *session;
+
+    if session.in_round {
+        return;
+    }
+
+    let own_ready = if session.is_leader {
+        RoundReady {
+            round: session.round,
+            game_mode: game_modes.current().name.clone(),
+            seed: session.seed,
+        }
+    } else {
+        RoundReady {
+            round: session.round,
+            game_mode: String::new(),
+            seed: 0,
+        }
+    };
+
+    if session.ready_timer.tick(time.delta()).just_finished() {
+        session
+            .link
+            .send(session.peer, &NetMessage::Ready(own_ready.clone()));
+    }
+
+    // The leader starts once the peer is ready, and the other peer starts
+    // with whatever the leader chose.
+    let ready = if session.is_leader {
+        (session.peer_ready_round == Some(session.round)).then_some(own_ready)
+    } else {
+        session.ready.clone()
+    };
+    let Some(ready) = ready else {
+        return;
+    };
+
+    if !game_modes.select(&ready.game_mode) {
+        warn!("Rollback: Missing game mode {:?}", ready.game_mode);
+    }
+
+    let local_device = *session
+        .local_device
+        .get_or_insert(player_slots.device(PlayerSlot::One));
+
+    player_slots.0.insert(session.local_slot(), local_device);
+    player_slots
+        .0
+        .insert(session.remote_slot(), PlayerDevice::Network(session.peer));
+
+    // Each round gets its own range of ids, so entities still fading out from
+    // the last one can't collide with new ones.
+    commands.insert_resource(RollbackIds {
+        next: session.round << 16,
+    });
+    commands.insert_resource(SpawnerRng(SmallRng::seed_from_u64(ready.seed)));
+    session
+        .link
+        .send(session.peer, &NetMessage::Ready(ready.clone()));
+    session.ready = Some(ready);
+    session.in_round = true;
+    session.last_heard_secs = time.elapsed_secs();
+    next_game_state.set(GameState::Playing);
+    info!("Rollback: Round {} started", session.round);
+}
+
+fn hand_rollback_crabs_to_their_peers(
+    mut commands: Commands,
+    session: Res<RollbackSession>,
+    settings: Res<Settings>,
+    game_assets: Res<GameAssets>,
+    game_configs: Res<Assets<GameConfig>>,
+    crabs_query: Query<(Entity, &Player), Added<Crab>>,
+) {
+    let Some(local_device) = session.local_device else {
+        return;
+    };
+    let game_config = game_configs.get(&game_assets.game_config).unwrap();
+
+    for (entity, player) in &crabs_query {
+        // Pointers move crabs directly, which can't be sent as inputs.
+        commands.entity(entity).remove::<PointerControlled>();
+
+        if player.0 == session.local_slot() {
+            commands
+                .entity(entity)
+                .insert(settings.controls.crab_input_map(
+                    local_device,
+                    true,
+                    game_config.gamepad_stick_deadzone,
+                ));
+        }
+    }
+}
+
+/// Samples local input, rolls back and re-simulates if the peer's inputs
+/// were mispredicted, then sets up this frame to simulate the next tick.
+///
+/// The frame that enters [`GameState::Playing`] only spawns the crabs, which
+/// can't move until they've faded in, so it plays out the same on both peers
+/// before the first tick.
+fn advance_rollback_session(world: &mut World) {
+    let Some(session) = world.get_resource::<RollbackSession>() else {
+        return;
+    };
+
+    if !session.in_round {
+        return;
+    }
+
+    let tick = session.tick;
+    let local_slot = session.local_slot();
+    let stalled =
+        tick >= session.remote_inputs.len() as u32 + MAX_PREDICTION_TICKS;
+
+    if !stalled {
+        let sequence = tick + INPUT_DELAY_TICKS;
+        let scripted_input = world
+            .get_resource_mut::<ScriptedInputs>()
+            .map(|mut scripted_inputs| scripted_inputs.next(sequence));
+        let input = scripted_input.unwrap_or_else(|| {
+            world
+                .query::<(&Player, &ActionState<CrabAction>)>()
+                .iter(world)
+                .find(|(player, _)| player.0 == local_slot)
+                .map(|(_, action_state)| {
+                    InputFrame::sample(sequence, action_state)
+                })
+                .unwrap_or(InputFrame {
+                    sequence,
+                    ..default()
+                })
+        });
+
+        world
+            .resource_mut::<RollbackSession>()
+            .local_inputs
+            .push(input);
+    }
+
+    let rollback_to =
+        world.resource_mut::<RollbackSession>().rollback_to.take();
+
+    if let Some(rollback_to) = rollback_to {
+        roll_back_and_resimulate(world, rollback_to, tick);
+    }
+
+    if stalled {
+        // Simulate nothing this frame, and undo anything that happened anyway.
+        let snapshot = WorldSnapshot::save(world);
+
+        world.resource_mut::<Time>().advance_by(Duration::ZERO);
+
+        let mut session = world.resource_mut::<RollbackSession>();
+
+        session.stalled = Some(snapshot);
+        session.stats.stalls += 1;
+        return;
+    }
+
+    apply_tick_inputs(world, tick);
+
+    let snapshot = WorldSnapshot::save(world);
+    let mut session = world.resource_mut::<RollbackSession>();
+    let final_tick = session.final_tick();
+
+    session.snapshots.insert(tick, snapshot);
+    session.snapshots.retain(|t, _| *t >= final_tick);
+    session.simulating = Some(tick);
+    session.tick += 1;
+}
+
+fn roll_back_and_resimulate(world: &mut World, from_tick: u32, to_tick: u32) {
+    let Some(snapshot) = world
+        .resource_mut::<RollbackSession>()
+        .snapshots
+        .remove(&from_tick)
+    else {
+        warn!("Rollback: Missing snapshot for tick {from_tick}");
+        return;
+    };
+
+    snapshot.restore(world);
+    clear_simulation_events(world);
+    world.resource_mut::<RollbackSession>().resimulating = true;
+
+    for tick in from_tick..to_tick {
+        apply_tick_inputs(world, tick);
+
+        let snapshot = WorldSnapshot::save(world);
+
+        world
+            .resource_mut::<RollbackSession>()
+            .snapshots
+            .insert(tick, snapshot);
+        world.run_schedule(Update);
+        world.run_schedule(PostUpdate);
+        world.run_schedule(Last);
+
+        let checksum = checksum(world);
+
+        world
+            .resource_mut::<RollbackSession>()
+            .checksums
+            .insert(tick, checksum);
+    }
+
+    // The simulation has read these events by now, and what they'd have set
+    // off outside it already happened the first time around.
+    clear_simulation_events(world);
+
+    let mut session = world.resource_mut::<RollbackSession>();
+
+    session.resimulating = false;
+    session.stats.rollbacks += 1;
+    session.stats.resimulated_ticks += to_tick - from_tick;
+}
+
+/// Makes both player crabs hold their inputs for a tick.
+fn apply_tick_inputs(world: &mut World, tick: u32) {
+    let mut session = world.resource_mut::<RollbackSession>();
+    let local_slot = session.local_slot();
+    let local_input = session.local_inputs[tick as usize].clone();
+    let remote_input = session.remote_input(tick);
+
+    for (player, mut action_state) in world
+        .query::<(&Player, &mut ActionState<CrabAction>)>()
+        .iter_mut(world)
+    {
+        if player.0 == local_slot {
+            local_input.apply(&mut action_state);
+        } else {
+            remote_input.apply(&mut action_state);
+        }
+    }
+}
+
+/// Forgets events from a timeline that's being replaced.
+fn clear_simulation_events(world: &mut World) {
+    world.resource_mut::<Events<GoalScoredEvent>>().clear();
+    world.resource_mut::<Events<GoalEliminatedEvent>>().clear();
+    world.resource_mut::<Events<BallDeflectedEvent>>().clear();
+    world.resource_mut::<Events<BallBouncedEvent>>().clear();
+}
+
+fn undo_stalled_frame(world: &mut World) {
+    let Some(snapshot) = world.resource_mut::<RollbackSession>().stalled.take()
+    else {
+        return;
+    };
+
+    snapshot.restore(world);
+    clear_simulation_events(world);
+}
+
+fn record_checksum_of_simulated_tick(world: &mut World) {
+    let Some(tick) = world.resource_mut::<RollbackSession>().simulating.take()
+    else {
+        return;
+    };
+    let checksum = checksum(world);
+
+    world
+        .resource_mut::<RollbackSession>()
+        .checksums
+        .insert(tick, checksum);
+}
+
+fn send_inputs_and_checksums(mut session: ResMut<RollbackSession>) {
+    let session = &mut *session;
+
+    if !session.in_round {
+        return;
+    }
+
+    // Resend every input the peer hasn't acknowledged, in case some were lost.
+    let first_tick = session.peer_ack;
+    let inputs: Vec<_> = session
+        .local_inputs
+        .iter()
+        .skip(first_tick as usize)
+        .take(MAX_FRAMES_PER_MESSAGE)
+        .cloned()
+        .collect();
+    let message = NetMessage::Inputs {
+        round: session.round,
+        first_tick,
+        ack: session.remote_inputs.len() as u32,
+        inputs,
+    };
+
+    session.link.send(session.peer, &message);
+
+    let final_tick = session.final_tick();
+
+    session.stats.final_ticks += final_tick.saturating_sub(session.final_tick);
+    session.final_tick = session.final_tick.max(final_tick);
+
+    if let Some(checksum) = session.checksums.get(&final_tick.wrapping_sub(1)) {
+        session.stats.last_checksum = Some(*checksum);
+    }
+
+    if session.checksums_sent < final_tick {
+        let checksums: Vec<_> = session
+            .checksums
+            .range(session.checksums_sent..final_tick)
+            .take(MAX_FRAMES_PER_MESSAGE)
+            .map(|(tick, checksum)| (*tick, *checksum))
+            .collect();
+
+        if let Some((last_tick, _)) = checksums.last() {
+            session.checksums_sent = last_tick + 1;
+        }
+
+        let message = NetMessage::Checksums {
+            round: session.round,
+            checksums,
+        };
+
+        session.link.send(session.peer, &message);
+    }
+
+    // Compare the peer's checksums against final ones of our own.
+    let comparable: Vec<_> = session
+        .peer_checksums
+        .range(..final_tick)
+        .map(|(tick, checksum)| (*tick, *checksum))
+        .collect();
+
+    for (tick, peer_checksum) in comparable {
+        session.peer_checksums.remove(&tick);
+
+        let Some(checksum) = session.checksums.get(&tick) else {
+            continue;
+        };
+
+        if *checksum != peer_checksum {
+            error!(
+                "Rollback: Desync on tick {tick} of round {} \
+                 ({checksum:016x} != {peer_checksum:016x})",
+                session.round
+            );
+            session.stats.desyncs.push(tick);
+        }
+    }
+
+    let oldest_kept = final_tick.saturating_sub(MAX_KEPT_CHECKSUMS);
+
+    session.checksums.retain(|tick, _| *tick >= oldest_kept);
+}
+
+fn say_goodbye_to_peer_on_exit(
+    mut session: ResMut<RollbackSession>,
+    mut app_exit_events: EventReader<AppExit>,
+) {
+    if app_exit_events.read().last().is_some() {
+        let peer = session.peer;
+
+        session.link.send(peer, &NetMessage::Goodbye);
+        session.link.flush();
+    }
+}
+
+fn flush_rollback_link(mut session: ResMut<RollbackSession>) {
+    session.link.flush();
+}
@@ -0,0 +1,410 @@
+use std::{
+    any::Any,
+    hash::{DefaultHasher, Hash, Hasher},
+    marker::PhantomData,
+};
+
+use bevy::{prelude::*, utils::HashMap};
+
+use crate::components::{
+    Direction, FadeTimer, Force, HitPoints, Speed, StoppingDistance, Target,
+};
+
+/// Identifies an entity across rollbacks, since restoring a
+/// [`WorldSnapshot`] can respawn it as a different [`Entity`].
+#[derive(
+    Clone, Component, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd,
+)]
+pub struct Rollback(pub u32);
+
+/// Hands out [`Rollback`] ids in spawn order, which both peers share.
+#[derive(Clone, Debug, Default, Resource)]
+pub struct RollbackIds {
+    pub next: u32,
+}
+
+/// Gives simulated entities a [`Rollback`] id as soon as they're spawned.
+pub fn assign_rollback_id<C: Component>(
+    trigger: Trigger<OnAdd, C>,
+    mut commands: Commands,
+    mut rollback_ids: ResMut<RollbackIds>,
+    rollback_query: Query<(), With<Rollback>>,
+) {
+    // Entities respawned by a restore already have their old id.
+    if rollback_query.contains(trigger.entity()) {
+        return;
+    }
+
+    commands
+        .entity(trigger.entity())
+        .insert(Rollback(rollback_ids.next));
+    rollback_ids.next += 1;
+}
+
+/// Adds components and resources to every [`WorldSnapshot`].
+pub trait RollbackAppExt {
+    /// Saves and restores a component on every [`Rollback`] entity.
+    fn rollback_component<C: Component + Clone>(&mut self) -> &mut Self;
+
+    /// Saves a component that's set when an entity is spawned, and gives it
+    /// back to [`Rollback`] entities that a restore respawns.
+    fn rollback_respawned_component<C: Component + Clone>(
+        &mut self,
+    ) -> &mut Self;
+
+    /// Saves and restores a resource.
+    fn rollback_resource<R: Resource + Clone>(&mut self) -> &mut Self;
+}
+
+impl RollbackAppExt for App {
+    fn rollback_component<C: Component + Clone>(&mut self) -> &mut Self {
+        self.init_resource::<RollbackRegistry>()
+            .world_mut()
+            .resource_mut::<RollbackRegistry>()
+            .components
+            .push(Box::new(ClonedComponent::<C>(PhantomData)));
+        self
+    }
+
+    fn rollback_respawned_component<C: Component + Clone>(
+        &mut self,
+    ) -> &mut Self {
+        self.init_resource::<RollbackRegistry>()
+            .world_mut()
+            .resource_mut::<RollbackRegistry>()
+            .components
+            .push(Box::new(RespawnedComponent::<C>(PhantomData)));
+        self
+    }
+
+    fn rollback_resource<R: Resource + Clone>(&mut self) -> &mut Self {
+        self.init_resource::<RollbackRegistry>()
+            .world_mut()
+            .resource_mut::<RollbackRegistry>()
+            .resources
+            .push(Box::new(ClonedResource::<R>(PhantomData)));
+        self
+    }
+}
+
+/// Everything that's saved in a [`WorldSnapshot`].
+#[derive(Resource)]
+pub struct RollbackRegistry {
+    components: Vec<Box<dyn SavedComponent>>,
+    resources: Vec<Box<dyn SavedResource>>,
+}
+
+impl Default for RollbackRegistry {
+    fn default() -> Self {
+        Self {
+            // Targets point at other entities, so they're always saved by id.
+            components: vec![Box::new(TargetComponent)],
+            resources: Vec::new(),
+        }
+    }
+}
+
+type Saved = Box<dyn Any + Send + Sync>;
+
+trait SavedComponent: Send + Sync {
+    fn save(&self, world: &mut World) -> Saved;
+    fn load(
+        &self,
+        world: &mut World,
+        saved: &Saved,
+        entities: &HashMap<Rollback, Entity>,
+    );
+}
+
+trait SavedResource: Send + Sync {
+    fn save(&self, world: &World) -> Option<Saved>;
+    fn load(&self, world: &mut World, saved: &Saved);
+}
+
+struct ClonedComponent<C>(PhantomData<fn() -> C>);
+
+impl<C: Component + Clone> SavedComponent for ClonedComponent<C> {
+    fn save(&self, world: &mut World) -> Saved {
+        let saved: HashMap<Rollback, C> = world
+            .query::<(&Rollback, &C)>()
+            .iter(world)
+            .map(|(rollback, component)| (*rollback, component.clone()))
+            .collect();
+
+        Box::new(saved)
+    }
+
+    fn load(
+        &self,
+        world: &mut World,
+        saved: &Saved,
+        entities: &HashMap<Rollback, Entity>,
+    ) {
+        let Some(saved) = saved.downcast_ref::<HashMap<Rollback, C>>() else {
+            return;
+        };
+
+        for (rollback, entity) in entities {
+            let mut entity_mut = world.entity_mut(*entity);
+
+            match saved.get(rollback) {
+                Some(component) => {
+                    entity_mut.insert(component.clone());
+                },
+                None if entity_mut.contains::<C>() => {
+                    entity_mut.remove::<C>();
+                },
+                None => {},
+            }
+        }
+    }
+}
+
+/// Like [`ClonedComponent`], but leaves entities that still have the
+/// component alone, for components that set something up when inserted.
+struct RespawnedComponent<C>(PhantomData<fn() -> C>);
+
+impl<C: Component + Clone> SavedComponent for RespawnedComponent<C> {
+    fn save(&self, world: &mut World) -> Saved {
+        ClonedComponent::<C>(PhantomData).save(world)
+    }
+
+    fn load(
+        &self,
+        world: &mut World,
+        saved: &Saved,
+        entities: &HashMap<Rollback, Entity>,
+    ) {
+        let Some(saved) = saved.downcast_ref::<HashMap<Rollback, C>>() else {
+            return;
+        };
+
+        for (rollback, entity) in entities {
+            let mut entity_mut = world.entity_mut(*entity);
+
+            if entity_mut.contains::<C>() {
+                continue;
+            }
+
+            if let Some(component) = saved.get(rollback) {
+                entity_mut.insert(component.clone());
+            }
+        }
+    }
+}
+
+struct ClonedResource<R>(PhantomData<fn() -> R>);
+
+impl<R: Resource + Clone> SavedResource for ClonedResource<R> {
+    fn save(&self, world: &World) -> Option<Saved> {
+        world
+            .get_resource::<R>()
+            .map(|resource| Box::new(resource.clone()) as Saved)
+    }
+
+    fn load(&self, world: &mut World, saved: &Saved) {
+        if let Some(resource) = saved.downcast_ref::<R>() {
+            world.insert_resource(resource.clone());
+        }
+    }
+}
+
+/// Saves [`Target`] by [`Rollback`] id, since the targeted ball might be
+/// respawned as a different entity.
+struct TargetComponent;
+
+impl SavedComponent for TargetComponent {
+    fn save(&self, world: &mut World) -> Saved {
+        let rollbacks: HashMap<Entity, Rollback> = world
+            .query::<(Entity, &Rollback)>()
+            .iter(world)
+            .map(|(entity, rollback)| (entity, *rollback))
+            .collect();
+        let saved: HashMap<Rollback, Rollback> = world
+            .query::<(&Rollback, &Target)>()
+            .iter(world)
+            .filter_map(|(rollback, target)| {
+                Some((*rollback, *rollbacks.get(&target.0)?))
+            })
+            .collect();
+
+        Box::new(saved)
+    }
+
+    fn load(
+        &self,
+        world: &mut World,
+        saved: &Saved,
+        entities: &HashMap<Rollback, Entity>,
+    ) {
+        let Some(saved) = saved.downcast_ref::<HashMap<Rollback, Rollback>>()
+        else {
+            return;
+        };
+
+        for (rollback, entity) in entities {
+            let target =
+                saved.get(rollback).and_then(|target| entities.get(target));
+            let mut entity_mut = world.entity_mut(*entity);
+
+            match target {
+                Some(target) => {
+                    entity_mut.insert(Target(*target));
+                },
+                None if entity_mut.contains::<Target>() => {
+                    entity_mut.remove::<Target>();
+                },
+                None => {},
+            }
+        }
+    }
+}
+
+/// The simulated state of the world at the start of a tick.
+pub struct WorldSnapshot {
+    /// Every [`Rollback`] entity and its parent.
+    entities: Vec<(Rollback, Option<Entity>)>,
+    components: Vec<Saved>,
+    resources: Vec<Option<Saved>>,
+}
+
+impl WorldSnapshot {
+    pub fn save(world: &mut World) -> Self {
+        world.resource_scope(|world, registry: Mut<RollbackRegistry>| {
+            let entities = world
+                .query::<(&Rollback, Option<&Parent>)>()
+                .iter(world)
+                .map(|(rollback, parent)| (*rollback, parent.map(Parent::get)))
+                .collect();
+
+            Self {
+                entities,
+                components: registry
+                    .components
+                    .iter()
+                    .map(|component| component.save(world))
+                    .collect(),
+                resources: registry
+                    .resources
+                    .iter()
+                    .map(|resource| resource.save(world))
+                    .collect(),
+            }
+        })
+    }
+
+    /// Puts the world back the way it was, despawning entities that were
+    /// spawned since and respawning ones that were despawned.
+    pub fn restore(&self, world: &mut World) {
+        let current: HashMap<Rollback, Entity> = world
+            .query::<(Entity, &Rollback)>()
+            .iter(world)
+            .map(|(entity, rollback)| (*rollback, entity))
+            .collect();
+        let saved: HashMap<Rollback, Option<Entity>> =
+            self.entities.iter().copied().collect();
+
+        for (rollback, entity) in &current {
+            if !saved.contains_key(rollback) {
+                if let Ok(entity_mut) = world.get_entity_mut(*entity) {
+                    entity_mut.despawn_recursive();
+                }
+            }
+        }
+
+        let mut entities = HashMap::default();
+
+        for (rollback, parent) in &self.entities {
+            let entity = current
+                .get(rollback)
+                .copied()
+                .filter(|entity| world.get_entity(*entity).is_ok())
+                .unwrap_or_else(|| {
+                    let mut entity_mut = world.spawn(*rollback);
+
+                    if let Some(parent) = parent {
+                        entity_mut.set_parent(*parent);
+                    }
+
+                    entity_mut.id()
+                });
+
+            entities.insert(*rollback, entity);
+        }
+
+        world.resource_scope(|world, registry: Mut<RollbackRegistry>| {
+            for (component, saved) in
+                registry.components.iter().zip(&self.components)
+            {
+                component.load(world, saved, &entities);
+            }
+
+            for (resource, saved) in
+                registry.resources.iter().zip(&self.resources)
+            {
+                if let Some(saved) = saved {
+                    resource.load(world, saved);
+                }
+            }
+        });
+    }
+}
+
+/// Hashes the simulated state of every [`Rollback`] entity, so peers can
+/// compare their worlds after the same tick.
+pub fn checksum(world: &mut World) -> u64 {
+    let mut entities: Vec<_> = world
+        .query::<(
+            &Rollback,
+            &Transform,
+            Option<&Speed>,
+            Option<&Direction>,
+            Option<&StoppingDistance>,
+            Option<&Force>,
+            Option<&HitPoints>,
+            Option<&FadeTimer>,
+        )>()
+        .iter(world)
+        .collect();
+    let mut hasher = DefaultHasher::new();
+
+    entities.sort_by_key(|(rollback, ..)| **rollback);
+
+    for (
+        rollback,
+        transform,
+        speed,
+        direction,
+        stopping_distance,
+        force,
+        hit_points,
+        fade_timer,
+    ) in entities
+    {
+        rollback.hash(&mut hasher);
+        transform
+            .translation
+            .to_array()
+            .map(f32::to_bits)
+            .hash(&mut hasher);
+        transform
+            .scale
+            .to_array()
+            .map(f32::to_bits)
+            .hash(&mut hasher);
+        speed.map(|speed| speed.0.to_bits()).hash(&mut hasher);
+        direction
+            .map(|direction| direction.0.to_array().map(f32::to_bits))
+            .hash(&mut hasher);
+        stopping_distance
+            .map(|stopping_distance| stopping_distance.0.to_bits())
+            .hash(&mut hasher);
+        force.hash(&mut hasher);
+        hit_points.map(|hit_points| hit_points.0).hash(&mut hasher);
+        fade_timer
+            .map(|fade_timer| fade_timer.0.elapsed().as_nanos())
+            .hash(&mut hasher);
+    }
+
+    hasher.finish()
+}
@@ -0,0 +1,345 @@
+use bevy::{prelude::*, utils::HashMap};
+use leafwing_input_manager::prelude::*;
+use serde::{Deserialize, Serialize};
+use strum::IntoEnumIterator;
+
+use crate::{
+    components::{CrabAction, KeyboardLayout, PlayerDevice, PlayerSlot},
+    ui::MenuAction,
+};
+
+/// A key or gamepad button that can be bound to an action.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+pub enum BindingInput {
+    Key(KeyCode),
+    Button(GamepadButton),
+}
+
+/// Identifies a single rebindable input in [`ControlBindings`].
+#[derive(Clone, Copy, Debug, Eq, Hash, PartialEq)]
+pub enum BindingSlot {
+    CrabKey(KeyboardLayout, CrabAction),
+    CrabButton(CrabAction),
+    MenuKey(MenuAction),
+    MenuButton(MenuAction),
+}
+
+impl BindingSlot {
+    /// Whether this slot's input is read while a round is being played.
+    pub fn is_active_during_gameplay(&self) -> bool {
+        use MenuAction::*;
+
+        match self {
+            Self::CrabKey(..) | Self::CrabButton(_) => true,
+            Self::MenuKey(action) | Self::MenuButton(action) => {
+                matches!(action, PauseUnpause | ReturnToStartMenu | Exit)
+            },
+        }
+    }
+
+    /// Whether this slot's input is read while navigating menus.
+    pub fn is_active_in_menus(&self) -> bool {
+        matches!(self, Self::MenuKey(_) | Self::MenuButton(_))
+    }
+
+    /// Whether two slots can be triggered at the same time, and so
+    /// shouldn't share an input.
+    pub fn overlaps(&self, other: &Self) -> bool {
+        (self.is_active_during_gameplay() && other.is_active_during_gameplay())
+            || (self.is_active_in_menus() && other.is_active_in_menus())
+    }
+
+    /// Whether the slot expects a key or a gamepad button.
+    pub fn accepts(&self, input: &BindingInput) -> bool {
+        match input {
+            BindingInput::Key(_) => {
+                matches!(self, Self::CrabKey(..) | Self::MenuKey(_))
+            },
+            BindingInput::Button(_) => {
+                matches!(self, Self::CrabButton(_) | Self::MenuButton(_))
+            },
+        }
+    }
+}
+
+/// The crab actions that can be rebound, in display order.
+pub const REBINDABLE_CRAB_ACTIONS: [CrabAction; 6] = [
+    CrabAction::MoveUp,
+    CrabAction::MoveDown,
+    CrabAction::MoveLeft,
+    CrabAction::MoveRight,
+    CrabAction::LeftTrigger,
+    CrabAction::RightTrigger,
+];
+
+/// The choices for which player slot the pointer controls, in display order.
+pub const POINTER_SLOTS: [Option<PlayerSlot>; 5] = [
+    None,
+    Some(PlayerSlot::One),
+    Some(PlayerSlot::Two),
+    Some(PlayerSlot::Three),
+    Some(PlayerSlot::Four),
+];
+
+/// The menu actions that must always have a key and a button, so every menu
+/// can be navigated, chosen from and backed out of.
+pub const REQUIRED_MENU_ACTIONS: [MenuAction; 4] = [
+    MenuAction::Accept,
+    MenuAction::Up,
+    MenuAction::Down,
+    MenuAction::ReturnToStartMenu,
+];
+
+/// Keyboard and gamepad bindings for every [`CrabAction`] and [`MenuAction`].
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct ControlBindings {
+    pub crab_keys: HashMap<KeyboardLayout, HashMap<CrabAction, KeyCode>>,
+    pub crab_buttons: HashMap<CrabAction, GamepadButton>,
+    pub menu_keys: HashMap<MenuAction, KeyCode>,
+    pub menu_buttons: HashMap<MenuAction, GamepadButton>,
+
+    /// The player slot the mouse or touch screen controls, if any.
+    pub pointer_slot: Option<PlayerSlot>,
+}
+
+impl Default for ControlBindings {
+    fn default() -> Self {
+        use CrabAction::*;
+
+        Self {
+            crab_keys: KeyboardLayout::iter()
+                .map(|layout| {
+                    (layout, layout.default_bindings().into_iter().collect())
+                })
+                .collect(),
+            crab_buttons: HashMap::from([
+                (MoveUp, GamepadButton::DPadUp),
+                (MoveDown, GamepadButton::DPadDown),
+                (MoveLeft, GamepadButton::DPadLeft),
+                (MoveRight, GamepadButton::DPadRight),
+                (LeftTrigger, GamepadButton::LeftTrigger2),
+                (RightTrigger, GamepadButton::RightTrigger2),
+            ]),
+            menu_keys: MenuAction::default_keys().into_iter().collect(),
+            menu_buttons: MenuAction::default_buttons().into_iter().collect(),
+            pointer_slot: None,
+        }
+    }
+}
+
+impl ControlBindings {
+    /// Every rebindable slot, in display order.
+    pub fn slots() -> impl Iterator<Item = BindingSlot> {
+        let crab_slots =
+            REBINDABLE_CRAB_ACTIONS.into_iter().flat_map(|action| {
+                KeyboardLayout::iter()
+                    .map(move |layout| BindingSlot::CrabKey(layout, action))
+                    .chain([BindingSlot::CrabButton(action)])
+            });
+        let menu_slots = MenuAction::iter().flat_map(|action| {
+            [
+                BindingSlot::MenuKey(action),
+                BindingSlot::MenuButton(action),
+            ]
+        });
+
+        crab_slots.chain(menu_slots)
+    }
+
+    /// Gets the input bound to a slot.
+    pub fn get(&self, slot: BindingSlot) -> Option<BindingInput> {
+        match slot {
+            BindingSlot::CrabKey(layout, action) => self
+                .crab_keys
+                .get(&layout)
+                .and_then(|keys| keys.get(&action))
+                .map(|key| BindingInput::Key(*key)),
+            BindingSlot::CrabButton(action) => self
+                .crab_buttons
+                .get(&action)
+                .map(|button| BindingInput::Button(*button)),
+            BindingSlot::MenuKey(action) => self
+                .menu_keys
+                .get(&action)
+                .map(|key| BindingInput::Key(*key)),
+            BindingSlot::MenuButton(action) => self
+                .menu_buttons
+                .get(&action)
+                .map(|button| BindingInput::Button(*button)),
+        }
+    }
+
+    /// Finds another slot that would be triggered by the same input at the
+    /// same time.
+    pub fn find_conflict(
+        &self,
+        slot: BindingSlot,
+        input: BindingInput,
+    ) -> Option<BindingSlot> {
+        Self::slots().find(|other| {
+            *other != slot
+                && other.overlaps(&slot)
+                && self.get(*other) == Some(input)
+        })
+    }
+
+    /// Binds an input to a slot, as long as it's the right kind of input and
+    /// doesn't conflict with another slot.
+    pub fn set(
+        &mut self,
+        slot: BindingSlot,
+        input: BindingInput,
+    ) -> Result<(), BindingError> {
+        if !slot.accepts(&input) {
+            return Err(BindingError::WrongDevice);
+        }
+
+        if let Some(other) = self.find_conflict(slot, input) {
+            return Err(BindingError::Conflict(other));
+        }
+
+        let mut bindings = self.clone();
+
+        bindings.insert(slot, input);
+
+        if !bindings.can_navigate_menus() {
+            return Err(BindingError::Unreachable);
+        }
+
+        *self = bindings;
+        Ok(())
+    }
+
+    /// Whether every [`REQUIRED_MENU_ACTIONS`] has both a key and a button
+    /// that no other menu action shares.
+    pub fn can_navigate_menus(&self) -> bool {
+        REQUIRED_MENU_ACTIONS.into_iter().all(|action| {
+            [
+                BindingSlot::MenuKey(action),
+                BindingSlot::MenuButton(action),
+            ]
+            .into_iter()
+            .all(|slot| {
+                self.get(slot).is_some_and(|input| {
+                    self.find_conflict(slot, input).is_none()
+                })
+            })
+        })
+    }
+
+    /// Puts the default menu bindings back if the menus can't be navigated
+    /// with the current ones, like from an edited settings file.
+    pub fn restore_menu_navigation(&mut self) {
+        if self.can_navigate_menus() {
+            return;
+        }
+
+        let defaults = Self::default();
+
+        warn!("Controls: Menus can't be navigated, restoring menu bindings");
+        self.menu_keys = defaults.menu_keys;
+        self.menu_buttons = defaults.menu_buttons;
+    }
+
+    fn insert(&mut self, slot: BindingSlot, input: BindingInput) {
+        match (slot, input) {
+            (BindingSlot::CrabKey(layout, action), BindingInput::Key(key)) => {
+                self.crab_keys
+                    .entry(layout)
+                    .or_default()
+                    .insert(action, key);
+            },
+            (BindingSlot::CrabButton(action), BindingInput::Button(button)) => {
+                self.crab_buttons.insert(action, button);
+            },
+            (BindingSlot::MenuKey(action), BindingInput::Key(key)) => {
+                self.menu_keys.insert(action, key);
+            },
+            (BindingSlot::MenuButton(action), BindingInput::Button(button)) => {
+                self.menu_buttons.insert(action, button);
+            },
+            _ => unreachable!(),
+        }
+    }
+
+    /// Builds the input map for a [`PlayerDevice`].
+    ///
+    /// A `sole_player` also accepts every keyboard layout and any unassigned
+    /// gamepad, so single-player modes work with whatever is at hand.
+    pub fn crab_input_map(
+        &self,
+        device: PlayerDevice,
+        sole_player: bool,
+        stick_deadzone: f32,
+    ) -> InputMap<CrabAction> {
+        let mut input_map = InputMap::default();
+
+        match device {
+            PlayerDevice::Keyboard(_) if sole_player => {
+                for keys in self.crab_keys.values() {
+                    input_map.insert_multiple(keys.clone());
+                }
+
+                self.insert_crab_gamepad_bindings(
+                    &mut input_map,
+                    stick_deadzone,
+                );
+            },
+            PlayerDevice::Keyboard(layout) => {
+                if let Some(keys) = self.crab_keys.get(&layout) {
+                    input_map.insert_multiple(keys.clone());
+                }
+            },
+            PlayerDevice::Gamepad(gamepad) => {
+                self.insert_crab_gamepad_bindings(
+                    &mut input_map,
+                    stick_deadzone,
+                );
+                input_map.set_gamepad(gamepad);
+            },
+            // Pointer crabs follow the cursor rather than input actions, and
+            // network crabs have their actions sent by the client.
+            PlayerDevice::Pointer | PlayerDevice::Network(_) => {},
+        }
+
+        input_map
+    }
+
+    /// Builds the input map for navigating menus.
+    pub fn menu_input_map(&self) -> InputMap<MenuAction> {
+        let mut input_map = InputMap::default();
+
+        input_map
+            .insert_multiple(self.menu_keys.clone())
+            .insert_multiple(self.menu_buttons.clone());
+        input_map
+    }
+
+    fn insert_crab_gamepad_bindings(
+        &self,
+        input_map: &mut InputMap<CrabAction>,
+        stick_deadzone: f32,
+    ) {
+        input_map
+            .insert_multiple(self.crab_buttons.clone())
+            .insert_dual_axis(
+                CrabAction::Move,
+                GamepadStick::LEFT.with_circle_deadzone(stick_deadzone),
+            );
+    }
+}
+
+/// Why an input couldn't be bound to a [`BindingSlot`].
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum BindingError {
+    /// A key was given for a gamepad slot, or vice versa.
+    WrongDevice,
+
+    /// The input is already used by another slot that's active at the same
+    /// time.
+    Conflict(BindingSlot),
+
+    /// The menus couldn't be navigated anymore.
+    Unreachable,
+}
@@ -0,0 +1,562 @@
+mod controls;
+mod options;
+
+pub use controls::*;
+pub use options::*;
+
+use std::{fs, path::PathBuf, sync::OnceLock};
+
+use bevy::{
+    audio::{GlobalVolume, Volume},
+    core_pipeline::{
+        experimental::taa::TemporalAntiAliasing, prepass::DeferredPrepass,
+    },
+    pbr::{
+        DefaultOpaqueRendererMethod, ScreenSpaceAmbientOcclusion,
+        ScreenSpaceReflections,
+    },
+    prelude::*,
+    render::{render_resource::WgpuAdapterInfo, renderer::RenderAdapterInfo},
+    window::{PrimaryWindow, WindowResolution},
+};
+use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+use wgpu_types::DeviceType;
+
+use crate::{
+    assets::CompetitorConfig,
+    components::Side,
+    net::is_offline,
+    states::{GameState, MenuScreen},
+};
+
+pub(super) struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        // The window may already have been created from loaded settings.
+        if !app.world().contains_resource::<Settings>() {
+            app.insert_resource(Settings::load());
+        }
+
+        app.init_resource::<GraphicsQuality>()
+            .add_systems(Startup, choose_renderer_method)
+            .add_systems(
+                PostUpdate,
+                (
+                    apply_display_settings,
+                    (update_graphics_quality, apply_graphics_settings).chain(),
+                    apply_audio_settings,
+                    apply_general_settings,
+                    apply_game_speed.run_if(
+                        is_offline.and(any_with_component::<PrimaryWindow>),
+                    ),
+                ),
+            )
+            // Settings are saved once they're done being changed, rather than
+            // on every change.
+            .add_systems(
+                OnExit(MenuScreen::Settings),
+                save_settings.run_if(
+                    resource_changed::<Settings>
+                        .and(any_with_component::<PrimaryWindow>),
+                ),
+            )
+            .add_systems(
+                OnExit(MenuScreen::Controls),
+                save_settings.run_if(
+                    resource_changed::<Settings>
+                        .and(any_with_component::<PrimaryWindow>),
+                ),
+            )
+            // The lobby chooses the pointer's slot on the start menu.
+            .add_systems(
+                OnExit(GameState::StartMenu),
+                save_settings.run_if(
+                    resource_changed::<Settings>
+                        .and(any_with_component::<PrimaryWindow>),
+                ),
+            )
+            .add_systems(
+                Last,
+                save_settings.run_if(
+                    on_event::<AppExit>
+                        .and(resource_changed::<Settings>)
+                        .and(any_with_component::<PrimaryWindow>),
+                ),
+            );
+    }
+}
+
+/// The locale used when the system's language isn't translated.
+pub const DEFAULT_LOCALE: &str = "en";
+
+/// Gets the translated locale closest to the system's language, detected
+/// once at startup.
+pub fn system_locale() -> &'static str {
+    static SYSTEM_LOCALE: OnceLock<&'static str> = OnceLock::new();
+
+    SYSTEM_LOCALE.get_or_init(|| {
+        // Match "de-AT" or "de_AT.UTF-8" to "de".
+        let language = sys_locale::get_locale()
+            .and_then(|locale| {
+                locale.split(['-', '_', '.']).next().map(str::to_lowercase)
+            })
+            .unwrap_or_default();
+        let locale = rust_i18n::available_locales!()
+            .into_iter()
+            .find(|locale| *locale == language)
+            .unwrap_or(DEFAULT_LOCALE);
+
+        info!("Settings: System language {language:?}, using {locale}");
+        locale
+    })
+}
+
+/// The quality preset chosen with `--quality`, which takes precedence over
+/// the settings.
+#[derive(Clone, Copy, Debug, Resource)]
+pub struct QualityOverride(pub QualityPreset);
+
+/// The quality preset the game is rendering with, from the command line, the
+/// settings, or detected from the graphics adapter.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Resource)]
+pub struct GraphicsQuality(pub QualityPreset);
+
+/// User preferences that persist between sessions.
+#[derive(
+    Clone, Debug, Default, Deserialize, PartialEq, Resource, Serialize,
+)]
+#[serde(default)]
+pub struct Settings {
+    pub controls: ControlBindings,
+    pub display: DisplaySettings,
+    pub graphics: GraphicsSettings,
+    pub audio: AudioSettings,
+    pub general: GeneralSettings,
+    pub accessibility: AccessibilitySettings,
+}
+
+impl Settings {
+    /// The locale all text should be shown in, following the system's
+    /// language unless one is chosen.
+    pub fn locale(&self) -> &str {
+        self.general
+            .language
+            .as_deref()
+            .unwrap_or_else(|| system_locale())
+    }
+
+    /// The color the competitor on a side is shown in, after any colorblind
+    /// palette.
+    pub fn competitor_color(
+        &self,
+        side: Side,
+        competitor: &CompetitorConfig,
+    ) -> Color {
+        self.accessibility
+            .palette
+            .side_color(side)
+            .unwrap_or_else(|| {
+                Srgba::hex(&competitor.color).unwrap_or(Srgba::RED).into()
+            })
+    }
+
+    /// Whether the camera sways, which reduced motion always stops.
+    pub fn camera_sways(&self) -> bool {
+        self.general.camera_sway && !self.accessibility.reduced_motion
+    }
+
+    /// Whether the camera shakes on goals, which reduced motion always stops.
+    pub fn camera_shakes(&self) -> bool {
+        self.general.camera_shake && !self.accessibility.reduced_motion
+    }
+
+    /// Creates the primary window as the display settings describe it.
+    pub fn primary_window(&self) -> Window {
+        let DisplaySettings {
+            window_mode,
+            resolution,
+            ..
+        } = &self.display;
+
+        Window {
+            title: t!("ui.window.title").to_string(),
+            mode: window_mode.window_mode(),
+            present_mode: self.display.present_mode(),
+            position: WindowPosition::Centered(MonitorSelection::Primary),
+            resolution: WindowResolution::new(
+                resolution.x as f32,
+                resolution.y as f32,
+            ),
+            resize_constraints: WindowResizeConstraints {
+                min_height: 640.0,
+                min_width: 640.0,
+                ..default()
+            },
+            fit_canvas_to_parent: true,
+            ..default()
+        }
+    }
+
+    /// Where the settings file is stored for the current user.
+    ///
+    /// There's none if the platform has no config directory, in which case
+    /// settings last only as long as the game is running.
+    pub fn path() -> Option<PathBuf> {
+        dirs::config_dir().map(|config_dir| {
+            config_dir
+                .join(env!("CARGO_PKG_NAME"))
+                .join("settings.yaml")
+        })
+    }
+
+    /// Reads the settings file, falling back to defaults if it's missing or
+    /// invalid.
+    pub fn load() -> Self {
+        let Some(path) = Self::path() else {
+            warn!("Settings: No config directory, using defaults");
+            return Self::default();
+        };
+        let Ok(contents) = fs::read_to_string(&path) else {
+            info!("Settings: Using defaults");
+            return Self::default();
+        };
+
+        match Self::from_yaml(&contents) {
+            Ok(settings) => {
+                info!("Settings: Loaded from {path:?}");
+                settings
+            },
+            Err(error) => {
+                warn!("Settings: Failed to parse {path:?}: {error}");
+                Self::default()
+            },
+        }
+    }
+
+    /// Reads settings the way they're written to the settings file, with
+    /// defaults for anything that's missing.
+    pub fn from_yaml(contents: &str) -> Result<Self, serde_yaml::Error> {
+        let mut settings = serde_yaml::from_str::<Self>(contents)?;
+
+        settings.controls.restore_menu_navigation();
+        Ok(settings)
+    }
+
+    /// Writes settings the way they're saved to the settings file.
+    pub fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        serde_yaml::to_string(self)
+    }
+
+    /// Writes the settings file, creating its directory if needed.
+    pub fn save(&self) {
+        let Some(path) = Self::path() else {
+            warn!("Settings: No config directory to save to");
+            return;
+        };
+        let result = self
+            .to_yaml()
+            .map_err(|error| error.to_string())
+            .and_then(|contents| {
+                if let Some(parent) = path.parent() {
+                    fs::create_dir_all(parent)
+                        .map_err(|error| error.to_string())?;
+                }
+
+                fs::write(&path, contents).map_err(|error| error.to_string())
+            });
+
+        match result {
+            Ok(()) => info!("Settings: Saved to {path:?}"),
+            Err(error) => warn!("Settings: Failed to save {path:?}: {error}"),
+        }
+    }
+}
+
+fn apply_display_settings(
+    settings: Res<Settings>,
+    mut windows_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    let Ok(mut window) = windows_query.get_single_mut() else {
+        return;
+    };
+    let DisplaySettings {
+        window_mode,
+        resolution,
+        ..
+    } = &settings.display;
+    let mode = window_mode.window_mode();
+    let present_mode = settings.display.present_mode();
+    let resolution = resolution.as_vec2();
+
+    if window.mode != mode {
+        window.mode = mode;
+    }
+
+    if window.present_mode != present_mode {
+        window.present_mode = present_mode;
+    }
+
+    if *window_mode == WindowModeSetting::Windowed
+        && window.resolution.size() != resolution
+    {
+        window.resolution.set(resolution.x, resolution.y);
+    }
+}
+
+fn update_graphics_quality(
+    settings: Res<Settings>,
+    quality_override: Option<Res<QualityOverride>>,
+    adapter_info: Option<Res<RenderAdapterInfo>>,
+    mut graphics_quality: ResMut<GraphicsQuality>,
+) {
+    let preset = quality_preset(
+        &settings,
+        quality_override.as_deref(),
+        adapter_info.as_deref(),
+    );
+
+    if graphics_quality.set_if_neq(GraphicsQuality(preset)) {
+        info!("Settings: Graphics quality {preset:?}");
+    }
+}
+
+/// Renders opaque materials the way the quality preset calls for, before any
+/// of them are prepared. The renderer can't be switched once running, so a
+/// new preset only changes it on the next launch.
+fn choose_renderer_method(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    quality_override: Option<Res<QualityOverride>>,
+    adapter_info: Option<Res<RenderAdapterInfo>>,
+) {
+    let preset = quality_preset(
+        &settings,
+        quality_override.as_deref(),
+        adapter_info.as_deref(),
+    );
+
+    commands.insert_resource(if preset.deferred() {
+        DefaultOpaqueRendererMethod::deferred()
+    } else {
+        DefaultOpaqueRendererMethod::forward()
+    });
+}
+
+/// Gets the quality preset from the command line, then the settings, and
+/// otherwise detects it from the graphics adapter.
+fn quality_preset(
+    settings: &Settings,
+    quality_override: Option<&QualityOverride>,
+    adapter_info: Option<&RenderAdapterInfo>,
+) -> QualityPreset {
+    match (quality_override, settings.graphics.quality) {
+        (Some(quality_override), _) => quality_override.0,
+        (None, Some(preset)) => preset,
+        (None, None) => detect_quality_preset(adapter_info),
+    }
+}
+
+/// Picks the cheapest preset for software renderers, which struggle with
+/// any post effects, and the default one for everything else.
+fn detect_quality_preset(
+    adapter_info: Option<&RenderAdapterInfo>,
+) -> QualityPreset {
+    match adapter_info {
+        Some(adapter_info) if is_software_adapter(adapter_info) => {
+            QualityPreset::Low
+        },
+        _ => QualityPreset::default(),
+    }
+}
+
+/// Checks if an adapter renders on the CPU.
+fn is_software_adapter(adapter_info: &WgpuAdapterInfo) -> bool {
+    adapter_info.device_type == DeviceType::Cpu
+}
+
+fn apply_graphics_settings(
+    mut commands: Commands,
+    settings: Res<Settings>,
+    graphics_quality: Res<GraphicsQuality>,
+    cameras_query: Query<(Entity, Ref<Camera3d>, Has<DeferredPrepass>)>,
+    mut lights_query: Query<&mut DirectionalLight>,
+) {
+    let GraphicsSettings {
+        ambient_occlusion,
+        reflections,
+        temporal_anti_aliasing,
+        ..
+    } = settings.graphics;
+    let preset = graphics_quality.0;
+    let changed = settings.is_changed() || graphics_quality.is_changed();
+
+    for (entity, camera, is_deferred) in &cameras_query {
+        if !changed && !camera.is_added() {
+            continue;
+        }
+
+        let mut camera_commands = commands.entity(entity);
+
+        match preset.ambient_occlusion().filter(|_| ambient_occlusion) {
+            Some(quality_level) => {
+                camera_commands.insert(ScreenSpaceAmbientOcclusion {
+                    quality_level,
+                    ..default()
+                });
+            },
+            None => {
+                camera_commands.remove::<ScreenSpaceAmbientOcclusion>();
+            },
+        }
+
+        if reflections && preset.reflections() {
+            camera_commands.insert(ScreenSpaceReflections::default());
+        } else {
+            camera_commands.remove::<ScreenSpaceReflections>();
+        }
+
+        if temporal_anti_aliasing && preset.temporal_anti_aliasing() {
+            camera_commands.insert(TemporalAntiAliasing::default());
+        } else {
+            camera_commands.remove::<TemporalAntiAliasing>();
+        }
+
+        // The deferred renderer can't multisample, even once reflections that
+        // needed it are turned off.
+        camera_commands.insert(if is_deferred {
+            Msaa::Off
+        } else {
+            preset.msaa()
+        });
+    }
+
+    for mut light in &mut lights_query {
+        if changed || light.is_added() {
+            light.shadows_enabled = preset.shadows();
+        }
+    }
+}
+
+fn apply_audio_settings(
+    settings: Res<Settings>,
+    global_volume: Option<ResMut<GlobalVolume>>,
+) {
+    if let Some(mut global_volume) =
+        global_volume.filter(|_| settings.is_changed())
+    {
+        global_volume.volume = Volume::new(settings.audio.master_volume);
+    }
+}
+
+fn apply_general_settings(
+    settings: Res<Settings>,
+    mut ui_scale: ResMut<UiScale>,
+    mut windows_query: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    if !settings.is_changed() {
+        return;
+    }
+
+    if *rust_i18n::locale() != *settings.locale() {
+        rust_i18n::set_locale(settings.locale());
+        info!("Settings: Language {}", settings.locale());
+
+        for mut window in &mut windows_query {
+            window.title = t!("ui.window.title").to_string();
+        }
+    }
+
+    if ui_scale.0 != settings.general.ui_scale {
+        ui_scale.0 = settings.general.ui_scale;
+    }
+}
+
+/// Slows the game down for players who need more time, except online where
+/// peers must run at the same speed, and headless where training and testing
+/// always run at full speed.
+fn apply_game_speed(
+    settings: Res<Settings>,
+    mut virtual_time: ResMut<Time<Virtual>>,
+) {
+    let game_speed = settings.accessibility.game_speed;
+
+    if virtual_time.relative_speed() != game_speed {
+        virtual_time.set_relative_speed(game_speed);
+        info!("Settings: Game speed {game_speed}");
+    }
+}
+
+/// Run condition that's true when the language shown has changed since the
+/// last time it was checked.
+pub fn locale_changed(mut last_locale: Local<Option<String>>) -> bool {
+    let locale = (*rust_i18n::locale()).to_string();
+    let changed = last_locale
+        .as_ref()
+        .is_some_and(|last_locale| *last_locale != locale);
+
+    *last_locale = Some(locale);
+    changed
+}
+
+/// Writes changed settings to the settings file, except in headless games,
+/// which only ever use defaults.
+fn save_settings(settings: Res<Settings>) {
+    settings.save();
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::components::PlayerSlot;
+
+    use super::*;
+
+    #[test]
+    fn settings_read_back_the_same_as_they_were_written() {
+        let mut settings = Settings::default();
+
+        settings.controls.pointer_slot = Some(PlayerSlot::Two);
+        settings.display.vsync = false;
+        settings.graphics.quality = Some(QualityPreset::Low);
+        settings.audio.sfx_volume = 0.3;
+        settings.general.language = Some("de".into());
+        settings.general.camera_mode = CameraMode::TopDown;
+        settings.accessibility.palette = ColorPalette::RedGreenSafe;
+        settings.accessibility.game_speed = 0.75;
+
+        let contents = settings.to_yaml().unwrap();
+
+        assert_eq!(Settings::from_yaml(&contents).unwrap(), settings);
+    }
+
+    #[test]
+    fn settings_missing_from_the_file_are_defaults() {
+        let settings =
+            Settings::from_yaml("audio:\n  sfx_volume: 0.5\n").unwrap();
+
+        assert_eq!(settings.audio.sfx_volume, 0.5);
+        assert_eq!(
+            settings.audio.music_volume,
+            AudioSettings::default().music_volume
+        );
+        assert_eq!(settings.controls, ControlBindings::default());
+        assert_eq!(settings.general, GeneralSettings::default());
+    }
+
+    #[test]
+    fn menu_bindings_are_restored_if_menus_cant_be_navigated() {
+        let settings =
+            Settings::from_yaml("controls:\n  menu_keys: {}\n").unwrap();
+
+        assert!(settings.controls.can_navigate_menus());
+        assert_eq!(
+            settings.controls.menu_keys,
+            ControlBindings::default().menu_keys
+        );
+    }
+}
@@ -0,0 +1,367 @@
+use bevy::{
+    pbr::ScreenSpaceAmbientOcclusionQualityLevel,
+    prelude::*,
+    window::{PresentMode, WindowMode},
+};
+use serde::{Deserialize, Serialize};
+
+use crate::components::Side;
+
+/// The window resolutions that can be picked from the settings screen.
+pub const RESOLUTIONS: [UVec2; 6] = [
+    UVec2::new(640, 640),
+    UVec2::new(800, 800),
+    UVec2::new(1024, 768),
+    UVec2::new(1280, 720),
+    UVec2::new(1600, 900),
+    UVec2::new(1920, 1080),
+];
+
+/// The UI scales that can be picked from the settings screen.
+pub const UI_SCALES: [f32; 5] = [0.75, 1.0, 1.25, 1.5, 2.0];
+
+/// How the game's window is shown.
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize,
+)]
+pub enum WindowModeSetting {
+    #[default]
+    Windowed,
+    Borderless,
+    Fullscreen,
+}
+
+impl WindowModeSetting {
+    pub fn window_mode(&self) -> WindowMode {
+        match self {
+            Self::Windowed => WindowMode::Windowed,
+            Self::Borderless => {
+                WindowMode::BorderlessFullscreen(MonitorSelection::Current)
+            },
+            Self::Fullscreen => {
+                WindowMode::Fullscreen(MonitorSelection::Current)
+            },
+        }
+    }
+
+    /// The next mode in display order, wrapping around.
+    pub fn cycle(&self, step: i32) -> Self {
+        let modes = [Self::Windowed, Self::Borderless, Self::Fullscreen];
+        let index = modes.iter().position(|mode| mode == self).unwrap_or(0);
+
+        modes[(index as i32 + step).rem_euclid(modes.len() as i32) as usize]
+    }
+}
+
+/// How the camera looks at the beach.
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize,
+)]
+pub enum CameraMode {
+    /// Looks over the beach from one side, swaying back and forth.
+    #[default]
+    Sway,
+
+    /// Looks straight down without perspective.
+    TopDown,
+
+    /// Turns so the local player's goal is always at the bottom.
+    PlayerAtBottom,
+
+    /// Moves to keep every ball in play in view.
+    FollowBalls,
+}
+
+impl CameraMode {
+    /// The next mode in display order, wrapping around.
+    pub fn cycle(&self, step: i32) -> Self {
+        let modes = [
+            Self::Sway,
+            Self::TopDown,
+            Self::PlayerAtBottom,
+            Self::FollowBalls,
+        ];
+        let index = modes.iter().position(|mode| mode == self).unwrap_or(0);
+
+        modes[(index as i32 + step).rem_euclid(modes.len() as i32) as usize]
+    }
+}
+
+/// Window options.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct DisplaySettings {
+    pub window_mode: WindowModeSetting,
+
+    /// The size of the window while it's windowed.
+    pub resolution: UVec2,
+    pub vsync: bool,
+}
+
+impl Default for DisplaySettings {
+    fn default() -> Self {
+        Self {
+            window_mode: WindowModeSetting::Windowed,
+            resolution: RESOLUTIONS[0],
+            vsync: true,
+        }
+    }
+}
+
+impl DisplaySettings {
+    pub fn present_mode(&self) -> PresentMode {
+        if self.vsync {
+            PresentMode::AutoVsync
+        } else {
+            PresentMode::AutoNoVsync
+        }
+    }
+}
+
+/// The quality presets that can be picked from the settings screen, where
+/// `None` detects one for the graphics adapter.
+pub const QUALITY_PRESETS: [Option<QualityPreset>; 5] = [
+    None,
+    Some(QualityPreset::Low),
+    Some(QualityPreset::Medium),
+    Some(QualityPreset::High),
+    Some(QualityPreset::Ultra),
+];
+
+/// How much rendering work the game does, from the cheapest to the prettiest.
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize,
+)]
+pub enum QualityPreset {
+    Low,
+    Medium,
+    #[default]
+    High,
+    Ultra,
+}
+
+impl QualityPreset {
+    /// Gets the preset with the given name, like the `--quality` argument
+    /// takes.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_lowercase().as_str() {
+            "low" => Some(Self::Low),
+            "medium" => Some(Self::Medium),
+            "high" => Some(Self::High),
+            "ultra" => Some(Self::Ultra),
+            _ => None,
+        }
+    }
+
+    /// Whether opaque materials use the deferred renderer, which only takes
+    /// effect on the next launch.
+    pub fn deferred(&self) -> bool {
+        matches!(self, Self::High | Self::Ultra)
+    }
+
+    pub fn ambient_occlusion(
+        &self,
+    ) -> Option<ScreenSpaceAmbientOcclusionQualityLevel> {
+        match self {
+            Self::Low | Self::Medium => None,
+            Self::High => Some(ScreenSpaceAmbientOcclusionQualityLevel::Medium),
+            Self::Ultra => Some(ScreenSpaceAmbientOcclusionQualityLevel::High),
+        }
+    }
+
+    pub fn reflections(&self) -> bool {
+        matches!(self, Self::Ultra)
+    }
+
+    pub fn temporal_anti_aliasing(&self) -> bool {
+        matches!(self, Self::High | Self::Ultra)
+    }
+
+    pub fn shadows(&self) -> bool {
+        !matches!(self, Self::Low)
+    }
+
+    /// Multisampling, for the presets without temporal anti-aliasing or the
+    /// deferred renderer, which don't work with it.
+    pub fn msaa(&self) -> Msaa {
+        match self {
+            Self::Medium => Msaa::Sample4,
+            _ => Msaa::Off,
+        }
+    }
+
+    pub fn scrolls_ocean(&self) -> bool {
+        !matches!(self, Self::Low)
+    }
+
+    /// How many of the configured particles get spawned.
+    pub fn particle_density(&self) -> f32 {
+        match self {
+            Self::Low => 0.25,
+            Self::Medium => 0.5,
+            Self::High | Self::Ultra => 1.0,
+        }
+    }
+}
+
+/// Post-processing effects on the game's camera.
+///
+/// Effects turned off here stay off whatever the quality preset.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct GraphicsSettings {
+    /// The quality preset, or `None` to pick one for the graphics adapter.
+    pub quality: Option<QualityPreset>,
+    pub ambient_occlusion: bool,
+    pub reflections: bool,
+    pub temporal_anti_aliasing: bool,
+
+    /// Spawns fewer particles, for slow machines and busy game modes.
+    pub low_effects: bool,
+}
+
+impl Default for GraphicsSettings {
+    fn default() -> Self {
+        Self {
+            quality: None,
+            ambient_occlusion: true,
+            reflections: true,
+            temporal_anti_aliasing: true,
+            low_effects: false,
+        }
+    }
+}
+
+/// Volumes from `0.0` to `1.0`.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct AudioSettings {
+    pub master_volume: f32,
+    pub music_volume: f32,
+    pub sfx_volume: f32,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self {
+            master_volume: 1.0,
+            music_volume: 0.8,
+            sfx_volume: 1.0,
+        }
+    }
+}
+
+/// Options that don't fit anywhere else.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct GeneralSettings {
+    /// The locale for all text, or `None` to use the default one.
+    pub language: Option<String>,
+    pub camera_sway: bool,
+
+    /// Used unless the game mode picks its own.
+    pub camera_mode: CameraMode,
+    pub camera_shake: bool,
+    pub ui_scale: f32,
+}
+
+impl Default for GeneralSettings {
+    fn default() -> Self {
+        Self {
+            language: None,
+            camera_sway: true,
+            camera_mode: CameraMode::Sway,
+            camera_shake: true,
+            ui_scale: 1.0,
+        }
+    }
+}
+
+/// The game speeds that can be picked from the settings screen.
+pub const GAME_SPEEDS: [f32; 5] = [0.5, 0.6, 0.75, 0.9, 1.0];
+
+/// Colors that replace each competitor's color from the game mode.
+#[derive(
+    Clone, Copy, Debug, Default, Deserialize, Eq, PartialEq, Serialize,
+)]
+pub enum ColorPalette {
+    /// The colors from the game mode.
+    #[default]
+    Original,
+
+    /// Okabe-Ito colors, for protanopia and deuteranopia.
+    RedGreenSafe,
+
+    /// Colors for tritanopia.
+    BlueYellowSafe,
+}
+
+impl ColorPalette {
+    /// Gets the color for the competitor on a side, or `None` to keep the
+    /// game mode's colors.
+    ///
+    /// Every side gets its own color, so teammates can still be told apart,
+    /// and none are black or white like the balls.
+    pub fn side_color(&self, side: Side) -> Option<Color> {
+        let colors: [&str; 4] = match self {
+            Self::Original => return None,
+            Self::RedGreenSafe => ["0072B2", "E69F00", "CC79A7", "009E73"],
+            Self::BlueYellowSafe => ["D81B60", "1E88E5", "FFC107", "004D40"],
+        };
+        let hex = colors[side as usize];
+
+        Srgba::hex(hex).ok().map(Color::from)
+    }
+
+    /// The next palette in display order, wrapping around.
+    pub fn cycle(&self, step: i32) -> Self {
+        let palettes =
+            [Self::Original, Self::RedGreenSafe, Self::BlueYellowSafe];
+        let index = palettes
+            .iter()
+            .position(|palette| palette == self)
+            .unwrap_or(0);
+
+        palettes
+            [(index as i32 + step).rem_euclid(palettes.len() as i32) as usize]
+    }
+}
+
+/// Options for players who find colors, motion or speed hard to follow.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+#[serde(default)]
+pub struct AccessibilitySettings {
+    pub palette: ColorPalette,
+
+    /// Shows a shape for each team on crabs and hit points.
+    pub team_symbols: bool,
+    pub high_contrast_ball: bool,
+
+    /// Holds the camera still and turns off shaking and flashing.
+    pub reduced_motion: bool,
+
+    /// How fast the game runs, where `1.0` is full speed.
+    pub game_speed: f32,
+}
+
+impl Default for AccessibilitySettings {
+    fn default() -> Self {
+        Self {
+            palette: ColorPalette::Original,
+            team_symbols: false,
+            high_contrast_ball: false,
+            reduced_motion: false,
+            game_speed: 1.0,
+        }
+    }
+}
+
+impl AccessibilitySettings {
+    pub fn ball_color(&self) -> Color {
+        if self.high_contrast_ball {
+            Color::BLACK
+        } else {
+            Color::WHITE
+        }
+    }
+}
@@ -0,0 +1,499 @@
+use bevy::{
+    audio::{PlaybackMode, Volume},
+    prelude::*,
+    window::PrimaryWindow,
+};
+use rand::prelude::*;
+
+use crate::{
+    assets::{GameAssets, GameConfig},
+    components::{
+        BallBouncedEvent, BallDeflectedEvent, GoalEliminatedEvent,
+        GoalScoredEvent, Obstacle, Player, Speed, Team,
+    },
+    net::{RollbackSession, is_resimulating},
+    settings::Settings,
+    states::GameState,
+    system_params::GameModes,
+    system_sets::{ActiveAfterLoadingSet, SideEffectsSet},
+    ui::{
+        MenuItem, MenuItemActivated, MenuItemAdjusted, Selected, WinningTeam,
+    },
+};
+
+pub(super) struct SoundPlugin;
+
+impl Plugin for SoundPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(GameState::Paused), play_pause_sound)
+            .add_systems(OnExit(GameState::Paused), play_resume_sound)
+            .add_systems(
+                PostUpdate,
+                (
+                    play_ball_sounds,
+                    play_goal_and_elimination_sounds,
+                    play_victory_or_defeat_sound
+                        .run_if(resource_exists_and_changed::<WinningTeam>),
+                    play_menu_move_sound,
+                    crossfade_music_for_game_state
+                        .run_if(any_with_component::<PrimaryWindow>),
+                    despawn_finished_sounds,
+                )
+                    .in_set(ActiveAfterLoadingSet)
+                    .in_set(SideEffectsSet),
+            )
+            .add_observer(play_menu_select_sound)
+            .add_observer(play_menu_adjust_sound)
+            .add_observer(play_sound);
+    }
+}
+
+/// How long a sound entity lives, in case no audio device ever plays it to
+/// the end.
+const SOUND_LIFETIME_SECS: f32 = 3.0;
+
+/// How much the pitch of repeated ball sounds varies, either way.
+const PITCH_VARIATION: f32 = 0.04;
+
+/// How loud the match behind the start menu is, relative to a real one.
+const ATTRACT_MATCH_VOLUME: f32 = 0.5;
+
+/// How much higher the pause sound plays when resuming.
+const RESUME_PITCH: f32 = 1.25;
+
+/// The game's sound effects.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum Sound {
+    Deflect,
+    Bounce,
+    Clack,
+    Goal,
+    Eliminated,
+    Victory,
+    Defeat,
+    MenuMove,
+    MenuSelect,
+    Pause,
+}
+
+impl Sound {
+    fn source(&self, game_assets: &GameAssets) -> Handle<AudioSource> {
+        match self {
+            Self::Deflect => game_assets.sound_deflect.clone(),
+            Self::Bounce => game_assets.sound_bounce.clone(),
+            Self::Clack => game_assets.sound_clack.clone(),
+            Self::Goal => game_assets.sound_goal.clone(),
+            Self::Eliminated => game_assets.sound_eliminated.clone(),
+            Self::Victory => game_assets.sound_victory.clone(),
+            Self::Defeat => game_assets.sound_defeat.clone(),
+            Self::MenuMove => game_assets.sound_menu_move.clone(),
+            Self::MenuSelect => game_assets.sound_menu_select.clone(),
+            Self::Pause => game_assets.sound_pause.clone(),
+        }
+    }
+}
+
+/// The game's music, which loops while in the game states it belongs to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum MusicTrack {
+    Menu,
+    Match,
+}
+
+impl MusicTrack {
+    fn for_game_state(game_state: GameState) -> Option<Self> {
+        match game_state {
+            GameState::Loading => None,
+            GameState::StartMenu => Some(Self::Menu),
+            // The pause sound marks the pause, so the match plays on.
+            GameState::Playing | GameState::Paused => Some(Self::Match),
+        }
+    }
+
+    fn source(&self, game_assets: &GameAssets) -> Handle<AudioSource> {
+        match self {
+            Self::Menu => game_assets.music_menu.clone(),
+            Self::Match => game_assets.music_match.clone(),
+        }
+    }
+}
+
+/// An event fired to play a [`Sound`], where `speed` also raises its pitch,
+/// and `volume` is scaled by the sound effects volume setting.
+#[derive(Clone, Debug, Event)]
+pub struct PlaySound {
+    pub sound: Sound,
+    pub speed: f32,
+    pub volume: f32,
+}
+
+impl PlaySound {
+    pub fn new(sound: Sound) -> Self {
+        Self {
+            sound,
+            speed: 1.0,
+            volume: 1.0,
+        }
+    }
+}
+
+/// Marks a playing sound effect entity, which is despawned when its timer
+/// runs out.
+#[derive(Component, Debug)]
+pub struct SoundLifetime(pub Timer);
+
+/// A looping [`MusicTrack`] entity, which fades in while it belongs to the
+/// current game state and out while it doesn't.
+#[derive(Component, Debug)]
+pub struct Music {
+    pub track: MusicTrack,
+
+    /// How far faded in the track is, from silent at 0 to full at 1.
+    pub fade: f32,
+}
+
+fn play_sound(
+    trigger: Trigger<PlaySound>,
+    mut commands: Commands,
+    settings: Res<Settings>,
+    game_assets: Res<GameAssets>,
+    rollback_session: Option<Res<RollbackSession>>,
+    windows_query: Query<(), With<PrimaryWindow>>,
+) {
+    // Headless games, like training environments, stay silent, and ticks
+    // that are played again were already heard the first time.
+    if windows_query.is_empty() || is_resimulating(rollback_session) {
+        return;
+    }
+
+    let PlaySound {
+        sound,
+        speed,
+        volume,
+    } = trigger.event();
+
+    commands.spawn((
+        SoundLifetime(Timer::from_seconds(
+            SOUND_LIFETIME_SECS,
+            TimerMode::Once,
+        )),
+        AudioPlayer(sound.source(&game_assets)),
+        PlaybackSettings {
+            mode: PlaybackMode::Despawn,
+            volume: Volume::new(volume * settings.audio.sfx_volume),
+            speed: *speed,
+            ..default()
+        },
+    ));
+}
+
+/// Plays sounds for balls hitting crabs, poles, barriers and each other,
+/// higher and louder the faster the ball is going.
+fn play_ball_sounds(
+    mut commands: Commands,
+    mut ball_deflected_events: EventReader<BallDeflectedEvent>,
+    mut ball_bounced_events: EventReader<BallBouncedEvent>,
+    game_modes: GameModes,
+    game_assets: Res<GameAssets>,
+    game_configs: Res<Assets<GameConfig>>,
+    balls_query: Query<&Speed>,
+) {
+    let game_config = game_configs.get(&game_assets.game_config).unwrap();
+    let deflections = ball_deflected_events
+        .read()
+        .map(|event| (event.ball_entity, Sound::Deflect));
+    let bounces = ball_bounced_events.read().map(|event| {
+        let sound = match event.obstacle {
+            Obstacle::Ball => Sound::Clack,
+            Obstacle::Barrier | Obstacle::Pole => Sound::Bounce,
+        };
+
+        (event.ball_entity, sound)
+    });
+    let match_volume = match_volume(&game_modes);
+    let mut rng = rand::rng();
+
+    for (ball_entity, sound) in deflections.chain(bounces) {
+        let speed_ratio = balls_query.get(ball_entity).map_or(1.0, |speed| {
+            speed.0.abs()
+                / game_config.sound_reference_ball_speed.max(f32::EPSILON)
+        });
+        let variation =
+            rng.random_range(1.0 - PITCH_VARIATION..=1.0 + PITCH_VARIATION);
+
+        commands.trigger(PlaySound {
+            sound,
+            speed: speed_ratio.powf(0.3).clamp(0.75, 1.5) * variation,
+            volume: (0.6 + 0.4 * speed_ratio).clamp(0.4, 1.0) * match_volume,
+        });
+    }
+}
+
+fn play_goal_and_elimination_sounds(
+    mut commands: Commands,
+    mut goal_scored_events: EventReader<GoalScoredEvent>,
+    mut goal_eliminated_events: EventReader<GoalEliminatedEvent>,
+    game_modes: GameModes,
+) {
+    let match_volume = match_volume(&game_modes);
+    let goals = goal_scored_events.read().map(|_| Sound::Goal);
+    let eliminations = goal_eliminated_events.read().map(|_| Sound::Eliminated);
+
+    for sound in goals.chain(eliminations) {
+        commands.trigger(PlaySound {
+            volume: match_volume,
+            ..PlaySound::new(sound)
+        });
+    }
+}
+
+/// Cheers if a local player's team won, or if nobody local was playing, and
+/// commiserates otherwise.
+fn play_victory_or_defeat_sound(
+    mut commands: Commands,
+    winning_team: Res<WinningTeam>,
+    players_query: Query<&Parent, With<Player>>,
+    goals_query: Query<&Team>,
+) {
+    let player_teams: Vec<usize> = players_query
+        .iter()
+        .filter_map(|parent| goals_query.get(parent.get()).ok())
+        .map(|team| team.0)
+        .collect();
+    let has_won =
+        player_teams.is_empty() || player_teams.contains(&winning_team.0);
+
+    commands.trigger(PlaySound::new(if has_won {
+        Sound::Victory
+    } else {
+        Sound::Defeat
+    }));
+}
+
+/// Ticks when the selection moves between items in a menu, but not when a
+/// new menu selects its first item.
+fn play_menu_move_sound(
+    mut commands: Commands,
+    mut removed_selections: RemovedComponents<Selected>,
+    selected_query: Query<(), (Added<Selected>, With<MenuItem>)>,
+) {
+    let has_deselected = removed_selections.read().count() > 0;
+
+    if has_deselected && !selected_query.is_empty() {
+        commands.trigger(PlaySound::new(Sound::MenuMove));
+    }
+}
+
+fn play_menu_select_sound(
+    _trigger: Trigger<MenuItemActivated>,
+    mut commands: Commands,
+) {
+    commands.trigger(PlaySound::new(Sound::MenuSelect));
+}
+
+fn play_menu_adjust_sound(
+    _trigger: Trigger<MenuItemAdjusted>,
+    mut commands: Commands,
+) {
+    commands.trigger(PlaySound::new(Sound::MenuMove));
+}
+
+fn play_pause_sound(mut commands: Commands) {
+    commands.trigger(PlaySound::new(Sound::Pause));
+}
+
+fn play_resume_sound(mut commands: Commands) {
+    commands.trigger(PlaySound {
+        speed: RESUME_PITCH,
+        ..PlaySound::new(Sound::Pause)
+    });
+}
+
+/// Starts the music for the current game state, fading it in while fading
+/// out any other music, at the music volume from the settings.
+fn crossfade_music_for_game_state(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    game_state: Res<State<GameState>>,
+    settings: Res<Settings>,
+    game_assets: Res<GameAssets>,
+    game_configs: Res<Assets<GameConfig>>,
+    mut music_query: Query<(Entity, &mut Music, Option<&AudioSink>)>,
+) {
+    let game_config = game_configs.get(&game_assets.game_config).unwrap();
+    let current_track = MusicTrack::for_game_state(**game_state);
+    let fade_step = time.delta_secs()
+        / game_config.music_crossfade_time_in_secs.max(f32::EPSILON);
+
+    // A track that's still fading out fades back in instead.
+    if let Some(track) = current_track.filter(|track| {
+        !music_query
+            .iter()
+            .any(|(_, music, _)| music.track == *track)
+    }) {
+        commands.spawn((
+            Music { track, fade: 0.0 },
+            AudioPlayer(track.source(&game_assets)),
+            PlaybackSettings::LOOP.with_volume(Volume::new(0.0)),
+        ));
+        info!("Music: Playing {track:?}");
+    }
+
+    for (entity, mut music, sink) in &mut music_query {
+        let is_current = current_track == Some(music.track);
+        let target = if is_current { 1.0 } else { 0.0 };
+
+        music.fade += (target - music.fade).clamp(-fade_step, fade_step);
+
+        if !is_current && music.fade <= 0.0 {
+            commands.entity(entity).despawn_recursive();
+            continue;
+        }
+
+        // No sink means no audio device, so there's nothing to fade.
+        if let Some(sink) = sink {
+            sink.set_volume(
+                settings.audio.master_volume
+                    * settings.audio.music_volume
+                    * music.fade,
+            );
+        }
+    }
+}
+
+/// Cleans up sound effects that never finish, like when there's no audio
+/// device to play them.
+fn despawn_finished_sounds(
+    mut commands: Commands,
+    time: Res<Time<Real>>,
+    mut sounds_query: Query<(Entity, &mut SoundLifetime)>,
+) {
+    for (entity, mut lifetime) in &mut sounds_query {
+        if lifetime.0.tick(time.delta()).finished() {
+            commands.entity(entity).despawn_recursive();
+        }
+    }
+}
+
+/// How loud match sounds play, quieter for the match behind the start menu.
+fn match_volume(game_modes: &GameModes) -> f32 {
+    if game_modes.is_attract_match() {
+        ATTRACT_MATCH_VOLUME
+    } else {
+        1.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::headless::build_headless_app;
+
+    use super::*;
+
+    /// Every sound that was asked to play, even without a window to hear it.
+    #[derive(Debug, Default, Resource)]
+    struct PlayedSounds(Vec<Sound>);
+
+    fn record_played_sound(
+        trigger: Trigger<PlaySound>,
+        mut played_sounds: ResMut<PlayedSounds>,
+    ) {
+        played_sounds.0.push(trigger.event().sound);
+    }
+
+    #[test]
+    fn ball_and_goal_events_play_their_sounds() {
+        let mut app = build_headless_app(Duration::from_millis(16), |app| {
+            app.init_resource::<PlayedSounds>()
+                .add_observer(record_played_sound);
+        });
+        let world = app.world_mut();
+        let ball_entity = world.spawn(Speed(1.0)).id();
+        let crab_entity = world.spawn_empty().id();
+        let goal_entity = world.spawn_empty().id();
+
+        world.send_event(BallDeflectedEvent {
+            crab_entity,
+            ball_entity,
+        });
+
+        for obstacle in [Obstacle::Ball, Obstacle::Barrier, Obstacle::Pole] {
+            world.send_event(BallBouncedEvent {
+                ball_entity,
+                obstacle,
+            });
+        }
+
+        world.send_event(GoalScoredEvent {
+            goal_entity,
+            scored_by: None,
+        });
+        world.send_event(GoalEliminatedEvent(goal_entity));
+        app.update();
+
+        let played_sounds = &app.world().resource::<PlayedSounds>().0;
+        let count = |sound: Sound| {
+            played_sounds
+                .iter()
+                .filter(|played| **played == sound)
+                .count()
+        };
+
+        assert_eq!(played_sounds.len(), 6);
+        assert_eq!(count(Sound::Deflect), 1);
+        assert_eq!(count(Sound::Clack), 1);
+        assert_eq!(count(Sound::Bounce), 2);
+        assert_eq!(count(Sound::Goal), 1);
+        assert_eq!(count(Sound::Eliminated), 1);
+    }
+
+    #[test]
+    fn pausing_plays_the_pause_sound_over_the_match_music() {
+        let mut app = build_headless_app(Duration::from_millis(16), |app| {
+            app.init_resource::<PlayedSounds>()
+                .add_observer(record_played_sound);
+        });
+
+        let music = |app: &mut App| {
+            let world = app.world_mut();
+
+            world
+                .query::<(Entity, &Music)>()
+                .iter(world)
+                .map(|(entity, music)| (entity, music.track, music.fade))
+                .collect::<Vec<_>>()
+        };
+
+        // Music only plays with a window to hear it in.
+        app.world_mut().spawn((Window::default(), PrimaryWindow));
+        app.world_mut()
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::Playing);
+        app.update();
+        app.update();
+
+        let [(match_music, MusicTrack::Match, playing_fade)] =
+            music(&mut app)[..]
+        else {
+            panic!("Expected only the match music while playing");
+        };
+
+        app.world_mut()
+            .resource_mut::<NextState<GameState>>()
+            .set(GameState::Paused);
+        app.update();
+        app.update();
+
+        let [(paused_music, MusicTrack::Match, paused_fade)] =
+            music(&mut app)[..]
+        else {
+            panic!("Expected only the match music while paused");
+        };
+
+        assert_eq!(app.world().resource::<PlayedSounds>().0, [Sound::Pause]);
+        assert_eq!(paused_music, match_music);
+        assert!(paused_fade >= playing_fade);
+    }
+}
@@ -1,33 +1,32 @@
 use std::time::Duration;
 
-use bevy::{
-    core_pipeline::experimental::taa::TemporalAntiAliasing,
-    math::Affine2,
-    pbr::{
-        ScreenSpaceAmbientOcclusion, ScreenSpaceAmbientOcclusionQualityLevel,
-        ScreenSpaceReflections,
-    },
-    prelude::*,
-    utils::HashMap,
-};
+use bevy::{math::Affine2, prelude::*};
 use bevy_ui_anchor::{
     AnchorTarget, AnchorUiNode, HorizontalAnchor, VerticalAnchor,
 };
+use leafwing_input_manager::prelude::InputMap;
 use rand::prelude::*;
 use strum::IntoEnumIterator;
 
 use crate::{
     assets::{CachedAssets, CrabController, GameAssets, GameConfig},
     components::{
-        AI, Acceleration, AnchoredUiCamera, Ball, CircleCollider, Collider,
-        Crab, CrabCollider, DepthCollider, Direction, Fade, FadeDuration,
-        FadeEffect, ForStates, Goal, GoalMouth, HitPoints, HitPointsUi,
-        InsertAfterFadeIn, MaxSpeed, Motion, Player, Pole, RemoveBeforeFadeOut,
-        ScrollingTexture, Side, Speed, StartFading, SwayingCamera, Team,
+        AI, Acceleration, AnchoredUiCamera, Ball, CameraRig, CircleCollider,
+        Collider, Crab, CrabAction, CrabCollider, DepthCollider, Direction,
+        Fade, FadeDuration, FadeEffect, ForStates, Goal, GoalMouth, HitPoints,
+        HitPointsUi, InsertAfterFadeIn, MaxSpeed, Motion, ParticleEffect,
+        Player, PlayerDevice, PlayerSlots, PointerControlled, Pole,
+        RemoteControlled, RemoveBeforeFadeOut, ScrollingTexture, Side,
+        SpawnParticles, Speed, StartFading, Team, TeamSymbol,
     },
-    states::GameState,
+    net::is_offline,
+    settings::Settings,
+    states::{GameState, MenuScreen},
     system_params::GameModes,
-    system_sets::ActiveDuringGameplaySet,
+    system_sets::{
+        ActiveAfterLoadingSet, ActiveDuringMatchSet, AuthoritativeSet,
+    },
+    ui::AttractMatch,
 };
 
 pub const LEVEL_CENTER_POINT: Vec3 = Vec3::ZERO;
@@ -37,21 +36,38 @@ pub(super) struct SpawnersPlugin;
 
 impl Plugin for SpawnersPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(
-            OnExit(GameState::Loading),
-            (spawn_level, reset_team_and_hit_points).chain(),
-        )
-        .add_systems(
-            OnExit(GameState::StartMenu),
-            (spawn_crabs_for_each_side, reset_team_and_hit_points),
-        )
-        .add_systems(
-            Update,
-            spawn_balls_sequentially_up_to_max_count
-                .in_set(ActiveDuringGameplaySet),
-        )
-        .add_observer(spawn_pole_in_a_goal)
-        .add_observer(spawn_ui_message);
+        app.init_resource::<SpawnerRng>()
+            .add_systems(
+                OnExit(GameState::Loading),
+                (spawn_level, reset_team_and_hit_points).chain(),
+            )
+            .add_systems(
+                OnExit(GameState::StartMenu),
+                (spawn_crabs_for_each_side, reset_team_and_hit_points),
+            )
+            .add_systems(
+                Update,
+                (spawn_crabs_for_each_side, reset_team_and_hit_points)
+                    .in_set(ActiveAfterLoadingSet)
+                    .run_if(resource_exists_and_changed::<AttractMatch>),
+            )
+            .add_systems(
+                Update,
+                (
+                    spawn_balls_sequentially_up_to_max_count
+                        .in_set(ActiveDuringMatchSet)
+                        .in_set(AuthoritativeSet),
+                    recolor_crabs_and_balls
+                        .in_set(ActiveAfterLoadingSet)
+                        .run_if(resource_changed::<Settings>),
+                    update_player_input_maps_from_settings
+                        .in_set(ActiveAfterLoadingSet)
+                        .run_if(resource_changed::<Settings>)
+                        .run_if(is_offline),
+                ),
+            )
+            .add_observer(spawn_pole_in_a_goal)
+            .add_observer(spawn_ui_message);
     }
 }
 
@@ -60,6 +76,18 @@ pub struct Beach {
     pub width: f32,
 }
 
+/// Random number generator used when spawning entities.
+///
+/// Can be replaced with a seeded generator to make a round reproducible.
+#[derive(Clone, Debug, Resource)]
+pub struct SpawnerRng(pub SmallRng);
+
+impl Default for SpawnerRng {
+    fn default() -> Self {
+        Self(SmallRng::from_os_rng())
+    }
+}
+
 /// An event fired to spawn a [`Pole`] in a [`Goal`].
 #[derive(Debug, Event)]
 pub struct SpawnPole {
@@ -72,6 +100,7 @@ pub struct SpawnPole {
 pub struct SpawnUiMessage {
     pub message: String,
     pub game_state: GameState,
+    pub menu_screen: Option<MenuScreen>,
 }
 
 fn spawn_level(
@@ -83,19 +112,13 @@ fn spawn_level(
 ) {
     let game_config = game_configs.get(&game_assets.game_config).unwrap();
 
-    // Camera
+    // Camera, with post effects added from the graphics settings.
     commands.spawn((
         Camera3d::default(),
         Msaa::Off,
-        TemporalAntiAliasing::default(),
-        ScreenSpaceReflections::default(),
-        ScreenSpaceAmbientOcclusion {
-            quality_level: ScreenSpaceAmbientOcclusionQualityLevel::High,
-            ..default()
-        },
         IsDefaultUiCamera,
         AnchoredUiCamera,
-        SwayingCamera {
+        CameraRig {
             target: LEVEL_CENTER_POINT,
             starting_position: Vec3::new(0., 2., 1.5),
             up_direction: Vec3::Y,
@@ -104,11 +127,10 @@ fn spawn_level(
         },
     ));
 
-    // Light
+    // Light, with shadows from the graphics quality.
     commands.spawn((
         DirectionalLight {
             illuminance: 2_500.0,
-            // shadows_enabled: true,
             ..default()
         },
         Transform::from_matrix(Mat4::from_euler(
@@ -169,41 +191,6 @@ fn spawn_level(
         materials.add(Color::Srgba(Srgba::hex("750000").unwrap()));
 
     let num_sides = Side::iter().len();
-    let hp_ui_configs = HashMap::from([
-        (
-            Side::Bottom,
-            (
-                Some(0.25 * Vec3::Z),
-                HorizontalAnchor::Right,
-                VerticalAnchor::Bottom,
-            ),
-        ),
-        (
-            Side::Right,
-            (
-                Some(0.25 * Vec3::X),
-                HorizontalAnchor::Right,
-                VerticalAnchor::Bottom,
-            ),
-        ),
-        (
-            Side::Top,
-            (
-                Some(0.25 * Vec3::NEG_Z),
-                HorizontalAnchor::Right,
-                VerticalAnchor::Bottom,
-            ),
-        ),
-        (
-            Side::Left,
-            (
-                Some(0.25 * Vec3::NEG_X),
-                HorizontalAnchor::Right,
-                VerticalAnchor::Bottom,
-            ),
-        ),
-    ]);
-
     for (i, side) in Side::iter().enumerate() {
         // Goal
         let goal_transform = Transform::from_rotation(Quat::from_axis_angle(
@@ -234,24 +221,24 @@ fn spawn_level(
             fade_in: false,
         });
 
-        // HP
-        let (offset, anchorwidth, anchorheight) = hp_ui_configs[&side];
+        // HP, just outside the goal and laid out along it, as the beach is
+        // seen from above until the camera says otherwise.
+        let outward = goal_transform.translation.normalize_or_zero();
+        let (anchorwidth, anchorheight, flex_direction) =
+            HitPointsUi::layout(outward.xz());
 
         commands.spawn((
             HitPointsUi { goal_entity },
             AnchorUiNode {
                 target: AnchorTarget::Entity(goal_entity),
-                offset,
+                offset: Some(HitPointsUi::OFFSET * outward),
                 anchorwidth,
                 anchorheight,
             },
-            Text("0".to_string()),
-            TextFont {
-                font: game_assets.font_menu.clone(),
-                font_size: 50.0,
-                ..Default::default()
+            Node {
+                flex_direction,
+                ..default()
             },
-            TextColor(Srgba::RED.into()),
         ));
 
         // Corner Barriers
@@ -285,7 +272,7 @@ fn reset_team_and_hit_points(
     game_modes: GameModes,
     mut goals_query: Query<(&Side, &mut Team, &mut HitPoints)>,
 ) {
-    for (side, competitor) in &game_modes.current().competitors {
+    for (side, competitor) in &game_modes.in_play().competitors {
         for (goal_side, mut team, mut hp) in &mut goals_query {
             if goal_side != side {
                 continue;
@@ -300,13 +287,26 @@ fn reset_team_and_hit_points(
 fn spawn_crabs_for_each_side(
     mut commands: Commands,
     mut materials: ResMut<Assets<StandardMaterial>>,
-    cached_assets: Res<CachedAssets>,
     game_modes: GameModes,
+    player_slots: Res<PlayerSlots>,
+    settings: Res<Settings>,
     goals_query: Query<(Entity, &Side, Option<&Children>), With<Goal>>,
     game_assets: Res<GameAssets>,
     game_configs: Res<Assets<GameConfig>>,
 ) {
     let game_config = game_configs.get(&game_assets.game_config).unwrap();
+    let is_attract_match = game_modes.is_attract_match();
+    let side_player_slots = if is_attract_match {
+        default()
+    } else {
+        game_modes.in_play().player_slots()
+    };
+    let is_sole_player = side_player_slots.len() == 1;
+    let symbol_states = if is_attract_match {
+        vec![GameState::StartMenu]
+    } else {
+        vec![GameState::Playing, GameState::Paused]
+    };
 
     for (goal_entity, side, children) in &goals_query {
         if let Some(children) = children {
@@ -315,7 +315,7 @@ fn spawn_crabs_for_each_side(
             }
         }
 
-        let crab_config = &game_modes.current().competitors[side];
+        let crab_config = &game_modes.in_play().competitors[side];
         let mut crab_commands = commands.spawn((
             Crab,
             Collider,
@@ -337,10 +337,11 @@ fn spawn_crabs_for_each_side(
             Acceleration(
                 crab_config.max_speed / crab_config.seconds_to_max_speed,
             ),
-            Mesh3d(cached_assets.crab_mesh.clone()),
+            SceneRoot(game_assets.scene_crab.clone()),
+            // Shared with the model's shell once it spawns, for fading and
+            // recoloring.
             MeshMaterial3d(materials.add(StandardMaterial {
-                base_color: Srgba::hex(&crab_config.color).unwrap().into(),
-                base_color_texture: Some(game_assets.image_crab.clone()),
+                base_color: settings.competitor_color(*side, crab_config),
                 reflectance: 0.2,
                 perceptual_roughness: 0.2,
                 ..default()
@@ -359,19 +360,70 @@ fn spawn_crabs_for_each_side(
 
         crab_commands.insert(*side);
 
-        if crab_config.controller == CrabController::AI {
-            crab_commands.insert(AI);
+        if let Some(slot) = side_player_slots.get(side) {
+            let device = player_slots.device(*slot);
+
+            crab_commands.insert((
+                Player(*slot),
+                settings.controls.crab_input_map(
+                    device,
+                    is_sole_player,
+                    game_config.gamepad_stick_deadzone,
+                ),
+            ));
+
+            if device == PlayerDevice::Pointer {
+                crab_commands.insert(PointerControlled);
+            }
+        } else if crab_config.controller == CrabController::Remote
+            && !is_attract_match
+        {
+            crab_commands.insert((RemoteControlled, AI));
         } else {
-            crab_commands.insert(Player);
+            crab_commands.insert(AI);
         }
 
         let crab_entity = crab_commands.id();
         commands.entity(goal_entity).add_child(crab_entity);
+
+        // Team symbol, floating above the crab.
+        commands.spawn((
+            TeamSymbol(*side),
+            ForStates(symbol_states.clone()),
+            AnchorUiNode {
+                target: AnchorTarget::Entity(crab_entity),
+                offset: Some(Vec3::Y * game_config.crab_depth),
+                anchorwidth: HorizontalAnchor::Mid,
+                anchorheight: VerticalAnchor::Mid,
+            },
+        ));
+
         commands.trigger(StartFading(Fade::In, crab_entity));
         info!("Crab({side:?}): Spawned");
     }
 }
 
+/// Applies controls rebound in the middle of a match to the crabs they move.
+fn update_player_input_maps_from_settings(
+    settings: Res<Settings>,
+    game_modes: GameModes,
+    player_slots: Res<PlayerSlots>,
+    game_assets: Res<GameAssets>,
+    game_configs: Res<Assets<GameConfig>>,
+    mut players_query: Query<(&Player, &mut InputMap<CrabAction>)>,
+) {
+    let game_config = game_configs.get(&game_assets.game_config).unwrap();
+    let is_sole_player = game_modes.current().player_slots().len() == 1;
+
+    for (player, mut input_map) in &mut players_query {
+        *input_map = settings.controls.crab_input_map(
+            player_slots.device(player.0),
+            is_sole_player,
+            game_config.gamepad_stick_deadzone,
+        );
+    }
+}
+
 fn spawn_balls_sequentially_up_to_max_count(
     mut commands: Commands,
     mut materials: ResMut<Assets<StandardMaterial>>,
@@ -380,6 +432,8 @@ fn spawn_balls_sequentially_up_to_max_count(
     moving_crabs_query: Query<Entity, With<Motion>>,
     non_moving_balls_query: Query<Entity, (With<Ball>, Without<Motion>)>,
     balls_query: Query<Entity, With<Ball>>,
+    mut spawner_rng: ResMut<SpawnerRng>,
+    settings: Res<Settings>,
     game_assets: Res<GameAssets>,
     game_configs: Res<Assets<GameConfig>>,
 ) {
@@ -394,7 +448,7 @@ fn spawn_balls_sequentially_up_to_max_count(
     }
 
     // Spawn balls up to max ball count.
-    let game_mode = game_modes.current();
+    let game_mode = game_modes.in_play();
     let ball_count: u8 = game_mode.ball_count.into();
 
     if balls_query.iter().len() >= ball_count as usize {
@@ -403,8 +457,7 @@ fn spawn_balls_sequentially_up_to_max_count(
 
     // Spawn a ball in a random direction from the center of the spawner.
     let game_config = game_configs.get(&game_assets.game_config).unwrap();
-    let mut rng = SmallRng::from_os_rng();
-    let angle = rng.random_range(0.0..std::f32::consts::TAU);
+    let angle = spawner_rng.0.random_range(0.0..std::f32::consts::TAU);
     let (angle_sin, angle_cos) = angle.sin_cos();
     let ball_entity = commands
         .spawn((
@@ -419,13 +472,17 @@ fn spawn_balls_sequentially_up_to_max_count(
             InsertAfterFadeIn::<Motion>::default(),
             InsertAfterFadeIn::<Collider>::default(),
             RemoveBeforeFadeOut::<Collider>::default(),
-            ForStates(vec![GameState::Playing, GameState::Paused]),
+            ForStates(if game_modes.is_attract_match() {
+                vec![GameState::StartMenu]
+            } else {
+                vec![GameState::Playing, GameState::Paused]
+            }),
             Direction(Dir3::new_unchecked(Vec3::new(
                 angle_cos, 0.0, angle_sin,
             ))),
             Speed(game_mode.ball_speed),
             Mesh3d(cached_assets.ball_mesh.clone()),
-            MeshMaterial3d(materials.add(Color::WHITE)),
+            MeshMaterial3d(materials.add(settings.accessibility.ball_color())),
             Transform::from_matrix(Mat4::from_scale_rotation_translation(
                 Vec3::splat(game_mode.ball_scale * game_config.ball_diameter),
                 Quat::IDENTITY,
@@ -440,11 +497,44 @@ fn spawn_balls_sequentially_up_to_max_count(
     info!("Ball({ball_entity:?}): Spawned");
 }
 
+/// Updates crab and ball colors to the accessibility settings, keeping any
+/// fade in progress.
+fn recolor_crabs_and_balls(
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    settings: Res<Settings>,
+    game_modes: GameModes,
+    crabs_query: Query<(&Side, &MeshMaterial3d<StandardMaterial>), With<Crab>>,
+    balls_query: Query<&MeshMaterial3d<StandardMaterial>, With<Ball>>,
+) {
+    let crab_colors = crabs_query.iter().filter_map(|(side, material)| {
+        let competitor = game_modes.in_play().competitors.get(side)?;
+
+        Some((material, settings.competitor_color(*side, competitor)))
+    });
+    let ball_colors = balls_query
+        .iter()
+        .map(|material| (material, settings.accessibility.ball_color()));
+
+    for (material, color) in crab_colors.chain(ball_colors) {
+        let Some(material) = materials.get_mut(material) else {
+            continue;
+        };
+        let alpha = material.base_color.alpha();
+
+        material.base_color = color.with_alpha(alpha);
+    }
+}
+
 fn spawn_pole_in_a_goal(
     trigger: Trigger<SpawnPole>,
     cached_assets: Res<CachedAssets>,
     mut commands: Commands,
-    goals_query: Query<(&Side, Option<&Children>), With<Goal>>,
+    game_modes: GameModes,
+    settings: Res<Settings>,
+    goals_query: Query<
+        (&Side, &GlobalTransform, Option<&Children>),
+        With<Goal>,
+    >,
     game_assets: Res<GameAssets>,
     game_configs: Res<Assets<GameConfig>>,
 ) {
@@ -452,7 +542,8 @@ fn spawn_pole_in_a_goal(
         goal_entity,
         fade_in,
     } = trigger.event();
-    let Ok((side, children)) = goals_query.get(*goal_entity) else {
+    let Ok((side, global_transform, children)) = goals_query.get(*goal_entity)
+    else {
         return;
     };
 
@@ -499,7 +590,25 @@ fn spawn_pole_in_a_goal(
     commands.entity(*goal_entity).add_child(pole_entity);
 
     if *fade_in {
+        let color = game_modes
+            .in_play()
+            .competitors
+            .get(side)
+            .map_or(Color::WHITE, |competitor| {
+                settings.competitor_color(*side, competitor)
+            });
+
         commands.trigger(StartFading(Fade::In, pole_entity));
+        commands.trigger(SpawnParticles {
+            effect: ParticleEffect::PoleBurst,
+            position: global_transform
+                .translation()
+                .with_y(game_config.pole_height_from_ground),
+            spread: global_transform.right().abs()
+                * 0.5
+                * game_config.beach_width,
+            colors: vec![color],
+        });
     }
 
     info!("Pole({side:?}): Spawned");
@@ -513,9 +622,10 @@ fn spawn_ui_message(
     let SpawnUiMessage {
         message,
         game_state,
+        menu_screen,
     } = trigger.event();
 
-    commands.spawn((
+    let mut entity = commands.spawn((
         ForStates(vec![*game_state]),
         AnchorUiNode {
             target: AnchorTarget::Translation(LEVEL_CENTER_POINT),
@@ -531,4 +641,8 @@ fn spawn_ui_message(
         },
         TextColor(Srgba::BLUE.into()),
     ));
+
+    if let Some(menu_screen) = menu_screen {
+        entity.insert(ForStates(vec![*menu_screen]));
+    }
 }
@@ -0,0 +1,343 @@
+use std::{
+    io::{ErrorKind, Write},
+    net::{Ipv4Addr, SocketAddr, TcpListener, TcpStream},
+};
+
+use bevy::prelude::*;
+use serde::Serialize;
+
+use crate::{
+    components::{
+        Ball, Crab, Direction, Goal, GoalEliminatedEvent, GoalScoredEvent,
+        HitPoints, Motion, Side, Speed, Target, Team,
+    },
+    states::GameState,
+    system_params::GameModes,
+    system_sets::SideEffectsSet,
+    ui::WinningTeam,
+};
+
+/// How many state frames are streamed per second unless `--spectate-rate` is
+/// given.
+pub const DEFAULT_SPECTATOR_RATE: f32 = 20.0;
+
+/// How many bytes can wait to be sent before a slow spectator is dropped.
+const MAX_UNSENT_BYTES: usize = 1 << 20;
+
+/// Streams the game's state to any number of spectators over TCP, as one line
+/// of JSON per [`SpectatorFrame`].
+pub struct SpectatorPlugin(pub SpectatorConfig);
+
+impl Plugin for SpectatorPlugin {
+    fn build(&self, app: &mut App) {
+        let SpectatorConfig { address, rate } = self.0.clone();
+        let listener = match TcpListener::bind(address) {
+            Ok(listener) => listener,
+            Err(error) => {
+                error!("Spectator: Failed to listen on {address}: {error}");
+                return;
+            },
+        };
+
+        if let Err(error) = listener.set_nonblocking(true) {
+            error!("Spectator: Failed to listen on {address}: {error}");
+            return;
+        }
+
+        info!("Spectator: Listening on {address}");
+        app.insert_resource(SpectatorServer {
+            listener,
+            spectators: Vec::new(),
+            timer: Timer::from_seconds(rate.recip(), TimerMode::Repeating),
+            events: Vec::new(),
+        })
+        .add_systems(
+            Last,
+            (
+                accept_spectators,
+                collect_spectator_events,
+                stream_state_to_spectators,
+                send_unsent_bytes_to_spectators,
+            )
+                .chain()
+                .in_set(SideEffectsSet),
+        );
+    }
+}
+
+/// Spectator options read from the command line.
+#[derive(Clone, Debug)]
+pub struct SpectatorConfig {
+    pub address: SocketAddr,
+
+    /// Frames per second.
+    pub rate: f32,
+}
+
+impl SpectatorConfig {
+    /// Parses `--spectate <address>` and an optional `--spectate-rate <hz>`.
+    /// Only local addresses are listened on, so any other address is replaced
+    /// with 127.0.0.1 on the same port.
+    ///
+    /// Returns `None` if spectating wasn't requested or the address is
+    /// invalid.
+    pub fn from_args(args: &[String]) -> Option<Self> {
+        let value_of = |flag: &str| {
+            args.iter()
+                .skip_while(|arg| *arg != flag)
+                .nth(1)
+                .map(String::as_str)
+        };
+
+        let mut address: SocketAddr = value_of("--spectate")?.parse().ok()?;
+
+        if !address.ip().is_loopback() {
+            warn!("Spectator: {address} isn't local, listening on 127.0.0.1");
+            address.set_ip(Ipv4Addr::LOCALHOST.into());
+        }
+
+        Some(Self {
+            address,
+            rate: value_of("--spectate-rate")
+                .and_then(|rate| rate.parse().ok())
+                .filter(|rate: &f32| *rate > 0.0)
+                .unwrap_or(DEFAULT_SPECTATOR_RATE),
+        })
+    }
+}
+
+/// Accepts spectators and holds the events they haven't been sent yet.
+#[derive(Resource)]
+pub struct SpectatorServer {
+    listener: TcpListener,
+    spectators: Vec<Spectator>,
+    timer: Timer,
+    events: Vec<SpectatorEvent>,
+}
+
+/// A connected spectator and the bytes it couldn't be sent yet.
+struct Spectator {
+    address: SocketAddr,
+    stream: TcpStream,
+    unsent: Vec<u8>,
+}
+
+/// Everything a spectator sees at one moment.
+#[derive(Clone, Debug, Serialize)]
+pub struct SpectatorFrame {
+    /// Seconds since the game started.
+    pub elapsed_secs: f32,
+    pub game_state: GameState,
+    pub game_mode: String,
+    pub goals: Vec<SpectatorGoal>,
+    pub crabs: Vec<SpectatorCrab>,
+    pub balls: Vec<SpectatorBall>,
+
+    /// Everything that happened since the previous frame.
+    pub events: Vec<SpectatorEvent>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SpectatorGoal {
+    pub side: Side,
+    pub team: usize,
+    pub hit_points: u8,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SpectatorCrab {
+    pub side: Side,
+    pub translation: Vec3,
+
+    /// The [`SpectatorBall::id`] an AI crab is moving toward.
+    pub target: Option<u64>,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct SpectatorBall {
+    /// Identifies the ball across frames.
+    pub id: u64,
+    pub translation: Vec3,
+    pub direction: Vec3,
+    pub speed: f32,
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SpectatorEvent {
+    GoalScored { side: Side },
+    GoalEliminated { side: Side },
+    Winner { team: usize },
+}
+
+fn accept_spectators(mut server: ResMut<SpectatorServer>) {
+    loop {
+        match server.listener.accept() {
+            Ok((stream, address)) => {
+                if let Err(error) = stream
+                    .set_nonblocking(true)
+                    .and_then(|_| stream.set_nodelay(true))
+                {
+                    warn!("Spectator({address}): Failed to connect: {error}");
+                    continue;
+                }
+
+                info!("Spectator({address}): Connected");
+                server.spectators.push(Spectator {
+                    address,
+                    stream,
+                    unsent: Vec::new(),
+                });
+            },
+            Err(error) if error.kind() == ErrorKind::WouldBlock => break,
+            Err(error) => {
+                warn!("Spectator: Failed to accept: {error}");
+                break;
+            },
+        }
+    }
+}
+
+fn collect_spectator_events(
+    mut server: ResMut<SpectatorServer>,
+    mut goal_scored_events: EventReader<GoalScoredEvent>,
+    mut goal_eliminated_events: EventReader<GoalEliminatedEvent>,
+    winning_team: Option<Res<WinningTeam>>,
+    goals_query: Query<&Side, With<Goal>>,
+) {
+    for GoalScoredEvent { goal_entity, .. } in goal_scored_events.read() {
+        if let Ok(side) = goals_query.get(*goal_entity) {
+            server
+                .events
+                .push(SpectatorEvent::GoalScored { side: *side });
+        }
+    }
+
+    for GoalEliminatedEvent(goal_entity) in goal_eliminated_events.read() {
+        if let Ok(side) = goals_query.get(*goal_entity) {
+            server
+                .events
+                .push(SpectatorEvent::GoalEliminated { side: *side });
+        }
+    }
+
+    if let Some(winning_team) = winning_team.filter(|w| w.is_changed()) {
+        server.events.push(SpectatorEvent::Winner {
+            team: winning_team.0,
+        });
+    }
+}
+
+fn stream_state_to_spectators(
+    mut server: ResMut<SpectatorServer>,
+    time: Res<Time<Real>>,
+    game_state: Res<State<GameState>>,
+    game_modes: GameModes,
+    goals_query: Query<(&Side, &Team, &HitPoints), With<Goal>>,
+    crabs_query: Query<
+        (&Side, &GlobalTransform, Option<&Target>),
+        (With<Crab>, With<Motion>),
+    >,
+    balls_query: Query<
+        (Entity, &GlobalTransform, &Direction, &Speed),
+        With<Ball>,
+    >,
+) {
+    let server = &mut *server;
+
+    if !server.timer.tick(time.delta()).just_finished() {
+        return;
+    }
+
+    // Nobody is around to see what happened.
+    if server.spectators.is_empty() {
+        server.events.clear();
+        return;
+    }
+
+    let frame = SpectatorFrame {
+        elapsed_secs: time.elapsed_secs(),
+        game_state: *game_state.get(),
+        game_mode: game_modes.in_play().name.clone(),
+        goals: goals_query
+            .iter()
+            .map(|(side, team, hit_points)| SpectatorGoal {
+                side: *side,
+                team: team.0,
+                hit_points: hit_points.0,
+            })
+            .collect(),
+        crabs: crabs_query
+            .iter()
+            .map(|(side, global_transform, target)| SpectatorCrab {
+                side: *side,
+                translation: global_transform.translation(),
+                target: target.map(|target| target.0.to_bits()),
+            })
+            .collect(),
+        balls: balls_query
+            .iter()
+            .map(
+                |(entity, global_transform, direction, speed)| SpectatorBall {
+                    id: entity.to_bits(),
+                    translation: global_transform.translation(),
+                    direction: direction.0.as_vec3(),
+                    speed: speed.0,
+                },
+            )
+            .collect(),
+        events: std::mem::take(&mut server.events),
+    };
+    let mut line = match serde_json::to_vec(&frame) {
+        Ok(line) => line,
+        Err(error) => {
+            warn!("Spectator: Failed to encode frame: {error}");
+            return;
+        },
+    };
+
+    line.push(b'\n');
+    server.spectators.retain_mut(|spectator| {
+        if spectator.unsent.len() + line.len() > MAX_UNSENT_BYTES {
+            info!("Spectator({}): Disconnected: Too slow", spectator.address);
+            return false;
+        }
+
+        spectator.unsent.extend_from_slice(&line);
+        true
+    });
+}
+
+/// Writes as much as each spectator's socket takes without blocking, and keeps
+/// the rest for the next frame, so frames are never cut short.
+fn send_unsent_bytes_to_spectators(mut server: ResMut<SpectatorServer>) {
+    server.spectators.retain_mut(
+        |Spectator {
+             address,
+             stream,
+             unsent,
+         }| {
+            while !unsent.is_empty() {
+                match stream.write(unsent) {
+                    Ok(0) => {
+                        info!("Spectator({address}): Disconnected");
+                        return false;
+                    },
+                    Ok(written) => {
+                        unsent.drain(..written);
+                    },
+                    Err(error) if error.kind() == ErrorKind::WouldBlock => {
+                        break;
+                    },
+                    Err(error) if error.kind() == ErrorKind::Interrupted => {},
+                    Err(error) => {
+                        info!("Spectator({address}): Disconnected: {error}");
+                        return false;
+                    },
+                }
+            }
+
+            true
+        },
+    );
+}
@@ -1,17 +1,28 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
 use strum::EnumIter;
 
 pub(super) struct StatesPlugin;
 
 impl Plugin for StatesPlugin {
     fn build(&self, app: &mut App) {
-        app.init_state::<GameState>();
+        app.init_state::<GameState>().add_sub_state::<MenuScreen>();
     }
 }
 
 // All the app's possible states.
 #[derive(
-    Clone, Copy, Debug, Default, EnumIter, Eq, Hash, PartialEq, States,
+    Clone,
+    Copy,
+    Debug,
+    Default,
+    Deserialize,
+    EnumIter,
+    Eq,
+    Hash,
+    PartialEq,
+    Serialize,
+    States,
 )]
 pub enum GameState {
     #[default]
@@ -20,3 +31,18 @@ pub enum GameState {
     Playing,
     Paused,
 }
+
+// Which screen is shown while in a menu.
+#[derive(
+    Clone, Copy, Debug, Default, EnumIter, Eq, Hash, PartialEq, SubStates,
+)]
+#[source(GameState = GameState::StartMenu | GameState::Paused)]
+pub enum MenuScreen {
+    #[default]
+    Main,
+    Controls,
+    Settings,
+    Results,
+    ModeEditor,
+    Confirm,
+}
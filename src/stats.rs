@@ -0,0 +1,174 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, utils::HashMap};
+use strum::IntoEnumIterator;
+
+use crate::{
+    components::{
+        BallDeflectedEvent, Crab, Goal, GoalEliminatedEvent, GoalMouth,
+        GoalScoredEvent, HitPoints, Side,
+    },
+    states::GameState,
+    system_params::Goals,
+    ui::MatchTime,
+};
+
+/// How far from the middle of a goal mouth, as a fraction of its half width,
+/// a deflection counts as an edge save.
+const EDGE_SAVE_FRACTION: f32 = 0.75;
+
+pub(super) struct StatsPlugin;
+
+impl Plugin for StatsPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MatchStats>()
+            .add_systems(OnExit(GameState::StartMenu), reset_match_stats)
+            .add_systems(
+                Last,
+                collect_match_stats.run_if(in_state(GameState::Playing)),
+            );
+    }
+}
+
+/// What each crab did during the current or most recent match.
+#[derive(Clone, Debug, Default, Resource)]
+pub struct MatchStats {
+    pub crabs: HashMap<Side, CrabStats>,
+
+    /// Sides in the order they were eliminated.
+    pub eliminations: Vec<Side>,
+    pub timeline: Vec<TimelineEntry>,
+
+    /// How many times each ball in play has been deflected.
+    rallies: HashMap<Entity, u32>,
+}
+
+impl MatchStats {
+    /// How long a side lasted, which is the whole match if it survived.
+    pub fn time_survived(&self, side: Side, match_time: Duration) -> Duration {
+        self.timeline
+            .iter()
+            .find(|entry| entry.event == TimelineEvent::Eliminated(side))
+            .map_or(match_time, |entry| entry.time)
+    }
+
+    /// The 1-based place a side was eliminated in, if it was.
+    pub fn elimination_place(&self, side: Side) -> Option<usize> {
+        self.eliminations
+            .iter()
+            .position(|eliminated| *eliminated == side)
+            .map(|index| index + 1)
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct CrabStats {
+    pub deflections: u32,
+    pub goals_conceded: u32,
+
+    /// Deflections close to the edge of the goal mouth.
+    pub edge_saves: u32,
+
+    /// The most deflections a ball had when this crab returned it.
+    pub longest_rally: u32,
+}
+
+/// Something that happened during a match, and when.
+#[derive(Clone, Debug)]
+pub struct TimelineEntry {
+    pub time: Duration,
+    pub event: TimelineEvent,
+}
+
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TimelineEvent {
+    /// A goal was scored on and has this many hit points left.
+    Scored(Side, u8),
+    Eliminated(Side),
+}
+
+fn reset_match_stats(mut match_stats: ResMut<MatchStats>) {
+    *match_stats = MatchStats {
+        crabs: Side::iter()
+            .map(|side| (side, CrabStats::default()))
+            .collect(),
+        ..default()
+    };
+}
+
+fn collect_match_stats(
+    mut match_stats: ResMut<MatchStats>,
+    mut ball_deflected_events: EventReader<BallDeflectedEvent>,
+    mut goal_scored_events: EventReader<GoalScoredEvent>,
+    mut goal_eliminated_events: EventReader<GoalEliminatedEvent>,
+    match_time: Res<MatchTime>,
+    goals: Goals,
+    crabs_query: Query<(&Side, &Parent), With<Crab>>,
+    goals_query: Query<(&Side, &HitPoints, &GoalMouth), With<Goal>>,
+    balls_query: Query<&GlobalTransform>,
+) {
+    let match_stats = &mut *match_stats;
+
+    for BallDeflectedEvent {
+        crab_entity,
+        ball_entity,
+    } in ball_deflected_events.read()
+    {
+        let Ok((side, parent)) = crabs_query.get(*crab_entity) else {
+            continue;
+        };
+        let rally = match_stats.rallies.entry(*ball_entity).or_default();
+
+        *rally += 1;
+
+        let rally = *rally;
+        let is_edge_save = match (
+            goals.get(parent.get()),
+            goals_query.get(parent.get()),
+            balls_query.get(*ball_entity),
+        ) {
+            (Ok(goal), Ok((_, _, goal_mouth)), Ok(global_transform)) => {
+                goal.map_to_local_x(global_transform).abs()
+                    > EDGE_SAVE_FRACTION * 0.5 * goal_mouth.width
+            },
+            _ => false,
+        };
+        let crab_stats = match_stats.crabs.entry(*side).or_default();
+
+        crab_stats.deflections += 1;
+        crab_stats.longest_rally = crab_stats.longest_rally.max(rally);
+
+        if is_edge_save {
+            crab_stats.edge_saves += 1;
+        }
+    }
+
+    for GoalScoredEvent { goal_entity, .. } in goal_scored_events.read() {
+        let Ok((side, hit_points, _)) = goals_query.get(*goal_entity) else {
+            continue;
+        };
+
+        match_stats.crabs.entry(*side).or_default().goals_conceded += 1;
+        match_stats.timeline.push(TimelineEntry {
+            time: match_time.0,
+            event: TimelineEvent::Scored(*side, hit_points.0),
+        });
+    }
+
+    for GoalEliminatedEvent(goal_entity) in goal_eliminated_events.read() {
+        let Ok((side, ..)) = goals_query.get(*goal_entity) else {
+            continue;
+        };
+
+        match_stats.eliminations.push(*side);
+        match_stats.timeline.push(TimelineEntry {
+            time: match_time.0,
+            event: TimelineEvent::Eliminated(*side),
+        });
+    }
+
+    // Forget the rallies of balls that have scored or faded away.
+    match_stats
+        .rallies
+        .retain(|ball_entity, _| balls_query.contains(*ball_entity));
+}
@@ -1,14 +1,16 @@
 use bevy::{
-    ecs::{query::QueryEntityError, system::SystemParam},
+    asset::AssetPath,
+    ecs::{query::QueryEntityError, system::SystemParam, world::Command},
     prelude::*,
 };
 use derive_getters::Getters;
-use std::ops::Add;
 
 use crate::{
     assets::{GameAssets, GameMode},
     components::{Direction, Goal},
     spawners::Beach,
+    states::GameState,
+    ui::AttractMatch,
 };
 
 pub(super) struct SystemParamsPlugin;
@@ -28,6 +30,8 @@ pub struct GameModes<'w> {
     game_assets: Res<'w, GameAssets>,
     game_modes: Res<'w, Assets<GameMode>>,
     selected: ResMut<'w, SelectedGameMode>,
+    attract_match: Option<Res<'w, AttractMatch>>,
+    game_state: Res<'w, State<GameState>>,
 }
 
 impl GameModes<'_> {
@@ -38,18 +42,109 @@ impl GameModes<'_> {
             .unwrap()
     }
 
-    /// Switch to the previous game mode.
+    /// Gets the file the current game mode was loaded from, which modes added
+    /// since the game started don't have.
+    pub fn current_path(&self) -> Option<&AssetPath<'static>> {
+        self.game_assets.game_modes[self.selected.0].path()
+    }
+
+    /// Gets the file the game mode with the given name was loaded from, which
+    /// modes added since the game started don't have.
+    pub fn path(&self, name: &str) -> Option<&AssetPath<'static>> {
+        self.game_assets
+            .game_modes
+            .iter()
+            .find(|handle| {
+                self.game_modes
+                    .get(*handle)
+                    .is_some_and(|game_mode| game_mode.name == name)
+            })?
+            .path()
+    }
+
+    /// Gets the game mode being played on the beach, which is the attract
+    /// match's while one is running behind the start menu.
+    pub fn in_play(&self) -> &GameMode {
+        self.attract_match
+            .as_ref()
+            .filter(|_| self.is_attract_match())
+            .and_then(|attract_match| {
+                self.game_modes.get(&attract_match.game_mode)
+            })
+            .unwrap_or_else(|| self.current())
+    }
+
+    /// Checks if an attract match is playing behind the start menu.
+    pub fn is_attract_match(&self) -> bool {
+        self.attract_match.is_some()
+            && *self.game_state.get() == GameState::StartMenu
+    }
+
+    /// Checks if a different game mode was selected since the system last
+    /// ran.
+    pub fn is_changed(&self) -> bool {
+        self.selected.is_changed()
+    }
+
+    /// Switch to the previous game mode, wrapping around to the last one.
     pub fn previous(&mut self) {
-        self.selected.0 = self.selected.0.saturating_sub(1);
+        let count = self.game_assets.game_modes.len();
+
+        self.selected.0 = (self.selected.0 + count - 1) % count;
+    }
+
+    /// Switch to the game mode with the given name.
+    ///
+    /// Returns `false` and keeps the current selection if there's no match.
+    pub fn select(&mut self, name: &str) -> bool {
+        let found = self.game_assets.game_modes.iter().position(|handle| {
+            self.game_modes
+                .get(handle)
+                .is_some_and(|game_mode| game_mode.name == name)
+        });
+
+        if let Some(index) = found {
+            self.selected.0 = index;
+        }
+
+        found.is_some()
     }
 
-    /// Switch to the next game mode.
+    /// Switch to the next game mode, wrapping around to the first one.
     pub fn next(&mut self) {
-        self.selected.0 = self
-            .selected
-            .0
-            .add(1)
-            .min(self.game_assets.game_modes.len() - 1);
+        self.selected.0 =
+            (self.selected.0 + 1) % self.game_assets.game_modes.len();
+    }
+}
+
+/// Adds a game mode to the list and selects it, replacing any mode with the
+/// same name.
+#[derive(Debug)]
+pub struct AddGameMode(pub GameMode);
+
+impl Command for AddGameMode {
+    fn apply(self, world: &mut World) {
+        world.resource_scope(|world, mut game_assets: Mut<GameAssets>| {
+            let mut game_modes = world.resource_mut::<Assets<GameMode>>();
+            let existing = game_assets.game_modes.iter().position(|handle| {
+                game_modes
+                    .get(handle)
+                    .is_some_and(|game_mode| game_mode.name == self.0.name)
+            });
+            let index = match existing {
+                Some(index) => {
+                    game_modes
+                        .insert(&game_assets.game_modes[index], self.0.clone());
+                    index
+                },
+                None => {
+                    game_assets.game_modes.push(game_modes.add(self.0.clone()));
+                    game_assets.game_modes.len() - 1
+                },
+            };
+
+            world.resource_mut::<SelectedGameMode>().0 = index;
+        });
     }
 }
 
@@ -62,7 +157,10 @@ pub struct Goals<'w, 's> {
 
 impl Goals<'_, '_> {
     /// Get the relevant data for the corresponding [Goal] entity.
-    pub fn get(&self, entity: Entity) -> Result<GoalData, QueryEntityError> {
+    pub fn get(
+        &self,
+        entity: Entity,
+    ) -> Result<GoalData, QueryEntityError<'_>> {
         let global_transform = self.goals_query.get(entity)?;
 
         Ok(GoalData {
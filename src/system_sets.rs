@@ -1,6 +1,10 @@
 use bevy::prelude::*;
 
-use crate::states::GameState;
+use crate::{
+    net::{NetClient, is_resimulating},
+    states::GameState,
+    ui::AttractMatch,
+};
 
 pub(super) struct SystemSetsPlugin;
 
@@ -23,6 +27,20 @@ impl Plugin for SystemSetsPlugin {
                 .after(StopWhenPausedSet)
                 .run_if(in_state(GameState::Playing)),
         )
+        .configure_sets(
+            Update,
+            ActiveDuringMatchSet
+                .in_set(ActiveAfterLoadingSet)
+                .after(StopWhenPausedSet)
+                .run_if(
+                    in_state(GameState::Playing)
+                        .or(resource_exists::<AttractMatch>),
+                ),
+        )
+        .configure_sets(
+            Update,
+            AuthoritativeSet.run_if(not(resource_exists::<NetClient>)),
+        )
         .configure_sets(
             PostUpdate,
             ActiveAfterLoadingSet.run_if(not(in_state(GameState::Loading))),
@@ -39,7 +57,24 @@ impl Plugin for SystemSetsPlugin {
                 .in_set(ActiveAfterLoadingSet)
                 .after(StopWhenPausedSet)
                 .run_if(in_state(GameState::Playing)),
-        );
+        )
+        .configure_sets(
+            PostUpdate,
+            ActiveDuringMatchSet
+                .in_set(ActiveAfterLoadingSet)
+                .after(StopWhenPausedSet)
+                .run_if(
+                    in_state(GameState::Playing)
+                        .or(resource_exists::<AttractMatch>),
+                ),
+        )
+        .configure_sets(
+            PostUpdate,
+            AuthoritativeSet.run_if(not(resource_exists::<NetClient>)),
+        )
+        .configure_sets(Update, SideEffectsSet.run_if(not(is_resimulating)))
+        .configure_sets(PostUpdate, SideEffectsSet.run_if(not(is_resimulating)))
+        .configure_sets(Last, SideEffectsSet.run_if(not(is_resimulating)));
     }
 }
 
@@ -54,3 +89,18 @@ pub struct StopWhenPausedSet;
 /// Systems that only run during gameplay.
 #[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
 pub struct ActiveDuringGameplaySet;
+
+/// Systems that play out a match, whether it's the player's or an attract
+/// match running behind the start menu.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub struct ActiveDuringMatchSet;
+
+/// Systems that decide the outcome of a round, which network clients leave
+/// to the host.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub struct AuthoritativeSet;
+
+/// Systems that only present the game, like sounds, particles, and UI, which
+/// are skipped while a rollback re-simulates ticks that already played out.
+#[derive(Clone, Debug, Eq, Hash, PartialEq, SystemSet)]
+pub struct SideEffectsSet;
@@ -0,0 +1,170 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, window::PrimaryWindow};
+use rand::prelude::*;
+
+use crate::{
+    assets::{GameAssets, GameConfig, GameMode},
+    components::{
+        Ball, Crab, Fade, Goal, GoalEliminatedEvent, HitPoints, StartFading,
+        Team, TeamSymbol,
+    },
+    net::is_offline,
+    states::{GameState, MenuScreen},
+    system_sets::{ActiveAfterLoadingSet, ActiveDuringMatchSet},
+};
+
+pub(super) struct AttractPlugin;
+
+impl Plugin for AttractPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<IdleTimer>()
+            .add_systems(OnEnter(GameState::StartMenu), reset_idle_timer)
+            .add_systems(OnExit(GameState::StartMenu), hand_over_attract_match)
+            .add_systems(
+                Update,
+                (
+                    reset_idle_timer_on_any_input,
+                    start_attract_match_when_idle
+                        .run_if(not(resource_exists::<AttractMatch>))
+                        .run_if(in_state(MenuScreen::Main))
+                        .run_if(any_with_component::<PrimaryWindow>)
+                        .run_if(is_offline),
+                )
+                    .chain()
+                    .in_set(ActiveAfterLoadingSet)
+                    .run_if(in_state(GameState::StartMenu)),
+            )
+            .add_systems(
+                PostUpdate,
+                start_next_attract_match_when_a_team_wins
+                    .in_set(ActiveDuringMatchSet)
+                    .run_if(resource_exists::<AttractMatch>),
+            );
+    }
+}
+
+/// Present while AI crabs play a match of a random game mode behind the start
+/// menu, to show off the game while nobody is at the controls.
+///
+/// Changing it starts a new attract match.
+#[derive(Debug, Resource)]
+pub struct AttractMatch {
+    pub game_mode: Handle<GameMode>,
+}
+
+/// How long the start menu has gone without input.
+#[derive(Debug, Default, Resource)]
+struct IdleTimer(Duration);
+
+fn reset_idle_timer(mut idle_timer: ResMut<IdleTimer>) {
+    idle_timer.0 = Duration::ZERO;
+}
+
+fn reset_idle_timer_on_any_input(
+    time: Res<Time<Real>>,
+    mut idle_timer: ResMut<IdleTimer>,
+    keys: Res<ButtonInput<KeyCode>>,
+    mouse_buttons: Res<ButtonInput<MouseButton>>,
+    gamepads_query: Query<&Gamepad>,
+) {
+    let has_input = keys.get_just_pressed().next().is_some()
+        || mouse_buttons.get_just_pressed().next().is_some()
+        || gamepads_query
+            .iter()
+            .any(|gamepad| gamepad.get_just_pressed().next().is_some());
+
+    if has_input {
+        idle_timer.0 = Duration::ZERO;
+    } else {
+        idle_timer.0 += time.delta();
+    }
+}
+
+fn start_attract_match_when_idle(
+    mut commands: Commands,
+    idle_timer: Res<IdleTimer>,
+    game_assets: Res<GameAssets>,
+    game_configs: Res<Assets<GameConfig>>,
+) {
+    let game_config = game_configs.get(&game_assets.game_config).unwrap();
+
+    if idle_timer.0.as_secs_f32() < game_config.attract_idle_time_in_secs {
+        return;
+    }
+
+    let Some(game_mode) = game_assets.game_modes.choose(&mut rand::rng())
+    else {
+        return;
+    };
+
+    commands.insert_resource(AttractMatch {
+        game_mode: game_mode.clone(),
+    });
+    info!("Attract Match: Started");
+}
+
+fn start_next_attract_match_when_a_team_wins(
+    mut commands: Commands,
+    mut attract_match: ResMut<AttractMatch>,
+    mut goal_eliminated_events: EventReader<GoalEliminatedEvent>,
+    game_assets: Res<GameAssets>,
+    teams_query: Query<(&Team, &HitPoints), With<Goal>>,
+    balls_query: Query<Entity, With<Ball>>,
+) {
+    if goal_eliminated_events.read().count() == 0 {
+        return;
+    }
+
+    // Same as a real match, except nobody is told who won.
+    let survivor = teams_query.iter().find(|(_, hp)| hp.0 > 0);
+    let is_over = survivor.is_none_or(|(survivor_team, _)| {
+        teams_query
+            .iter()
+            .all(|(team, hp)| team.0 == survivor_team.0 || hp.0 == 0)
+    });
+
+    if !is_over {
+        return;
+    }
+
+    for ball_entity in &balls_query {
+        commands.trigger(StartFading(Fade::Out, ball_entity));
+    }
+
+    if let Some(game_mode) = game_assets.game_modes.choose(&mut rand::rng()) {
+        attract_match.game_mode = game_mode.clone();
+    }
+
+    attract_match.set_changed();
+    info!("Attract Match: Restarted");
+}
+
+/// Clears the beach for the player once a match starts, while the rest of the
+/// menu leaves the attract match playing behind it.
+fn hand_over_attract_match(
+    mut commands: Commands,
+    mut idle_timer: ResMut<IdleTimer>,
+    attract_match: Option<Res<AttractMatch>>,
+    crabs_query: Query<Entity, With<Crab>>,
+    balls_query: Query<Entity, With<Ball>>,
+    symbols_query: Query<Entity, (With<TeamSymbol>, Without<Parent>)>,
+) {
+    idle_timer.0 = Duration::ZERO;
+
+    if attract_match.is_none() {
+        return;
+    }
+
+    for entity in crabs_query.iter().chain(&balls_query) {
+        commands.trigger(StartFading(Fade::Out, entity));
+    }
+
+    // Symbols that float above crabs, rather than those next to hit points.
+    for symbol_entity in &symbols_query {
+        commands.entity(symbol_entity).despawn_recursive();
+    }
+
+    commands.remove_resource::<AttractMatch>();
+    info!("Attract Match: Handed Over");
+}
@@ -0,0 +1,155 @@
+use bevy::prelude::*;
+use rust_i18n::t;
+
+use crate::{
+    assets::GameAssets,
+    components::{Ball, ForStates},
+    states::{GameState, MenuScreen},
+};
+
+use super::{MenuItemActivated, MenuList, spawn_menu_item};
+
+pub(super) struct ConfirmationPlugin;
+
+impl Plugin for ConfirmationPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(MenuScreen::Confirm), spawn_confirmation_ui)
+            .add_systems(
+                OnEnter(GameState::StartMenu),
+                finish_restarting_match
+                    .run_if(resource_exists::<RestartingMatch>),
+            );
+    }
+}
+
+/// A destructive action that waits for the player to confirm it.
+#[derive(Clone, Copy, Debug, Eq, PartialEq, Resource)]
+pub enum Confirmation {
+    /// Starts the current game mode over.
+    RestartMatch,
+
+    /// Abandons the match and goes back to the start menu.
+    QuitToMenu,
+}
+
+/// Present while a match is restarting by passing through the start menu,
+/// which spawns a fresh set of crabs.
+#[derive(Debug, Resource)]
+pub struct RestartingMatch;
+
+/// Marks the list of options on the confirmation screen.
+#[derive(Component, Debug)]
+#[require(MenuList)]
+pub struct ConfirmationUi;
+
+fn spawn_confirmation_ui(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    confirmation: Option<Res<Confirmation>>,
+) {
+    let font = &game_assets.font_menu;
+    let question = match confirmation.as_deref() {
+        Some(Confirmation::RestartMatch) => t!("ui.confirmation.restart_match"),
+        Some(Confirmation::QuitToMenu) | None => {
+            t!("ui.confirmation.quit_to_menu")
+        },
+    };
+
+    commands
+        .spawn((
+            ForStates(vec![MenuScreen::Confirm]),
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(8.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.85)),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text(question.to_string()),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 23.0,
+                    ..default()
+                },
+                TextColor(Srgba::BLUE.into()),
+                TextLayout::new_with_justify(JustifyText::Center),
+            ));
+
+            // "No" comes first, so a stray accept doesn't confirm.
+            parent
+                .spawn((
+                    ConfirmationUi,
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                ))
+                .with_children(|parent| {
+                    spawn_menu_item(parent, font, t!("ui.confirmation.no"))
+                        .observe(cancel_confirmation);
+                    spawn_menu_item(parent, font, t!("ui.confirmation.yes"))
+                        .observe(confirm);
+                });
+        });
+}
+
+fn cancel_confirmation(
+    _trigger: Trigger<MenuItemActivated>,
+    mut commands: Commands,
+    mut next_menu_screen: ResMut<NextState<MenuScreen>>,
+) {
+    commands.remove_resource::<Confirmation>();
+    next_menu_screen.set(MenuScreen::Main);
+}
+
+fn confirm(
+    _trigger: Trigger<MenuItemActivated>,
+    mut commands: Commands,
+    confirmation: Option<Res<Confirmation>>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    mut next_menu_screen: ResMut<NextState<MenuScreen>>,
+    balls_query: Query<Entity, With<Ball>>,
+) {
+    let Some(confirmation) = confirmation else {
+        return;
+    };
+
+    match *confirmation {
+        Confirmation::RestartMatch => {
+            // Get rid of the balls in play immediately, instead of letting
+            // them fade out over the new match.
+            for ball_entity in &balls_query {
+                commands.entity(ball_entity).despawn_recursive();
+            }
+
+            // Stay on this screen so the start menu isn't shown in passing.
+            commands.insert_resource(RestartingMatch);
+            next_game_state.set(GameState::StartMenu);
+            info!("Restarting Match");
+        },
+        Confirmation::QuitToMenu => {
+            next_game_state.set(GameState::StartMenu);
+            next_menu_screen.set(MenuScreen::Main);
+            info!("Start Menu");
+        },
+    }
+
+    commands.remove_resource::<Confirmation>();
+}
+
+fn finish_restarting_match(
+    mut commands: Commands,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    commands.remove_resource::<RestartingMatch>();
+    next_game_state.set(GameState::Playing);
+    info!("New Game");
+}
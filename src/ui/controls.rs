@@ -0,0 +1,387 @@
+use bevy::prelude::*;
+use rust_i18n::t;
+use strum::IntoEnumIterator;
+
+use crate::{
+    assets::GameAssets,
+    components::{CrabAction, ForStates, KeyboardLayout},
+    settings::{
+        BindingError, BindingInput, BindingSlot, ControlBindings,
+        REBINDABLE_CRAB_ACTIONS, Settings,
+    },
+    states::MenuScreen,
+};
+
+use super::{
+    MenuAction, MenuInputLock, MenuItemActivated, MenuItemAdjusted, MenuList,
+    return_to_main_menu_screen, spawn_menu_item,
+};
+
+pub(super) struct ControlsPlugin;
+
+impl Plugin for ControlsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(MenuScreen::Controls), spawn_controls_ui)
+            .add_systems(OnExit(MenuScreen::Controls), cancel_rebind_capture)
+            .add_systems(
+                Update,
+                update_binding_row_texts.run_if(in_state(MenuScreen::Controls)),
+            )
+            .add_systems(
+                PostUpdate,
+                capture_input_for_rebinding
+                    .run_if(resource_exists::<RebindCapture>),
+            );
+    }
+}
+
+const ROW_FONT_SIZE: f32 = 16.0;
+
+/// Marks the list of bindings on the controls screen.
+#[derive(Component, Debug)]
+#[require(MenuList)]
+pub struct ControlsUi;
+
+/// Marks the [`Text`] that explains the state of the current rebind.
+#[derive(Component, Debug)]
+#[require(Text)]
+pub struct RebindStatus;
+
+/// An action whose bindings are shown on a row of the controls screen.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum RowAction {
+    Crab(CrabAction),
+    Menu(MenuAction),
+}
+
+/// A controls screen row that shows one [`BindingSlot`] at a time.
+///
+/// Left and right cycle through the devices the action can be bound on.
+#[derive(Clone, Component, Copy, Debug)]
+pub struct BindingRow {
+    pub action: RowAction,
+    pub column: usize,
+}
+
+impl BindingRow {
+    fn column_count(&self) -> usize {
+        match self.action {
+            RowAction::Crab(_) => KeyboardLayout::iter().count() + 1,
+            RowAction::Menu(_) => 2,
+        }
+    }
+
+    /// The slot for the currently shown column.
+    pub fn slot(&self) -> BindingSlot {
+        match self.action {
+            RowAction::Crab(action) => {
+                match KeyboardLayout::iter().nth(self.column) {
+                    Some(layout) => BindingSlot::CrabKey(layout, action),
+                    None => BindingSlot::CrabButton(action),
+                }
+            },
+            RowAction::Menu(action) if self.column == 0 => {
+                BindingSlot::MenuKey(action)
+            },
+            RowAction::Menu(action) => BindingSlot::MenuButton(action),
+        }
+    }
+}
+
+/// Waits for the next key or gamepad button to bind it to a slot.
+#[derive(Debug, Resource)]
+pub struct RebindCapture {
+    pub slot: BindingSlot,
+}
+
+fn spawn_controls_ui(mut commands: Commands, game_assets: Res<GameAssets>) {
+    let font = &game_assets.font_menu;
+
+    commands
+        .spawn((
+            ForStates(vec![MenuScreen::Controls]),
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(8.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.85)),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text(t!("ui.controls.title").to_string()),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 23.0,
+                    ..default()
+                },
+                TextColor(Srgba::BLUE.into()),
+            ));
+
+            parent
+                .spawn((
+                    ControlsUi,
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                ))
+                .with_children(|parent| {
+                    let crab_rows = REBINDABLE_CRAB_ACTIONS
+                        .into_iter()
+                        .map(RowAction::Crab);
+                    let menu_rows = MenuAction::iter().map(RowAction::Menu);
+
+                    for action in crab_rows.chain(menu_rows) {
+                        spawn_menu_item(parent, font, "")
+                            .insert((
+                                BindingRow { action, column: 0 },
+                                TextFont {
+                                    font: font.clone(),
+                                    font_size: ROW_FONT_SIZE,
+                                    ..default()
+                                },
+                            ))
+                            .observe(start_rebind_capture)
+                            .observe(change_binding_row_column);
+                    }
+
+                    spawn_menu_item(parent, font, t!("ui.controls.reset"))
+                        .observe(reset_bindings_to_defaults);
+                    spawn_menu_item(parent, font, t!("ui.controls.back"))
+                        .observe(return_to_main_menu_screen);
+                });
+
+            parent.spawn((
+                RebindStatus,
+                Text(t!("ui.controls.hint").to_string()),
+                TextFont {
+                    font: font.clone(),
+                    font_size: ROW_FONT_SIZE,
+                    ..default()
+                },
+                TextColor(Srgba::BLUE.into()),
+            ));
+        });
+}
+
+fn start_rebind_capture(
+    trigger: Trigger<MenuItemActivated>,
+    mut commands: Commands,
+    binding_rows_query: Query<&BindingRow>,
+    mut rebind_status_query: Query<&mut Text, With<RebindStatus>>,
+) {
+    let Ok(binding_row) = binding_rows_query.get(trigger.entity()) else {
+        return;
+    };
+    let slot = binding_row.slot();
+    let message = if matches!(
+        slot,
+        BindingSlot::CrabButton(_) | BindingSlot::MenuButton(_)
+    ) {
+        t!("ui.controls.press_button")
+    } else {
+        t!("ui.controls.press_key")
+    };
+
+    commands.insert_resource(RebindCapture { slot });
+    commands.init_resource::<MenuInputLock>();
+
+    for mut text in &mut rebind_status_query {
+        text.0 = message.to_string();
+    }
+}
+
+fn change_binding_row_column(
+    trigger: Trigger<MenuItemAdjusted>,
+    mut binding_rows_query: Query<&mut BindingRow>,
+) {
+    let Ok(mut binding_row) = binding_rows_query.get_mut(trigger.entity())
+    else {
+        return;
+    };
+    let column_count = binding_row.column_count() as i32;
+
+    binding_row.column = (binding_row.column as i32 + trigger.event().0)
+        .rem_euclid(column_count) as usize;
+}
+
+fn reset_bindings_to_defaults(
+    _trigger: Trigger<MenuItemActivated>,
+    mut settings: ResMut<Settings>,
+    mut rebind_status_query: Query<&mut Text, With<RebindStatus>>,
+) {
+    settings.controls = ControlBindings::default();
+
+    for mut text in &mut rebind_status_query {
+        text.0 = t!("ui.controls.reset_done").to_string();
+    }
+
+    info!("Controls: Reset to defaults");
+}
+
+fn capture_input_for_rebinding(
+    mut commands: Commands,
+    rebind_capture: Res<RebindCapture>,
+    mut settings: ResMut<Settings>,
+    keys: Res<ButtonInput<KeyCode>>,
+    gamepads_query: Query<&Gamepad>,
+    mut rebind_status_query: Query<&mut Text, With<RebindStatus>>,
+) {
+    // Ignore the input that started the capture.
+    if rebind_capture.is_added() {
+        return;
+    }
+
+    let key = keys.get_just_pressed().next().copied();
+    let button = gamepads_query
+        .iter()
+        .find_map(|gamepad| gamepad.get_just_pressed().next().copied());
+    let input = match (key, button) {
+        (Some(KeyCode::Escape), _) => None,
+        (Some(key), _) => Some(BindingInput::Key(key)),
+        (None, Some(button)) => Some(BindingInput::Button(button)),
+        (None, None) => return,
+    };
+    let slot = rebind_capture.slot;
+    let message = match input.map(|input| settings.controls.set(slot, input)) {
+        None => t!("ui.controls.cancelled"),
+        Some(Ok(())) => {
+            info!("Controls: Bound {input:?} to {slot:?}");
+            t!("ui.controls.bound")
+        },
+        Some(Err(BindingError::WrongDevice)) => t!("ui.controls.wrong_device"),
+        Some(Err(BindingError::Unreachable)) => t!("ui.controls.unreachable"),
+        Some(Err(BindingError::Conflict(other))) => {
+            let (action, column) = describe_slot(other);
+
+            t!("ui.controls.conflict", action = action, column = column)
+        },
+    };
+
+    for mut text in &mut rebind_status_query {
+        text.0 = message.to_string();
+    }
+
+    commands.remove_resource::<RebindCapture>();
+    commands.remove_resource::<MenuInputLock>();
+}
+
+fn cancel_rebind_capture(mut commands: Commands) {
+    commands.remove_resource::<RebindCapture>();
+    commands.remove_resource::<MenuInputLock>();
+}
+
+fn update_binding_row_texts(
+    settings: Res<Settings>,
+    rebind_capture: Option<Res<RebindCapture>>,
+    mut binding_rows_query: Query<(&BindingRow, &mut Text)>,
+) {
+    for (binding_row, mut text) in &mut binding_rows_query {
+        let slot = binding_row.slot();
+        let (action, column) = describe_slot(slot);
+        let input = if rebind_capture
+            .as_ref()
+            .is_some_and(|rebind_capture| rebind_capture.slot == slot)
+        {
+            "...".to_string()
+        } else {
+            settings
+                .controls
+                .get(slot)
+                .map_or_else(|| "-".to_string(), describe_input)
+        };
+        let label = t!(
+            "ui.controls.row",
+            action = action,
+            column = column,
+            input = input
+        );
+
+        if text.0 != label {
+            text.0 = label.to_string();
+        }
+    }
+}
+
+/// Gets the localized action and device names for a slot.
+fn describe_slot(slot: BindingSlot) -> (String, String) {
+    let action = match slot {
+        BindingSlot::CrabKey(_, action) | BindingSlot::CrabButton(action) => {
+            describe_crab_action(action)
+        },
+        BindingSlot::MenuKey(action) | BindingSlot::MenuButton(action) => {
+            describe_menu_action(action)
+        },
+    };
+    let column = match slot {
+        BindingSlot::CrabKey(KeyboardLayout::Wasd, _) => {
+            t!("ui.lobby.device.wasd")
+        },
+        BindingSlot::CrabKey(KeyboardLayout::Arrows, _) => {
+            t!("ui.lobby.device.arrows")
+        },
+        BindingSlot::CrabKey(KeyboardLayout::Ijkl, _) => {
+            t!("ui.lobby.device.ijkl")
+        },
+        BindingSlot::CrabKey(KeyboardLayout::Numpad, _) => {
+            t!("ui.lobby.device.numpad")
+        },
+        BindingSlot::MenuKey(_) => t!("ui.controls.keyboard"),
+        BindingSlot::CrabButton(_) | BindingSlot::MenuButton(_) => {
+            t!("ui.lobby.device.gamepad")
+        },
+    };
+
+    (action, column.to_string())
+}
+
+fn describe_crab_action(action: CrabAction) -> String {
+    use CrabAction::*;
+
+    match action {
+        MoveUp => t!("ui.controls.actions.move_up"),
+        MoveDown => t!("ui.controls.actions.move_down"),
+        MoveLeft => t!("ui.controls.actions.move_left"),
+        MoveRight => t!("ui.controls.actions.move_right"),
+        Move => t!("ui.controls.actions.move"),
+        LeftTrigger => t!("ui.controls.actions.left_trigger"),
+        RightTrigger => t!("ui.controls.actions.right_trigger"),
+    }
+    .to_string()
+}
+
+fn describe_menu_action(action: MenuAction) -> String {
+    use MenuAction::*;
+
+    match action {
+        Accept => t!("ui.controls.actions.accept"),
+        PauseUnpause => t!("ui.controls.actions.pause_unpause"),
+        ReturnToStartMenu => t!("ui.controls.actions.return_to_start_menu"),
+        Up => t!("ui.controls.actions.up"),
+        Down => t!("ui.controls.actions.down"),
+        Left => t!("ui.controls.actions.left"),
+        Right => t!("ui.controls.actions.right"),
+        Exit => t!("ui.controls.actions.exit"),
+    }
+    .to_string()
+}
+
+/// Gets a short name for a key or button, like `W` or `DPadUp`.
+fn describe_input(input: BindingInput) -> String {
+    let name = match input {
+        BindingInput::Key(key) => format!("{key:?}"),
+        BindingInput::Button(button) => format!("{button:?}"),
+    };
+
+    name.strip_prefix("Key")
+        .or_else(|| name.strip_prefix("Digit"))
+        .unwrap_or(&name)
+        .to_string()
+}
@@ -0,0 +1,259 @@
+use bevy::prelude::*;
+use rust_i18n::t;
+
+use crate::{
+    assets::GameAssets,
+    components::{Goal, GoalEliminatedEvent, GoalScoredEvent, Side},
+    settings::Settings,
+    states::GameState,
+    system_params::GameModes,
+    system_sets::{ActiveAfterLoadingSet, SideEffectsSet},
+};
+
+use super::{WinningTeam, side_name, team_colors, winning_team_message};
+
+pub(super) struct EventFeedPlugin;
+
+impl Plugin for EventFeedPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnExit(GameState::Loading), spawn_event_feed)
+            .add_systems(
+                Update,
+                (
+                    announce_goals_and_eliminations,
+                    announce_winning_team
+                        .run_if(resource_exists_and_changed::<WinningTeam>),
+                    fade_out_event_feed_entries,
+                )
+                    .chain()
+                    .in_set(ActiveAfterLoadingSet)
+                    .in_set(SideEffectsSet),
+            )
+            .add_observer(add_event_feed_entry);
+    }
+}
+
+const FEED_FONT_SIZE: f32 = 16.0;
+const FEED_TEXT_COLOR: Color = Color::srgb(0.0, 0.0, 1.0);
+const MAX_ENTRIES: usize = 5;
+const ENTRY_SECS: f32 = 4.0;
+const FADE_SECS: f32 = 1.0;
+
+/// Marks the list of recent match events in the corner of the screen.
+#[derive(Component, Debug)]
+#[require(Node)]
+pub struct EventFeedUi;
+
+/// A line in the [`EventFeedUi`], which fades away when its timer runs out.
+#[derive(Component, Debug)]
+#[require(Text)]
+pub struct EventFeedEntry(pub Timer);
+
+/// An event fired to add a line to the [`EventFeedUi`], made of spans of text
+/// and their colors.
+#[derive(Debug, Event)]
+pub struct AddEventFeedEntry(pub Vec<(String, Color)>);
+
+fn spawn_event_feed(mut commands: Commands) {
+    commands.spawn((
+        EventFeedUi,
+        Node {
+            position_type: PositionType::Absolute,
+            // Kept clear of the lobby in the bottom left corner.
+            right: Val::Px(8.0),
+            bottom: Val::Px(8.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::End,
+            ..default()
+        },
+        // Stay visible over the results screen, to announce the winner.
+        GlobalZIndex(1),
+    ));
+}
+
+fn add_event_feed_entry(
+    trigger: Trigger<AddEventFeedEntry>,
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    feed_query: Query<Entity, With<EventFeedUi>>,
+) {
+    let Ok(feed_entity) = feed_query.get_single() else {
+        return;
+    };
+    let text_font = TextFont {
+        font: game_assets.font_menu.clone(),
+        font_size: FEED_FONT_SIZE,
+        ..default()
+    };
+
+    commands.entity(feed_entity).with_children(|parent| {
+        parent
+            .spawn((
+                EventFeedEntry(Timer::from_seconds(
+                    ENTRY_SECS,
+                    TimerMode::Once,
+                )),
+                text_font.clone(),
+                TextColor(FEED_TEXT_COLOR),
+            ))
+            .with_children(|parent| {
+                for (text, color) in &trigger.event().0 {
+                    parent.spawn((
+                        TextSpan(text.clone()),
+                        text_font.clone(),
+                        TextColor(*color),
+                    ));
+                }
+            });
+    });
+}
+
+fn announce_goals_and_eliminations(
+    mut commands: Commands,
+    mut goal_scored_events: EventReader<GoalScoredEvent>,
+    mut goal_eliminated_events: EventReader<GoalEliminatedEvent>,
+    game_modes: GameModes,
+    settings: Res<Settings>,
+    goals_query: Query<&Side, With<Goal>>,
+) {
+    // Nobody is playing the attract match.
+    if game_modes.is_attract_match() {
+        goal_scored_events.clear();
+        goal_eliminated_events.clear();
+        return;
+    }
+
+    let side_color = |side: Side| {
+        game_modes
+            .in_play()
+            .competitors
+            .get(&side)
+            .map_or(FEED_TEXT_COLOR, |competitor| {
+                settings.competitor_color(side, competitor)
+            })
+    };
+    let side_value = |side: Side| (side_name(side), side_color(side));
+
+    for &GoalScoredEvent {
+        goal_entity,
+        scored_by,
+    } in goal_scored_events.read()
+    {
+        let Ok(side) = goals_query.get(goal_entity) else {
+            continue;
+        };
+
+        // Crabs don't get credit for their own goals.
+        let spans = match scored_by.filter(|scorer| scorer != side) {
+            Some(scorer) => colored_spans(
+                &t!("ui.event_feed.scored_on"),
+                &[("scorer", side_value(scorer)), ("side", side_value(*side))],
+            ),
+            None => colored_spans(
+                &t!("ui.event_feed.conceded"),
+                &[("side", side_value(*side))],
+            ),
+        };
+
+        commands.trigger(AddEventFeedEntry(spans));
+    }
+
+    for GoalEliminatedEvent(goal_entity) in goal_eliminated_events.read() {
+        let Ok(side) = goals_query.get(*goal_entity) else {
+            continue;
+        };
+
+        commands.trigger(AddEventFeedEntry(colored_spans(
+            &t!("ui.event_feed.eliminated"),
+            &[("side", side_value(*side))],
+        )));
+    }
+}
+
+fn announce_winning_team(
+    mut commands: Commands,
+    winning_team: Res<WinningTeam>,
+    game_modes: GameModes,
+    settings: Res<Settings>,
+) {
+    let color = team_colors(game_modes.in_play(), &settings)
+        .into_iter()
+        .find(|(team, _)| *team == winning_team.0)
+        .map_or(FEED_TEXT_COLOR, |(_, color)| color);
+    let message = winning_team_message(winning_team.0);
+
+    commands
+        .trigger(AddEventFeedEntry(vec![(message.trim().to_string(), color)]));
+}
+
+fn fade_out_event_feed_entries(
+    mut commands: Commands,
+    time: Res<Time>,
+    feed_query: Query<&Children, With<EventFeedUi>>,
+    mut entries_query: Query<(&mut EventFeedEntry, &Children)>,
+    mut spans_query: Query<&mut TextColor, With<TextSpan>>,
+) {
+    for feed_children in &feed_query {
+        let excess = feed_children.len().saturating_sub(MAX_ENTRIES);
+
+        for (index, entry_entity) in feed_children.iter().enumerate() {
+            let Ok((mut entry, entry_children)) =
+                entries_query.get_mut(*entry_entity)
+            else {
+                continue;
+            };
+
+            entry.0.tick(time.delta());
+
+            if index < excess || entry.0.finished() {
+                commands.entity(*entry_entity).despawn_recursive();
+                continue;
+            }
+
+            let alpha = (entry.0.remaining_secs() / FADE_SECS).min(1.0);
+
+            for span_entity in entry_children {
+                if let Ok(mut text_color) = spans_query.get_mut(*span_entity) {
+                    text_color.0.set_alpha(alpha);
+                }
+            }
+        }
+    }
+}
+
+/// Splits a translated message into spans of text, giving the value of each
+/// `%{name}` placeholder its own color.
+fn colored_spans(
+    message: &str,
+    values: &[(&str, (String, Color))],
+) -> Vec<(String, Color)> {
+    let mut spans = Vec::new();
+    let mut rest = message;
+
+    while let Some(start) = rest.find("%{") {
+        let Some(length) = rest[start..].find('}') else {
+            break;
+        };
+        let name = &rest[start + 2..start + length];
+
+        if start > 0 {
+            spans.push((rest[..start].to_string(), FEED_TEXT_COLOR));
+        }
+
+        match values.iter().find(|(key, _)| *key == name) {
+            Some((_, (value, color))) => spans.push((value.clone(), *color)),
+            None => spans.push((
+                rest[start..=start + length].to_string(),
+                FEED_TEXT_COLOR,
+            )),
+        }
+
+        rest = &rest[start + length + 1..];
+    }
+
+    if !rest.is_empty() {
+        spans.push((rest.to_string(), FEED_TEXT_COLOR));
+    }
+
+    spans
+}
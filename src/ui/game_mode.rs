@@ -0,0 +1,207 @@
+use bevy::prelude::*;
+use rust_i18n::t;
+use strum::IntoEnumIterator;
+
+use crate::{
+    assets::{CrabController, GameAssets, GameMode},
+    components::{Side, TeamSymbol},
+    settings::Settings,
+    states::GameState,
+    system_params::GameModes,
+    system_sets::ActiveAfterLoadingSet,
+};
+
+use super::{MenuItemAdjusted, spawn_menu_item};
+
+pub(super) struct GameModePlugin;
+
+impl Plugin for GameModePlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            update_game_mode_ui
+                .in_set(ActiveAfterLoadingSet)
+                .run_if(in_state(GameState::StartMenu)),
+        );
+    }
+}
+
+const SUMMARY_TEXT_COLOR: Color = Color::srgb(0.0, 0.0, 1.0);
+
+/// A [`MenuItem`](super::MenuItem) showing the selected game mode's name,
+/// which switches modes when adjusted.
+#[derive(Component, Debug)]
+pub struct GameModeItem;
+
+/// A panel listing the balls and competitors of the selected game mode.
+#[derive(Component, Debug)]
+#[require(Node)]
+pub struct GameModeSummaryUi;
+
+/// Spawns a [`GameModeItem`] under a [`MenuList`](super::MenuList).
+pub fn spawn_game_mode_item<'a>(
+    parent: &'a mut ChildBuilder,
+    font: &Handle<Font>,
+) -> EntityCommands<'a> {
+    let mut item = spawn_menu_item(parent, font, "");
+
+    item.insert(GameModeItem).observe(change_game_mode);
+    item
+}
+
+/// Spawns an empty [`GameModeSummaryUi`] that's filled in once it's added.
+pub fn spawn_game_mode_summary<'a>(
+    commands: &'a mut Commands,
+) -> EntityCommands<'a> {
+    commands.spawn((
+        GameModeSummaryUi,
+        Node {
+            position_type: PositionType::Absolute,
+            width: Val::Percent(100.0),
+            top: Val::Percent(8.0),
+            flex_direction: FlexDirection::Column,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(4.0),
+            ..default()
+        },
+    ))
+}
+
+/// Gets the localized name of a side.
+pub fn side_name(side: Side) -> String {
+    match side {
+        Side::Bottom => t!("ui.start_menu.mode.side.bottom"),
+        Side::Right => t!("ui.start_menu.mode.side.right"),
+        Side::Top => t!("ui.start_menu.mode.side.top"),
+        Side::Left => t!("ui.start_menu.mode.side.left"),
+    }
+    .to_string()
+}
+
+fn change_game_mode(
+    trigger: Trigger<MenuItemAdjusted>,
+    mut game_modes: GameModes,
+) {
+    if trigger.event().0 < 0 {
+        game_modes.previous();
+    } else {
+        game_modes.next();
+    }
+
+    info!("Game Mode: {}", &game_modes.current().name);
+}
+
+fn update_game_mode_ui(
+    mut commands: Commands,
+    game_modes: GameModes,
+    game_assets: Res<GameAssets>,
+    settings: Res<Settings>,
+    mut game_mode_items_query: Query<(&mut Text, Ref<GameModeItem>)>,
+    summaries_query: Query<(Entity, Ref<GameModeSummaryUi>)>,
+) {
+    let game_mode = game_modes.current();
+
+    for (mut text, game_mode_item) in &mut game_mode_items_query {
+        if game_modes.is_changed() || game_mode_item.is_added() {
+            text.0 = t!("ui.start_menu.mode.name", name = game_mode.name)
+                .to_string();
+        }
+    }
+
+    for (entity, summary) in &summaries_query {
+        if !game_modes.is_changed()
+            && !settings.is_changed()
+            && !summary.is_added()
+        {
+            continue;
+        }
+
+        commands
+            .entity(entity)
+            .despawn_descendants()
+            .with_children(|parent| {
+                spawn_summary_rows(
+                    parent,
+                    &game_assets.font_menu,
+                    &settings,
+                    game_mode,
+                );
+            });
+    }
+}
+
+fn spawn_summary_rows(
+    parent: &mut ChildBuilder,
+    font: &Handle<Font>,
+    settings: &Settings,
+    game_mode: &GameMode,
+) {
+    let text_font = TextFont {
+        font: font.clone(),
+        font_size: 16.0,
+        ..default()
+    };
+
+    parent.spawn((
+        Text(
+            t!(
+                "ui.start_menu.mode.balls",
+                count = game_mode.ball_count,
+                speed = game_mode.ball_speed
+            )
+            .to_string(),
+        ),
+        text_font.clone(),
+        TextColor(SUMMARY_TEXT_COLOR),
+    ));
+
+    for side in Side::iter() {
+        let Some(competitor) = game_mode.competitors.get(&side) else {
+            continue;
+        };
+        let side_name = side_name(side);
+        let controller = match competitor.controller {
+            CrabController::Player => {
+                t!("ui.start_menu.mode.controller.player")
+            },
+            CrabController::AI => t!("ui.start_menu.mode.controller.ai"),
+            CrabController::Remote => {
+                t!("ui.start_menu.mode.controller.remote")
+            },
+        };
+        let color = settings.competitor_color(side, competitor);
+
+        parent
+            .spawn(Node {
+                align_items: AlignItems::Center,
+                column_gap: Val::Px(8.0),
+                ..default()
+            })
+            .with_children(|row| {
+                row.spawn((
+                    Node {
+                        width: Val::Px(14.0),
+                        height: Val::Px(14.0),
+                        ..default()
+                    },
+                    BackgroundColor(color),
+                ));
+                row.spawn(TeamSymbol(side));
+                row.spawn((
+                    Text(
+                        t!(
+                            "ui.start_menu.mode.competitor",
+                            side = side_name,
+                            controller = controller,
+                            team = competitor.team,
+                            hit_points = competitor.hit_points,
+                            max_speed = competitor.max_speed
+                        )
+                        .to_string(),
+                    ),
+                    text_font.clone(),
+                    TextColor(SUMMARY_TEXT_COLOR),
+                ));
+            });
+    }
+}
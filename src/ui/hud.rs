@@ -0,0 +1,215 @@
+use std::time::Duration;
+
+use bevy::{prelude::*, utils::HashMap};
+use rust_i18n::t;
+use strum::IntoEnumIterator;
+
+use crate::{
+    assets::{GameAssets, GameMode},
+    components::{Ball, ForStates, Goal, HitPoints, Motion, Side, Team},
+    settings::Settings,
+    states::GameState,
+    system_params::GameModes,
+    system_sets::{ActiveAfterLoadingSet, ActiveDuringGameplaySet},
+};
+
+pub(super) struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<MatchTime>()
+            .add_systems(
+                OnExit(GameState::StartMenu),
+                (reset_match_time, spawn_hud),
+            )
+            .add_systems(
+                Update,
+                (
+                    tick_match_time.in_set(ActiveDuringGameplaySet),
+                    update_hud_texts.in_set(ActiveAfterLoadingSet),
+                )
+                    .chain(),
+            );
+    }
+}
+
+const HUD_FONT_SIZE: f32 = 16.0;
+const HUD_TEXT_COLOR: Color = Color::srgb(0.0, 0.0, 1.0);
+
+/// How long the current match has been played, not counting pauses.
+#[derive(Debug, Default, Resource)]
+pub struct MatchTime(pub Duration);
+
+/// A [`Text`] in the HUD and what it shows.
+#[derive(Clone, Component, Copy, Debug, Eq, PartialEq)]
+#[require(Text)]
+pub enum HudText {
+    GameMode,
+    MatchTime,
+    Balls,
+    Team(usize),
+}
+
+fn reset_match_time(mut match_time: ResMut<MatchTime>) {
+    match_time.0 = Duration::ZERO;
+}
+
+fn tick_match_time(time: Res<Time>, mut match_time: ResMut<MatchTime>) {
+    match_time.0 += time.delta();
+}
+
+fn spawn_hud(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    game_modes: GameModes,
+    settings: Res<Settings>,
+) {
+    let text_font = TextFont {
+        font: game_assets.font_menu.clone(),
+        font_size: HUD_FONT_SIZE,
+        ..default()
+    };
+
+    let team_colors = team_colors(game_modes.current(), &settings);
+
+    commands
+        .spawn((
+            ForStates(vec![GameState::Playing, GameState::Paused]),
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                top: Val::Px(0.0),
+                justify_content: JustifyContent::SpaceBetween,
+                padding: UiRect::all(Val::Px(8.0)),
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            parent
+                .spawn(Node {
+                    flex_direction: FlexDirection::Column,
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for hud_text in
+                        [HudText::GameMode, HudText::MatchTime, HudText::Balls]
+                    {
+                        parent.spawn((
+                            hud_text,
+                            text_font.clone(),
+                            TextColor(HUD_TEXT_COLOR),
+                        ));
+                    }
+                });
+
+            parent
+                .spawn(Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::End,
+                    ..default()
+                })
+                .with_children(|parent| {
+                    for (team, color) in team_colors {
+                        parent.spawn((
+                            HudText::Team(team),
+                            text_font.clone(),
+                            TextColor(color),
+                        ));
+                    }
+                });
+        });
+}
+
+/// Gets the color of each team, which is the color of its first competitor.
+pub fn team_colors(
+    game_mode: &GameMode,
+    settings: &Settings,
+) -> Vec<(usize, Color)> {
+    let mut team_colors: Vec<(usize, Color)> = Vec::new();
+
+    for side in Side::iter() {
+        let Some(competitor) = game_mode.competitors.get(&side) else {
+            continue;
+        };
+        let team = competitor.team.get();
+
+        if !team_colors.iter().any(|(existing, _)| *existing == team) {
+            team_colors
+                .push((team, settings.competitor_color(side, competitor)));
+        }
+    }
+
+    team_colors
+}
+
+fn update_hud_texts(
+    game_modes: GameModes,
+    settings: Res<Settings>,
+    match_time: Res<MatchTime>,
+    mut hud_texts_query: Query<(&HudText, &mut Text, &mut TextColor)>,
+    goals_query: Query<(&Team, &HitPoints), With<Goal>>,
+    balls_query: Query<(), (With<Ball>, With<Motion>)>,
+) {
+    let game_mode = game_modes.current();
+    let mut teams: HashMap<usize, (usize, u32)> = HashMap::default();
+
+    for (team, hit_points) in &goals_query {
+        let (goals_left, total_hit_points) = teams.entry(team.0).or_default();
+
+        if hit_points.0 > 0 {
+            *goals_left += 1;
+        }
+
+        *total_hit_points += u32::from(hit_points.0);
+    }
+
+    let team_colors = team_colors(game_mode, &settings);
+
+    for (hud_text, mut text, mut text_color) in &mut hud_texts_query {
+        let message = match hud_text {
+            HudText::GameMode => game_mode.name.clone(),
+            HudText::MatchTime => {
+                let seconds = match_time.0.as_secs();
+
+                t!(
+                    "ui.hud.match_time",
+                    minutes = seconds / 60,
+                    seconds = format!("{:02}", seconds % 60)
+                )
+                .to_string()
+            },
+            HudText::Balls => t!(
+                "ui.hud.balls",
+                active = balls_query.iter().count(),
+                max = game_mode.ball_count
+            )
+            .to_string(),
+            HudText::Team(team) => {
+                let (goals_left, total_hit_points) =
+                    teams.get(team).copied().unwrap_or_default();
+
+                // Follow palette changes made while paused.
+                if let Some((_, color)) =
+                    team_colors.iter().find(|(existing, _)| existing == team)
+                {
+                    if text_color.0 != *color {
+                        text_color.0 = *color;
+                    }
+                }
+
+                t!(
+                    "ui.hud.team",
+                    team = team,
+                    goals = goals_left,
+                    hit_points = total_hit_points
+                )
+                .to_string()
+            },
+        };
+
+        // Only touch the text when it changes, to avoid relayouts.
+        if text.0 != message {
+            text.0 = message;
+        }
+    }
+}
@@ -0,0 +1,181 @@
+use bevy::prelude::*;
+use rust_i18n::t;
+use strum::IntoEnumIterator;
+
+use crate::{
+    assets::GameAssets,
+    components::{
+        ForStates, KeyboardLayout, PlayerDevice, PlayerSlot, PlayerSlots,
+    },
+    settings::{POINTER_SLOTS, Settings, locale_changed},
+    states::GameState,
+};
+
+use super::RestartingMatch;
+
+pub(super) struct LobbyPlugin;
+
+impl Plugin for LobbyPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(GameState::StartMenu),
+            spawn_lobby_ui.run_if(not(resource_exists::<RestartingMatch>)),
+        )
+        .add_systems(
+            Update,
+            (
+                join_or_leave_player_slots_with_gamepads,
+                choose_player_slot_for_pointer,
+                move_pointer_to_its_player_slot
+                    .run_if(resource_changed::<Settings>),
+                free_player_slots_of_disconnected_gamepads,
+                update_lobby_ui
+                    .run_if(resource_changed::<PlayerSlots>.or(locale_changed)),
+            )
+                .chain()
+                .run_if(in_state(GameState::StartMenu)),
+        );
+    }
+}
+
+/// Marks a [`Text`] entity that lists the device for each [`PlayerSlot`].
+///
+/// Clicking or tapping it toggles whether the pointer controls a slot.
+#[derive(Component, Debug)]
+#[require(Text, Interaction)]
+pub struct LobbyUi;
+
+fn spawn_lobby_ui(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    player_slots: Res<PlayerSlots>,
+) {
+    commands.spawn((
+        LobbyUi,
+        ForStates(vec![GameState::StartMenu]),
+        Node {
+            position_type: PositionType::Absolute,
+            left: Val::Px(10.0),
+            bottom: Val::Px(10.0),
+            ..default()
+        },
+        Text(make_lobby_message(&player_slots)),
+        TextFont {
+            font: game_assets.font_menu.clone(),
+            font_size: 16.0,
+            ..default()
+        },
+        TextColor(Srgba::BLUE.into()),
+    ));
+}
+
+fn join_or_leave_player_slots_with_gamepads(
+    mut player_slots: ResMut<PlayerSlots>,
+    gamepads_query: Query<(Entity, &Gamepad)>,
+) {
+    for (entity, gamepad) in &gamepads_query {
+        if gamepad.just_pressed(GamepadButton::North) {
+            if let Some(slot) = player_slots.join_gamepad(entity) {
+                info!("Gamepad({entity:?}): Joined as Player {slot:?}");
+            }
+        } else if gamepad.just_pressed(GamepadButton::East) {
+            if let Some(slot) = player_slots.leave_gamepad(entity) {
+                info!("Gamepad({entity:?}): Left Player {slot:?}");
+            }
+        }
+    }
+}
+
+/// Clicking or tapping the lobby moves the pointer on to the next slot, or
+/// off them all after the last.
+fn choose_player_slot_for_pointer(
+    mut settings: ResMut<Settings>,
+    lobby_ui_query: Query<&Interaction, (Changed<Interaction>, With<LobbyUi>)>,
+) {
+    for interaction in &lobby_ui_query {
+        if *interaction != Interaction::Pressed {
+            continue;
+        }
+
+        let pointer_slot = settings.controls.pointer_slot;
+        let index = POINTER_SLOTS
+            .iter()
+            .position(|slot| *slot == pointer_slot)
+            .unwrap_or(0);
+
+        settings.controls.pointer_slot =
+            POINTER_SLOTS[(index + 1) % POINTER_SLOTS.len()];
+    }
+}
+
+/// Gives the pointer the slot chosen in the settings, unless a gamepad
+/// already has it.
+fn move_pointer_to_its_player_slot(
+    settings: Res<Settings>,
+    mut player_slots: ResMut<PlayerSlots>,
+) {
+    let pointer_slot = settings.controls.pointer_slot;
+
+    if player_slots.device_slot(PlayerDevice::Pointer) == pointer_slot {
+        return;
+    }
+
+    match player_slots.set_pointer_slot(pointer_slot) {
+        Some(slot) => info!("Pointer: Joined as Player {slot:?}"),
+        None => info!("Pointer: Left its player slot"),
+    }
+}
+
+fn free_player_slots_of_disconnected_gamepads(
+    mut player_slots: ResMut<PlayerSlots>,
+    mut removed: RemovedComponents<Gamepad>,
+) {
+    for entity in removed.read() {
+        if let Some(slot) = player_slots.leave_gamepad(entity) {
+            info!("Gamepad({entity:?}): Disconnected from Player {slot:?}");
+        }
+    }
+}
+
+fn update_lobby_ui(
+    player_slots: Res<PlayerSlots>,
+    mut lobby_ui_query: Query<&mut Text, With<LobbyUi>>,
+) {
+    for mut text in &mut lobby_ui_query {
+        text.0 = make_lobby_message(&player_slots);
+    }
+}
+
+fn make_lobby_message(player_slots: &PlayerSlots) -> String {
+    let mut message = String::new();
+
+    for (i, slot) in PlayerSlot::iter().enumerate() {
+        let device = match player_slots.device(slot) {
+            PlayerDevice::Keyboard(KeyboardLayout::Wasd) => {
+                t!("ui.lobby.device.wasd")
+            },
+            PlayerDevice::Keyboard(KeyboardLayout::Arrows) => {
+                t!("ui.lobby.device.arrows")
+            },
+            PlayerDevice::Keyboard(KeyboardLayout::Ijkl) => {
+                t!("ui.lobby.device.ijkl")
+            },
+            PlayerDevice::Keyboard(KeyboardLayout::Numpad) => {
+                t!("ui.lobby.device.numpad")
+            },
+            PlayerDevice::Gamepad(_) => t!("ui.lobby.device.gamepad"),
+            PlayerDevice::Pointer => t!("ui.lobby.device.pointer"),
+            PlayerDevice::Network(_) => t!("ui.lobby.device.network"),
+        };
+
+        message.push_str(&t!(
+            "ui.lobby.player_slot",
+            slot = i + 1,
+            device = device
+        ));
+        message.push('\n');
+    }
+
+    message.push_str(&t!("ui.lobby.join_hint"));
+    message
+}
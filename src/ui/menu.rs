@@ -1,32 +1,65 @@
 use bevy::{prelude::*, window::WindowFocused};
 use leafwing_input_manager::prelude::*;
 use rust_i18n::t;
+use serde::{Deserialize, Serialize};
+use strum::EnumIter;
 
 use crate::{
-    components::Player,
+    assets::GameAssets,
+    components::{ForStates, Player},
+    net::{NetClient, NetHost, RollbackSession, is_offline},
+    settings::{Settings, locale_changed},
     spawners::SpawnUiMessage,
-    states::GameState,
-    system_params::GameModes,
+    states::{GameState, MenuScreen},
     system_sets::{ActiveAfterLoadingSet, ActiveDuringGameplaySet},
 };
 
+use super::{
+    Confirmation, MenuInputLock, MenuItem, MenuItemActivated, MenuList,
+    RestoredSelection, Selected, spawn_game_mode_item, spawn_game_mode_summary,
+    spawn_menu_item,
+};
+
 pub(super) struct MenuPlugin;
 
 impl Plugin for MenuPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(InputManagerPlugin::<MenuAction>::default())
             .init_resource::<ActionState<MenuAction>>()
-            .insert_resource(MenuAction::make_input_map())
-            .add_systems(OnEnter(GameState::StartMenu), show_start_menu_ui)
-            .add_systems(OnEnter(GameState::Paused), show_pause_ui)
+            .init_resource::<InputMap<MenuAction>>()
+            .add_systems(
+                OnEnter(GameState::StartMenu),
+                show_start_menu_ui.run_if(in_state(MenuScreen::Main)),
+            )
+            .add_systems(
+                OnEnter(MenuScreen::Main),
+                show_start_menu_ui.run_if(in_state(GameState::StartMenu)),
+            )
+            .add_systems(
+                OnEnter(GameState::Paused),
+                show_pause_ui.run_if(in_state(MenuScreen::Main)),
+            )
+            .add_systems(
+                OnEnter(MenuScreen::Main),
+                show_pause_ui.run_if(in_state(GameState::Paused)),
+            )
+            .add_systems(
+                Update,
+                respawn_menu_screen_when_locale_changes.run_if(locale_changed),
+            )
             .add_systems(
                 Update,
-                handle_menu_inputs.in_set(ActiveAfterLoadingSet),
+                (
+                    update_menu_input_map_from_settings,
+                    handle_menu_inputs.in_set(ActiveAfterLoadingSet),
+                )
+                    .chain(),
             )
             .add_systems(
                 Update,
                 pause_player_controlled_game_when_window_loses_focus
-                    .in_set(ActiveDuringGameplaySet),
+                    .in_set(ActiveDuringGameplaySet)
+                    .run_if(is_offline),
             );
     }
 }
@@ -35,112 +68,402 @@ impl Plugin for MenuPlugin {
 #[derive(Debug, Default, Resource)]
 pub struct WinningTeam(pub usize);
 
+/// Marks the list of options on the start menu.
+#[derive(Component, Debug)]
+#[require(MenuList)]
+pub struct StartMenuUi;
+
+/// Marks the list of options on the pause menu.
+#[derive(Component, Debug)]
+#[require(MenuList)]
+pub struct PauseMenuUi;
+
 // List of user actions associated to menu/ui interaction
-#[derive(Actionlike, Clone, Copy, Debug, Eq, Hash, PartialEq, Reflect)]
+#[derive(
+    Actionlike,
+    Clone,
+    Copy,
+    Debug,
+    Deserialize,
+    EnumIter,
+    Eq,
+    Hash,
+    PartialEq,
+    Reflect,
+    Serialize,
+)]
 pub enum MenuAction {
     Accept,
     PauseUnpause,
     ReturnToStartMenu,
-    NextGameMode,
-    PrevGameMode,
+    Up,
+    Down,
+    Left,
+    Right,
     Exit,
 }
 
 impl MenuAction {
-    fn make_input_map() -> InputMap<Self> {
+    /// The key bound to each action by default.
+    pub fn default_keys() -> [(Self, KeyCode); 8] {
         use MenuAction::*;
 
-        let mut input_map = InputMap::new([
+        [
             (Accept, KeyCode::Enter),
             (PauseUnpause, KeyCode::Space),
             (ReturnToStartMenu, KeyCode::Backspace),
-            (PrevGameMode, KeyCode::KeyA),
-            (PrevGameMode, KeyCode::ArrowLeft),
-            (NextGameMode, KeyCode::KeyD),
-            (NextGameMode, KeyCode::ArrowRight),
+            (Up, KeyCode::ArrowUp),
+            (Down, KeyCode::ArrowDown),
+            (Left, KeyCode::ArrowLeft),
+            (Right, KeyCode::ArrowRight),
             (Exit, KeyCode::Escape),
-        ]);
-        input_map.insert_multiple([
+        ]
+    }
+
+    /// The gamepad button bound to each action by default.
+    pub fn default_buttons() -> [(Self, GamepadButton); 7] {
+        use MenuAction::*;
+
+        [
             (Accept, GamepadButton::South),
             (PauseUnpause, GamepadButton::Start),
             (ReturnToStartMenu, GamepadButton::Select),
-            (PrevGameMode, GamepadButton::DPadLeft),
-            (NextGameMode, GamepadButton::DPadRight),
-        ]);
+            (Up, GamepadButton::DPadUp),
+            (Down, GamepadButton::DPadDown),
+            (Left, GamepadButton::DPadLeft),
+            (Right, GamepadButton::DPadRight),
+        ]
+    }
+}
+
+fn update_menu_input_map_from_settings(
+    settings: Res<Settings>,
+    mut input_map: ResMut<InputMap<MenuAction>>,
+) {
+    if settings.is_changed() {
+        *input_map = settings.controls.menu_input_map();
+    }
+}
 
-        input_map
+/// Spawns the current menu screen again, so all of its text is shown in the
+/// new language.
+fn respawn_menu_screen_when_locale_changes(
+    mut commands: Commands,
+    menu_screen: Option<Res<State<MenuScreen>>>,
+    menu_screen_entities_query: Query<
+        Entity,
+        (With<ForStates<MenuScreen>>, Without<Parent>),
+    >,
+    menu_lists_query: Query<&Children, With<MenuList>>,
+    menu_items_query: Query<Has<Selected>, With<MenuItem>>,
+) {
+    let Some(menu_screen) = menu_screen else {
+        return;
+    };
+
+    // Keep the same item selected once the screen is back.
+    for children in &menu_lists_query {
+        let selected_index = children
+            .iter()
+            .filter_map(|child| menu_items_query.get(*child).ok())
+            .position(|is_selected| is_selected);
+
+        if let Some(selected_index) = selected_index {
+            commands.insert_resource(RestoredSelection(selected_index));
+            break;
+        }
+    }
+
+    for entity in &menu_screen_entities_query {
+        commands.entity(entity).despawn_recursive();
     }
+
+    // Setting the same state again wouldn't re-enter it, so run the screen's
+    // spawn systems directly, once the old screen is gone.
+    let menu_screen = *menu_screen.get();
+
+    commands.queue(move |world: &mut World| {
+        world.run_schedule(OnEnter(menu_screen));
+    });
+    info!("Menu: Respawned {menu_screen:?}");
 }
 
 fn show_start_menu_ui(
     mut commands: Commands,
+    game_assets: Res<GameAssets>,
     winning_team: Option<Res<WinningTeam>>,
+    start_menu_ui_query: Query<(), With<StartMenuUi>>,
 ) {
-    let winning_team_messages = [
-        t!("ui.start_menu.winning_team.draw"),
-        t!("ui.start_menu.winning_team.player"),
-        t!("ui.start_menu.winning_team.ai"),
-        t!("ui.start_menu.winning_team.reds"),
-        t!("ui.start_menu.winning_team.greens"),
-        t!("ui.start_menu.winning_team.blues"),
-    ];
-    let mut message = String::from(match winning_team {
-        Some(winning_team) => winning_team_messages[winning_team.0].to_string(),
-        _ => "".to_string(),
-    });
+    // Entering the start menu can also enter its main screen.
+    if !start_menu_ui_query.is_empty() {
+        return;
+    }
+
+    let mut message = winning_team
+        .map(|winning_team| winning_team_message(winning_team.0))
+        .unwrap_or_default();
 
     message.push_str(&t!("ui.start_menu.new_game"));
 
     commands.trigger(SpawnUiMessage {
         message,
         game_state: GameState::StartMenu,
+        menu_screen: Some(MenuScreen::Main),
     });
+
+    spawn_game_mode_summary(&mut commands).insert((
+        ForStates(vec![GameState::StartMenu]),
+        ForStates(vec![MenuScreen::Main]),
+    ));
+
+    commands
+        .spawn((
+            StartMenuUi,
+            ForStates(vec![GameState::StartMenu]),
+            ForStates(vec![MenuScreen::Main]),
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                bottom: Val::Percent(10.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            let font = &game_assets.font_menu;
+
+            spawn_game_mode_item(parent, font).observe(start_new_game);
+            spawn_menu_item(parent, font, t!("ui.start_menu.items.new_game"))
+                .observe(start_new_game);
+            spawn_menu_item(
+                parent,
+                font,
+                t!("ui.start_menu.items.mode_editor"),
+            )
+            .observe(open_mode_editor_screen);
+            spawn_menu_item(parent, font, t!("ui.start_menu.items.controls"))
+                .observe(open_controls_screen);
+            spawn_menu_item(parent, font, t!("ui.start_menu.items.settings"))
+                .observe(open_settings_screen);
+            spawn_menu_item(parent, font, t!("ui.start_menu.items.quit"))
+                .observe(quit_game);
+        });
+}
+
+/// Describes which team won, followed by a line break.
+pub fn winning_team_message(team: usize) -> String {
+    match team {
+        0 => t!("ui.start_menu.winning_team.draw"),
+        1 => t!("ui.start_menu.winning_team.player"),
+        2 => t!("ui.start_menu.winning_team.ai"),
+        3 => t!("ui.start_menu.winning_team.reds"),
+        4 => t!("ui.start_menu.winning_team.greens"),
+        5 => t!("ui.start_menu.winning_team.blues"),
+        team => t!("ui.start_menu.winning_team.other", team = team),
+    }
+    .to_string()
 }
 
-fn show_pause_ui(mut commands: Commands) {
+/// Starts a round of the selected game mode when a menu item is chosen.
+pub fn start_new_game(
+    _trigger: Trigger<MenuItemActivated>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    rollback_session: Option<Res<RollbackSession>>,
+) {
+    // Rollback rounds start once both peers are ready.
+    if rollback_session.is_some() {
+        return;
+    }
+
+    next_game_state.set(GameState::Playing);
+    info!("New Game");
+}
+
+/// Goes back to the main screen of the start or pause menu when a menu item
+/// is chosen.
+pub fn return_to_main_menu_screen(
+    _trigger: Trigger<MenuItemActivated>,
+    mut next_menu_screen: ResMut<NextState<MenuScreen>>,
+) {
+    next_menu_screen.set(MenuScreen::Main);
+}
+
+fn open_controls_screen(
+    _trigger: Trigger<MenuItemActivated>,
+    mut next_menu_screen: ResMut<NextState<MenuScreen>>,
+) {
+    next_menu_screen.set(MenuScreen::Controls);
+    info!("Controls");
+}
+
+fn open_mode_editor_screen(
+    _trigger: Trigger<MenuItemActivated>,
+    mut next_menu_screen: ResMut<NextState<MenuScreen>>,
+) {
+    next_menu_screen.set(MenuScreen::ModeEditor);
+    info!("Mode Editor");
+}
+
+fn open_settings_screen(
+    _trigger: Trigger<MenuItemActivated>,
+    mut next_menu_screen: ResMut<NextState<MenuScreen>>,
+) {
+    next_menu_screen.set(MenuScreen::Settings);
+    info!("Settings");
+}
+
+fn resume_game(
+    _trigger: Trigger<MenuItemActivated>,
+    mut next_game_state: ResMut<NextState<GameState>>,
+) {
+    next_game_state.set(GameState::Playing);
+    info!("Unpaused");
+}
+
+fn confirm_restart_match(
+    _trigger: Trigger<MenuItemActivated>,
+    mut commands: Commands,
+    mut next_menu_screen: ResMut<NextState<MenuScreen>>,
+) {
+    commands.insert_resource(Confirmation::RestartMatch);
+    next_menu_screen.set(MenuScreen::Confirm);
+}
+
+fn confirm_quit_to_menu(
+    _trigger: Trigger<MenuItemActivated>,
+    mut commands: Commands,
+    mut next_menu_screen: ResMut<NextState<MenuScreen>>,
+) {
+    commands.insert_resource(Confirmation::QuitToMenu);
+    next_menu_screen.set(MenuScreen::Confirm);
+}
+
+fn quit_game(
+    _trigger: Trigger<MenuItemActivated>,
+    mut app_exit: EventWriter<AppExit>,
+) {
+    app_exit.send_default();
+}
+
+fn show_pause_ui(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    net_host: Option<Res<NetHost>>,
+    net_client: Option<Res<NetClient>>,
+    rollback_session: Option<Res<RollbackSession>>,
+    pause_menu_ui_query: Query<(), With<PauseMenuUi>>,
+) {
+    // Entering the pause menu can also enter its main screen.
+    if !pause_menu_ui_query.is_empty() {
+        return;
+    }
+
     commands.trigger(SpawnUiMessage {
         message: t!("ui.pause_menu.paused").to_string(),
         game_state: GameState::Paused,
+        menu_screen: Some(MenuScreen::Main),
     });
+
+    commands
+        .spawn((
+            PauseMenuUi,
+            ForStates(vec![GameState::Paused]),
+            ForStates(vec![MenuScreen::Main]),
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                bottom: Val::Percent(10.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                ..default()
+            },
+        ))
+        .with_children(|parent| {
+            let font = &game_assets.font_menu;
+
+            spawn_menu_item(parent, font, t!("ui.pause_menu.items.resume"))
+                .observe(resume_game);
+
+            // Network matches restart together, from the start menu.
+            if net_host.is_none()
+                && net_client.is_none()
+                && rollback_session.is_none()
+            {
+                spawn_menu_item(
+                    parent,
+                    font,
+                    t!("ui.pause_menu.items.restart"),
+                )
+                .observe(confirm_restart_match);
+            }
+
+            spawn_menu_item(parent, font, t!("ui.pause_menu.items.settings"))
+                .observe(open_settings_screen);
+            spawn_menu_item(parent, font, t!("ui.pause_menu.items.controls"))
+                .observe(open_controls_screen);
+            spawn_menu_item(
+                parent,
+                font,
+                t!("ui.pause_menu.items.quit_to_menu"),
+            )
+            .observe(confirm_quit_to_menu);
+        });
 }
 
 fn handle_menu_inputs(
+    mut commands: Commands,
     game_state: Res<State<GameState>>,
-    mut game_modes: GameModes,
+    menu_screen: Option<Res<State<MenuScreen>>>,
+    menu_input_lock: Option<Res<MenuInputLock>>,
     mut next_game_state: ResMut<NextState<GameState>>,
+    mut next_menu_screen: ResMut<NextState<MenuScreen>>,
     menu_action_state: Res<ActionState<MenuAction>>,
     mut app_exit: EventWriter<AppExit>,
 ) {
     use GameState::*;
     use MenuAction::*;
 
-    match game_state.get() {
+    // Inputs are being captured by something else, like a rebinding prompt.
+    if menu_input_lock.is_some() {
+        return;
+    }
+
+    let menu_screen = menu_screen.map(|menu_screen| *menu_screen.get());
+
+    match (game_state.get(), menu_screen) {
+        (
+            _,
+            Some(
+                MenuScreen::Controls
+                | MenuScreen::Settings
+                | MenuScreen::Results
+                | MenuScreen::Confirm
+                | MenuScreen::ModeEditor,
+            ),
+        ) if menu_action_state.just_pressed(&ReturnToStartMenu)
+            || menu_action_state.just_pressed(&Exit) =>
+        {
+            commands.remove_resource::<Confirmation>();
+            next_menu_screen.set(MenuScreen::Main);
+        },
         _ if menu_action_state.just_pressed(&Exit) => {
             app_exit.send_default();
         },
-        StartMenu => {
-            if menu_action_state.just_pressed(&Accept) {
-                next_game_state.set(Playing);
-                info!("New Game");
-            } else if menu_action_state.just_pressed(&PrevGameMode) {
-                game_modes.previous();
-                info!("Game Mode: {}", &game_modes.current().name);
-            } else if menu_action_state.just_pressed(&NextGameMode) {
-                game_modes.next();
-                info!("Game Mode: {}", &game_modes.current().name);
-            }
-        },
-        Playing | Paused
+        (Playing | Paused, _)
             if menu_action_state.just_pressed(&ReturnToStartMenu) =>
         {
-            next_game_state.set(StartMenu);
-            info!("Start Menu");
+            // Ask first, since the match would be lost.
+            commands.insert_resource(Confirmation::QuitToMenu);
+            next_game_state.set(Paused);
+            next_menu_screen.set(MenuScreen::Confirm);
+            info!("Paused");
         },
-        Playing if menu_action_state.just_pressed(&PauseUnpause) => {
+        (Playing, _) if menu_action_state.just_pressed(&PauseUnpause) => {
             next_game_state.set(Paused);
             info!("Paused");
         },
-        Paused if menu_action_state.just_pressed(&PauseUnpause) => {
+        (Paused, _) if menu_action_state.just_pressed(&PauseUnpause) => {
             next_game_state.set(Playing);
             info!("Unpaused");
         },
@@ -0,0 +1,217 @@
+use bevy::prelude::*;
+use leafwing_input_manager::prelude::*;
+
+use crate::system_sets::ActiveAfterLoadingSet;
+
+use super::MenuAction;
+
+pub(super) struct MenuListPlugin;
+
+impl Plugin for MenuListPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            Update,
+            (
+                select_first_item_of_new_menu_lists,
+                navigate_menu_lists_with_menu_actions,
+                navigate_menu_lists_with_pointer,
+                highlight_selected_menu_items,
+            )
+                .chain()
+                .in_set(ActiveAfterLoadingSet),
+        );
+    }
+}
+
+const ITEM_COLOR: Color = Color::NONE;
+const SELECTED_ITEM_COLOR: Color = Color::srgba(0.0, 0.0, 1.0, 0.25);
+const ITEM_TEXT_COLOR: Color = Color::srgb(0.0, 0.0, 1.0);
+const SELECTED_ITEM_TEXT_COLOR: Color = Color::WHITE;
+
+/// A vertical list of [`MenuItem`] children that can be navigated with menu
+/// actions or the mouse.
+#[derive(Component, Debug, Default)]
+#[require(Node)]
+pub struct MenuList;
+
+/// An entry in a [`MenuList`] that shows its label as [`Text`].
+#[derive(Component, Debug)]
+#[require(Button, BackgroundColor, Text)]
+pub struct MenuItem;
+
+/// Marks the [`MenuItem`] that currently has focus in its [`MenuList`].
+#[derive(Component, Debug)]
+#[component(storage = "SparseSet")]
+pub struct Selected;
+
+/// A triggered event fired on a [`MenuItem`] when it's chosen.
+#[derive(Clone, Copy, Debug, Event)]
+pub struct MenuItemActivated;
+
+/// A triggered event fired on a selected [`MenuItem`] when left or right is
+/// pressed, with the direction as `-1` or `1`.
+#[derive(Clone, Copy, Debug, Event)]
+pub struct MenuItemAdjusted(pub i32);
+
+/// The position of the [`MenuItem`] to select in the next new [`MenuList`],
+/// instead of its first.
+#[derive(Debug, Resource)]
+pub struct RestoredSelection(pub usize);
+
+/// While present, [`MenuList`] entities ignore all input.
+#[derive(Debug, Default, Resource)]
+pub struct MenuInputLock;
+
+/// Spawns a [`MenuItem`] with a label under a [`MenuList`].
+pub fn spawn_menu_item<'a>(
+    parent: &'a mut ChildBuilder,
+    font: &Handle<Font>,
+    label: impl Into<String>,
+) -> EntityCommands<'a> {
+    parent.spawn((
+        MenuItem,
+        Node {
+            padding: UiRect::axes(Val::Px(12.0), Val::Px(4.0)),
+            justify_content: JustifyContent::Center,
+            ..default()
+        },
+        Text(label.into()),
+        TextFont {
+            font: font.clone(),
+            font_size: 23.0,
+            ..default()
+        },
+        TextColor(ITEM_TEXT_COLOR),
+    ))
+}
+
+fn select_first_item_of_new_menu_lists(
+    mut commands: Commands,
+    restored_selection: Option<Res<RestoredSelection>>,
+    menu_lists_query: Query<&Children, Added<MenuList>>,
+    menu_items_query: Query<(), With<MenuItem>>,
+) {
+    let mut index = restored_selection.map_or(0, |selection| selection.0);
+
+    for children in &menu_lists_query {
+        let items: Vec<Entity> = children
+            .iter()
+            .copied()
+            .filter(|child| menu_items_query.contains(*child))
+            .collect();
+
+        if let Some(item) = items.get(index).or(items.first()) {
+            commands.entity(*item).insert(Selected);
+            commands.remove_resource::<RestoredSelection>();
+            index = 0;
+        }
+    }
+}
+
+fn navigate_menu_lists_with_menu_actions(
+    mut commands: Commands,
+    menu_input_lock: Option<Res<MenuInputLock>>,
+    menu_action_state: Res<ActionState<MenuAction>>,
+    menu_lists_query: Query<&Children, With<MenuList>>,
+    menu_items_query: Query<Has<Selected>, With<MenuItem>>,
+) {
+    use MenuAction::*;
+
+    if menu_input_lock.is_some() {
+        return;
+    }
+
+    for children in &menu_lists_query {
+        let items: Vec<Entity> = children
+            .iter()
+            .copied()
+            .filter(|child| menu_items_query.contains(*child))
+            .collect();
+        let Some(selected_index) = items
+            .iter()
+            .position(|item| menu_items_query.get(*item).unwrap_or(false))
+        else {
+            continue;
+        };
+        let selected = items[selected_index];
+
+        if menu_action_state.just_pressed(&Up)
+            || menu_action_state.just_pressed(&Down)
+        {
+            let step = if menu_action_state.just_pressed(&Up) {
+                items.len() - 1
+            } else {
+                1
+            };
+            let next = items[(selected_index + step) % items.len()];
+
+            commands.entity(selected).remove::<Selected>();
+            commands.entity(next).insert(Selected);
+        } else if menu_action_state.just_pressed(&Accept) {
+            commands.trigger_targets(MenuItemActivated, selected);
+        } else if menu_action_state.just_pressed(&Left) {
+            commands.trigger_targets(MenuItemAdjusted(-1), selected);
+        } else if menu_action_state.just_pressed(&Right) {
+            commands.trigger_targets(MenuItemAdjusted(1), selected);
+        }
+    }
+}
+
+fn navigate_menu_lists_with_pointer(
+    mut commands: Commands,
+    menu_input_lock: Option<Res<MenuInputLock>>,
+    interactions_query: Query<
+        (Entity, &Interaction, &Parent),
+        (Changed<Interaction>, With<MenuItem>),
+    >,
+    menu_lists_query: Query<&Children, With<MenuList>>,
+    selected_query: Query<(), With<Selected>>,
+) {
+    if menu_input_lock.is_some() {
+        return;
+    }
+
+    for (entity, interaction, parent) in &interactions_query {
+        if *interaction == Interaction::None {
+            continue;
+        }
+
+        // Move the selection from any sibling onto the hovered item.
+        if let Ok(siblings) = menu_lists_query.get(parent.get()) {
+            for sibling in siblings {
+                if *sibling != entity && selected_query.contains(*sibling) {
+                    commands.entity(*sibling).remove::<Selected>();
+                }
+            }
+        }
+
+        commands.entity(entity).insert(Selected);
+
+        if *interaction == Interaction::Pressed {
+            commands.trigger_targets(MenuItemActivated, entity);
+        }
+    }
+}
+
+fn highlight_selected_menu_items(
+    mut menu_items_query: Query<
+        (&mut BackgroundColor, &mut TextColor, Has<Selected>),
+        With<MenuItem>,
+    >,
+) {
+    for (mut background_color, mut text_color, is_selected) in
+        &mut menu_items_query
+    {
+        let (background, text) = if is_selected {
+            (SELECTED_ITEM_COLOR, SELECTED_ITEM_TEXT_COLOR)
+        } else {
+            (ITEM_COLOR, ITEM_TEXT_COLOR)
+        };
+
+        background_color.set_if_neq(BackgroundColor(background));
+
+        if text_color.0 != text {
+            text_color.0 = text;
+        }
+    }
+}
@@ -1,8 +1,30 @@
+mod attract;
+mod confirmation;
+mod controls;
 mod debug_mode;
+mod event_feed;
+mod game_mode;
+mod hud;
+mod lobby;
 mod menu;
+mod menu_list;
+mod mode_editor;
+mod results;
+mod settings;
 
+pub use attract::*;
+pub use confirmation::*;
+pub use controls::*;
 pub use debug_mode::*;
+pub use event_feed::*;
+pub use game_mode::*;
+pub use hud::*;
+pub use lobby::*;
 pub use menu::*;
+pub use menu_list::*;
+pub use mode_editor::*;
+pub use results::*;
+pub use settings::*;
 
 use bevy::prelude::*;
 
@@ -10,6 +32,20 @@ pub(super) struct UiPlugin;
 
 impl Plugin for UiPlugin {
     fn build(&self, app: &mut App) {
-        app.add_plugins((DebugModePlugin, MenuPlugin));
+        app.add_plugins((
+            AttractPlugin,
+            ConfirmationPlugin,
+            ControlsPlugin,
+            DebugModePlugin,
+            EventFeedPlugin,
+            GameModePlugin,
+            HudPlugin,
+            LobbyPlugin,
+            MenuListPlugin,
+            MenuPlugin,
+            ModeEditorPlugin,
+            ResultsPlugin,
+            SettingsUiPlugin,
+        ));
     }
 }
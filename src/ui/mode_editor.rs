@@ -0,0 +1,692 @@
+use std::{
+    fs,
+    num::{NonZeroU8, NonZeroUsize},
+    path::PathBuf,
+};
+
+use bevy::{
+    asset::io::file::FileAssetReader,
+    input::{
+        ButtonState,
+        keyboard::{Key, KeyboardInput},
+    },
+    prelude::*,
+};
+use rust_i18n::t;
+use strum::{EnumIter, IntoEnumIterator};
+
+use crate::{
+    assets::{CompetitorConfig, CrabController, GameAssets, GameMode},
+    components::{ForStates, Side},
+    net::RollbackSession,
+    states::{GameState, MenuScreen},
+    system_params::{AddGameMode, GameModes},
+};
+
+use super::{
+    MenuInputLock, MenuItemActivated, MenuItemAdjusted, MenuList,
+    return_to_main_menu_screen, side_name, spawn_menu_item,
+};
+
+pub(super) struct ModeEditorPlugin;
+
+impl Plugin for ModeEditorPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(
+            OnEnter(MenuScreen::ModeEditor),
+            (start_mode_draft, spawn_mode_editor_ui).chain(),
+        )
+        .add_systems(OnExit(MenuScreen::ModeEditor), stop_name_capture)
+        .add_systems(
+            Update,
+            update_editor_row_texts.run_if(in_state(MenuScreen::ModeEditor)),
+        )
+        .add_systems(
+            PreUpdate,
+            unlock_menu_inputs_after_name_capture
+                .run_if(resource_removed::<NameCapture>),
+        )
+        .add_systems(
+            PostUpdate,
+            capture_text_for_name.run_if(resource_exists::<NameCapture>),
+        );
+    }
+}
+
+const ROW_FONT_SIZE: f32 = 16.0;
+const MAX_NAME_LENGTH: usize = 32;
+
+/// The files of the game modes that come with the game, which saving never
+/// overwrites.
+const SHIPPED_GAME_MODE_FILES: [&str; 7] = [
+    "1-classic-player.mode.yaml",
+    "2-classic-ai.mode.yaml",
+    "3-red-vs-blue-player.mode.yaml",
+    "4-red-vs-blue-ai.mode.yaml",
+    "5-this-game-is-rigged.mode.yaml",
+    "6-stress-test.mode.yaml",
+    "7-classic-bots.mode.yaml",
+];
+
+/// The colors a competitor can be given in the editor.
+const COLORS: [&str; 8] = [
+    "FF0000", "0000FF", "FFA600", "7F007F", "00FFFF", "FFFF00", "FF00FF",
+    "000000",
+];
+
+/// The game mode being edited, and which of its sides is shown.
+#[derive(Debug, Resource)]
+pub struct ModeDraft {
+    pub game_mode: GameMode,
+    pub side: Side,
+}
+
+/// Types the next characters into the draft's name.
+#[derive(Debug, Resource)]
+pub struct NameCapture;
+
+/// Marks the list of options on the mode editor screen.
+#[derive(Component, Debug)]
+#[require(MenuList)]
+pub struct ModeEditorUi;
+
+/// Marks the [`Text`] that reports what the editor last did.
+#[derive(Component, Debug)]
+#[require(Text)]
+pub struct ModeEditorStatus;
+
+/// A mode editor row that shows and changes one field of the draft.
+///
+/// Rows after [`EditorRow::Side`] edit the competitor on the chosen side.
+#[derive(Clone, Component, Copy, Debug, EnumIter, Eq, PartialEq)]
+pub enum EditorRow {
+    Name,
+    BallCount,
+    BallScale,
+    BallSpeed,
+    Side,
+    Color,
+    Team,
+    Controller,
+    HitPoints,
+    MaxSpeed,
+    SecondsToMaxSpeed,
+}
+
+impl EditorRow {
+    /// Changes the field by `step` increments, within sensible limits.
+    pub fn adjust(&self, draft: &mut ModeDraft, step: i32) {
+        let game_mode = &mut draft.game_mode;
+
+        match self {
+            Self::Name => {},
+            Self::BallCount => {
+                game_mode.ball_count =
+                    step_non_zero(game_mode.ball_count.get(), step, 12);
+            },
+            Self::BallScale => {
+                game_mode.ball_scale =
+                    step_f32(game_mode.ball_scale, step, 0.25, 0.25, 3.0);
+            },
+            Self::BallSpeed => {
+                game_mode.ball_speed =
+                    step_f32(game_mode.ball_speed, step, 0.1, 0.1, 3.0);
+            },
+            Self::Side => {
+                let sides: Vec<_> = Side::iter().collect();
+                let index = draft.side as i32 + step;
+
+                draft.side =
+                    sides[index.rem_euclid(sides.len() as i32) as usize];
+            },
+            _ => {
+                let Some(competitor) =
+                    game_mode.competitors.get_mut(&draft.side)
+                else {
+                    return;
+                };
+
+                match self {
+                    Self::Color => {
+                        let index = COLORS
+                            .iter()
+                            .position(|color| {
+                                color.eq_ignore_ascii_case(&competitor.color)
+                            })
+                            .map_or(0, |index| index as i32 + step);
+
+                        competitor.color = COLORS
+                            [index.rem_euclid(COLORS.len() as i32) as usize]
+                            .to_string();
+                    },
+                    Self::Team => {
+                        let team = (competitor.team.get() as i32 - 1 + step)
+                            .rem_euclid(Side::iter().len() as i32)
+                            + 1;
+
+                        competitor.team = NonZeroUsize::new(team as usize)
+                            .unwrap_or(NonZeroUsize::MIN);
+                    },
+                    Self::Controller => {
+                        let controllers = [
+                            CrabController::Player,
+                            CrabController::AI,
+                            CrabController::Remote,
+                        ];
+                        let index = controllers
+                            .iter()
+                            .position(|controller| {
+                                *controller == competitor.controller
+                            })
+                            .unwrap_or(0)
+                            as i32
+                            + step;
+
+                        competitor.controller = controllers[index
+                            .rem_euclid(controllers.len() as i32)
+                            as usize];
+                    },
+                    Self::HitPoints => {
+                        competitor.hit_points = step_non_zero(
+                            competitor.hit_points.get(),
+                            step,
+                            20,
+                        );
+                    },
+                    Self::MaxSpeed => {
+                        competitor.max_speed =
+                            step_f32(competitor.max_speed, step, 0.5, 0.5, 6.0);
+                    },
+                    Self::SecondsToMaxSpeed => {
+                        competitor.seconds_to_max_speed = step_f32(
+                            competitor.seconds_to_max_speed,
+                            step,
+                            0.1,
+                            0.1,
+                            3.0,
+                        );
+                    },
+                    _ => {},
+                }
+            },
+        }
+    }
+
+    /// The row's label and current value.
+    pub fn text(&self, draft: &ModeDraft, is_capturing_name: bool) -> String {
+        let game_mode = &draft.game_mode;
+        let competitor = game_mode.competitors.get(&draft.side);
+        let competitor_value = |value: &dyn Fn(&CompetitorConfig) -> String| {
+            competitor.map_or_else(|| "-".to_string(), value)
+        };
+        let (name, value) = match self {
+            Self::Name if is_capturing_name => (
+                t!("ui.mode_editor.rows.name"),
+                format!("{}_", game_mode.name),
+            ),
+            Self::Name => {
+                (t!("ui.mode_editor.rows.name"), game_mode.name.clone())
+            },
+            Self::BallCount => (
+                t!("ui.mode_editor.rows.ball_count"),
+                game_mode.ball_count.to_string(),
+            ),
+            Self::BallScale => (
+                t!("ui.mode_editor.rows.ball_scale"),
+                format!("{:.2}", game_mode.ball_scale),
+            ),
+            Self::BallSpeed => (
+                t!("ui.mode_editor.rows.ball_speed"),
+                format!("{:.1}", game_mode.ball_speed),
+            ),
+            Self::Side => {
+                (t!("ui.mode_editor.rows.side"), side_name(draft.side))
+            },
+            Self::Color => (
+                t!("ui.mode_editor.rows.color"),
+                competitor_value(&|competitor| competitor.color.clone()),
+            ),
+            Self::Team => (
+                t!("ui.mode_editor.rows.team"),
+                competitor_value(&|competitor| competitor.team.to_string()),
+            ),
+            Self::Controller => (
+                t!("ui.mode_editor.rows.controller"),
+                competitor_value(&|competitor| {
+                    match competitor.controller {
+                        CrabController::Player => {
+                            t!("ui.start_menu.mode.controller.player")
+                        },
+                        CrabController::AI => {
+                            t!("ui.start_menu.mode.controller.ai")
+                        },
+                        CrabController::Remote => {
+                            t!("ui.start_menu.mode.controller.remote")
+                        },
+                    }
+                    .to_string()
+                }),
+            ),
+            Self::HitPoints => (
+                t!("ui.mode_editor.rows.hit_points"),
+                competitor_value(&|competitor| {
+                    competitor.hit_points.to_string()
+                }),
+            ),
+            Self::MaxSpeed => (
+                t!("ui.mode_editor.rows.max_speed"),
+                competitor_value(&|competitor| {
+                    format!("{:.1}", competitor.max_speed)
+                }),
+            ),
+            Self::SecondsToMaxSpeed => (
+                t!("ui.mode_editor.rows.seconds_to_max_speed"),
+                competitor_value(&|competitor| {
+                    format!("{:.1}", competitor.seconds_to_max_speed)
+                }),
+            ),
+        };
+
+        t!("ui.mode_editor.row", name = name, value = value).to_string()
+    }
+}
+
+fn step_non_zero(value: u8, step: i32, max: u8) -> NonZeroU8 {
+    let value = (value as i32 + step).clamp(1, max as i32) as u8;
+
+    NonZeroU8::new(value).unwrap_or(NonZeroU8::MIN)
+}
+
+fn step_f32(value: f32, step: i32, increment: f32, min: f32, max: f32) -> f32 {
+    let steps = (value / increment).round() + step as f32;
+
+    (steps * increment).clamp(min, max)
+}
+
+/// Where new game modes are saved, so the next launch loads them with the
+/// rest of the modes.
+pub fn game_modes_dir() -> PathBuf {
+    FileAssetReader::get_base_path()
+        .join("assets")
+        .join("modes")
+}
+
+/// Writes a game mode to [`game_modes_dir`], replacing the file of a saved
+/// mode with the same name.
+///
+/// Modes without a name, or with the name of a mode that comes with the game,
+/// aren't saved.
+pub fn save_game_mode(game_mode: &GameMode) -> Result<PathBuf, String> {
+    if game_mode.name.trim().is_empty() {
+        return Err(t!("ui.mode_editor.name_missing").to_string());
+    }
+
+    let dir = game_modes_dir();
+    let slug: String = game_mode
+        .name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { '-' })
+        .collect::<String>()
+        .split('-')
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    let file_names: Vec<String> = fs::read_dir(&dir)
+        .map_err(|error| error.to_string())?
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| entry.file_name().into_string().ok())
+        .filter(|file_name| file_name.ends_with(".mode.yaml"))
+        .collect();
+    let same_name = file_names.iter().find(|file_name| {
+        fs::read_to_string(dir.join(file_name))
+            .ok()
+            .and_then(|contents| {
+                serde_yaml::from_str::<GameMode>(&contents).ok()
+            })
+            .is_some_and(|saved| saved.name == game_mode.name)
+    });
+
+    if same_name.is_some_and(|file_name| is_shipped_game_mode(file_name)) {
+        return Err(t!("ui.mode_editor.name_taken").to_string());
+    }
+
+    // Numbered after every other mode, so it's listed last.
+    let file_name = same_name.cloned().unwrap_or_else(|| {
+        let number = file_names
+            .iter()
+            .filter_map(|file_name| {
+                file_name.split(['-', '.']).next()?.parse().ok()
+            })
+            .max()
+            .unwrap_or(0usize)
+            + 1;
+
+        if slug.is_empty() {
+            format!("{number}.mode.yaml")
+        } else {
+            format!("{number}-{slug}.mode.yaml")
+        }
+    });
+    let path = dir.join(file_name);
+    let contents =
+        serde_yaml::to_string(game_mode).map_err(|error| error.to_string())?;
+
+    fs::write(&path, contents).map_err(|error| error.to_string())?;
+
+    Ok(path)
+}
+
+/// Checks if a game mode file comes with the game.
+fn is_shipped_game_mode(file_name: &str) -> bool {
+    SHIPPED_GAME_MODE_FILES.contains(&file_name)
+}
+
+/// Checks if the loaded game mode with this name came with the game.
+fn is_shipped_game_mode_name(game_modes: &GameModes, name: &str) -> bool {
+    game_modes
+        .path(name)
+        .and_then(|path| path.path().file_name()?.to_str())
+        .is_some_and(is_shipped_game_mode)
+}
+
+fn start_mode_draft(mut commands: Commands, game_modes: GameModes) {
+    let mut game_mode = game_modes.current().clone();
+    let is_shipped = game_modes
+        .current_path()
+        .and_then(|path| path.path().file_name()?.to_str())
+        .is_some_and(is_shipped_game_mode);
+
+    // Keep the original mode around unless a custom one is being changed.
+    if is_shipped {
+        game_mode.name =
+            t!("ui.mode_editor.custom_name", name = game_mode.name).to_string();
+    }
+
+    commands.insert_resource(ModeDraft {
+        game_mode,
+        side: Side::Bottom,
+    });
+}
+
+fn spawn_mode_editor_ui(mut commands: Commands, game_assets: Res<GameAssets>) {
+    let font = &game_assets.font_menu;
+
+    commands
+        .spawn((
+            ForStates(vec![MenuScreen::ModeEditor]),
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(8.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.85)),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text(t!("ui.mode_editor.title").to_string()),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 23.0,
+                    ..default()
+                },
+                TextColor(Srgba::BLUE.into()),
+            ));
+
+            parent
+                .spawn((
+                    ModeEditorUi,
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                ))
+                .with_children(|parent| {
+                    for row in EditorRow::iter() {
+                        spawn_menu_item(parent, font, "")
+                            .insert((
+                                row,
+                                TextFont {
+                                    font: font.clone(),
+                                    font_size: ROW_FONT_SIZE,
+                                    ..default()
+                                },
+                            ))
+                            .observe(activate_editor_row)
+                            .observe(adjust_editor_row);
+                    }
+
+                    spawn_menu_item(parent, font, t!("ui.mode_editor.preview"))
+                        .observe(preview_mode_draft);
+                    spawn_menu_item(parent, font, t!("ui.mode_editor.save"))
+                        .observe(save_mode_draft);
+                    spawn_menu_item(parent, font, t!("ui.mode_editor.back"))
+                        .observe(return_to_main_menu_screen);
+                });
+
+            parent.spawn((
+                ModeEditorStatus,
+                Text(t!("ui.mode_editor.hint").to_string()),
+                TextFont {
+                    font: font.clone(),
+                    font_size: ROW_FONT_SIZE,
+                    ..default()
+                },
+                TextColor(Srgba::BLUE.into()),
+            ));
+        });
+}
+
+fn update_editor_row_texts(
+    draft: Res<ModeDraft>,
+    name_capture: Option<Res<NameCapture>>,
+    mut editor_rows_query: Query<(&EditorRow, &mut Text)>,
+) {
+    for (row, mut text) in &mut editor_rows_query {
+        let label = row.text(&draft, name_capture.is_some());
+
+        if text.0 != label {
+            text.0 = label;
+        }
+    }
+}
+
+fn activate_editor_row(
+    trigger: Trigger<MenuItemActivated>,
+    mut commands: Commands,
+    mut draft: ResMut<ModeDraft>,
+    editor_rows_query: Query<&EditorRow>,
+) {
+    match editor_rows_query.get(trigger.entity()) {
+        Ok(EditorRow::Name) => {
+            commands.insert_resource(NameCapture);
+            commands.init_resource::<MenuInputLock>();
+        },
+        Ok(row) => row.adjust(&mut draft, 1),
+        Err(_) => {},
+    }
+}
+
+fn adjust_editor_row(
+    trigger: Trigger<MenuItemAdjusted>,
+    mut draft: ResMut<ModeDraft>,
+    editor_rows_query: Query<&EditorRow>,
+) {
+    if let Ok(row) = editor_rows_query.get(trigger.entity()) {
+        row.adjust(&mut draft, trigger.event().0);
+    }
+}
+
+fn capture_text_for_name(
+    mut commands: Commands,
+    name_capture: Res<NameCapture>,
+    mut draft: ResMut<ModeDraft>,
+    mut keyboard_input_events: EventReader<KeyboardInput>,
+) {
+    // Ignore the input that started the capture.
+    if name_capture.is_added() {
+        keyboard_input_events.clear();
+        return;
+    }
+
+    for event in keyboard_input_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+
+        let name = &mut draft.game_mode.name;
+
+        match &event.logical_key {
+            Key::Enter | Key::Escape => {
+                commands.remove_resource::<NameCapture>();
+                break;
+            },
+            Key::Backspace => {
+                name.pop();
+            },
+            Key::Space if name.chars().count() < MAX_NAME_LENGTH => {
+                name.push(' ');
+            },
+            Key::Character(text) => {
+                for c in text.chars().filter(|c| !c.is_control()) {
+                    if name.chars().count() < MAX_NAME_LENGTH {
+                        name.push(c);
+                    }
+                }
+            },
+            _ => {},
+        }
+    }
+}
+
+/// Keeps menus locked until the frame after the name is entered, so the key
+/// that entered it isn't also handled as a menu input.
+fn unlock_menu_inputs_after_name_capture(mut commands: Commands) {
+    commands.remove_resource::<MenuInputLock>();
+}
+
+fn stop_name_capture(mut commands: Commands) {
+    commands.remove_resource::<NameCapture>();
+    commands.remove_resource::<MenuInputLock>();
+}
+
+fn preview_mode_draft(
+    _trigger: Trigger<MenuItemActivated>,
+    mut commands: Commands,
+    draft: Res<ModeDraft>,
+    game_modes: GameModes,
+    mut next_game_state: ResMut<NextState<GameState>>,
+    rollback_session: Option<Res<RollbackSession>>,
+    mut status_query: Query<&mut Text, With<ModeEditorStatus>>,
+) {
+    // Previewing replaces the loaded mode with the same name, which can't be
+    // one that came with the game.
+    if is_shipped_game_mode_name(&game_modes, &draft.game_mode.name) {
+        for mut text in &mut status_query {
+            text.0 = t!("ui.mode_editor.name_taken").to_string();
+        }
+
+        return;
+    }
+
+    commands.queue(AddGameMode(draft.game_mode.clone()));
+
+    // Rollback rounds start once both peers are ready.
+    if rollback_session.is_none() {
+        next_game_state.set(GameState::Playing);
+    }
+
+    info!("Mode Editor: Previewing {}", draft.game_mode.name);
+}
+
+fn save_mode_draft(
+    _trigger: Trigger<MenuItemActivated>,
+    mut commands: Commands,
+    draft: Res<ModeDraft>,
+    mut status_query: Query<&mut Text, With<ModeEditorStatus>>,
+) {
+    let message = match save_game_mode(&draft.game_mode) {
+        Ok(path) => {
+            commands.queue(AddGameMode(draft.game_mode.clone()));
+            info!("Mode Editor: Saved to {path:?}");
+            t!("ui.mode_editor.saved", path = path.display())
+        },
+        Err(error) => {
+            warn!("Mode Editor: Failed to save: {error}");
+            t!("ui.mode_editor.save_failed", error = error)
+        },
+    };
+
+    for mut text in &mut status_query {
+        text.0 = message.to_string();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use crate::headless::build_headless_app;
+
+    use super::*;
+
+    fn open_mode_editor() -> App {
+        let mut app = build_headless_app(Duration::from_millis(16), |_| {});
+
+        app.world_mut()
+            .resource_mut::<NextState<MenuScreen>>()
+            .set(MenuScreen::ModeEditor);
+        app.update();
+
+        app
+    }
+
+    fn press_escape(app: &mut App) {
+        app.world_mut().send_event(KeyboardInput {
+            key_code: KeyCode::Escape,
+            logical_key: Key::Escape,
+            state: ButtonState::Pressed,
+            repeat: false,
+            window: Entity::PLACEHOLDER,
+        });
+        app.update();
+        app.update();
+    }
+
+    fn menu_screen(app: &App) -> MenuScreen {
+        *app.world().resource::<State<MenuScreen>>().get()
+    }
+
+    #[test]
+    fn exit_backs_out_of_the_mode_editor_instead_of_quitting() {
+        let mut app = open_mode_editor();
+
+        assert_eq!(menu_screen(&app), MenuScreen::ModeEditor);
+
+        press_escape(&mut app);
+
+        assert!(app.world().resource::<Events<AppExit>>().is_empty());
+        assert_eq!(menu_screen(&app), MenuScreen::Main);
+    }
+
+    #[test]
+    fn escape_only_stops_entering_the_name() {
+        let mut app = open_mode_editor();
+
+        app.insert_resource(NameCapture)
+            .init_resource::<MenuInputLock>();
+        app.update();
+        press_escape(&mut app);
+
+        assert!(!app.world().contains_resource::<NameCapture>());
+        assert!(!app.world().contains_resource::<MenuInputLock>());
+        assert!(app.world().resource::<Events<AppExit>>().is_empty());
+        assert_eq!(menu_screen(&app), MenuScreen::ModeEditor);
+    }
+}
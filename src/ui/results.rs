@@ -0,0 +1,224 @@
+use std::time::Duration;
+
+use bevy::prelude::*;
+use rust_i18n::t;
+use strum::IntoEnumIterator;
+
+use crate::{
+    assets::GameAssets,
+    components::{ForStates, Side},
+    settings::Settings,
+    states::MenuScreen,
+    stats::{MatchStats, TimelineEvent},
+    system_params::GameModes,
+};
+
+use super::{
+    MatchTime, MenuList, WinningTeam, return_to_main_menu_screen, side_name,
+    spawn_menu_item, start_new_game, winning_team_message,
+};
+
+pub(super) struct ResultsPlugin;
+
+impl Plugin for ResultsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(MenuScreen::Results), spawn_results_ui);
+    }
+}
+
+const CELL_FONT_SIZE: f32 = 14.0;
+const TEXT_COLOR: Color = Color::srgb(0.0, 0.0, 1.0);
+
+/// How many of the latest timeline entries are listed.
+const MAX_TIMELINE_ENTRIES: usize = 8;
+
+/// Marks the list of options on the results screen.
+#[derive(Component, Debug)]
+#[require(MenuList)]
+pub struct ResultsUi;
+
+fn spawn_results_ui(
+    mut commands: Commands,
+    game_assets: Res<GameAssets>,
+    game_modes: GameModes,
+    match_stats: Res<MatchStats>,
+    match_time: Res<MatchTime>,
+    settings: Res<Settings>,
+    winning_team: Option<Res<WinningTeam>>,
+) {
+    let font = &game_assets.font_menu;
+    let game_mode = game_modes.current();
+    let side_color = |side: Side| -> Color {
+        game_mode
+            .competitors
+            .get(&side)
+            .map_or(Srgba::BLUE.into(), |competitor| {
+                settings.competitor_color(side, competitor)
+            })
+    };
+    let cell_font = TextFont {
+        font: font.clone(),
+        font_size: CELL_FONT_SIZE,
+        ..default()
+    };
+    let title = winning_team
+        .map(|winning_team| winning_team_message(winning_team.0))
+        .unwrap_or_default();
+
+    commands
+        .spawn((
+            ForStates(vec![MenuScreen::Results]),
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(12.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.85)),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text(format!(
+                    "{}\n{}",
+                    t!("ui.results.title"),
+                    title.trim_end()
+                )),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 23.0,
+                    ..default()
+                },
+                TextColor(TEXT_COLOR),
+                TextLayout::new_with_justify(JustifyText::Center),
+            ));
+
+            // One row per crab, with a header.
+            parent
+                .spawn(Node {
+                    display: Display::Grid,
+                    grid_template_columns: RepeatedGridTrack::auto(7),
+                    column_gap: Val::Px(10.0),
+                    row_gap: Val::Px(2.0),
+                    ..default()
+                })
+                .with_children(|parent| {
+                    let headers = [
+                        t!("ui.results.columns.crab"),
+                        t!("ui.results.columns.deflections"),
+                        t!("ui.results.columns.conceded"),
+                        t!("ui.results.columns.edge_saves"),
+                        t!("ui.results.columns.longest_rally"),
+                        t!("ui.results.columns.time_survived"),
+                        t!("ui.results.columns.eliminated"),
+                    ];
+
+                    for header in headers {
+                        parent.spawn((
+                            Text(header.to_string()),
+                            cell_font.clone(),
+                            TextColor(TEXT_COLOR),
+                        ));
+                    }
+
+                    for side in Side::iter() {
+                        let Some(crab_stats) = match_stats.crabs.get(&side)
+                        else {
+                            continue;
+                        };
+                        let eliminated =
+                            match_stats.elimination_place(side).map_or_else(
+                                || t!("ui.results.survived").to_string(),
+                                |place| place.to_string(),
+                            );
+                        let cells = [
+                            side_name(side),
+                            crab_stats.deflections.to_string(),
+                            crab_stats.goals_conceded.to_string(),
+                            crab_stats.edge_saves.to_string(),
+                            crab_stats.longest_rally.to_string(),
+                            format_time(
+                                match_stats.time_survived(side, match_time.0),
+                            ),
+                            eliminated,
+                        ];
+
+                        for cell in cells {
+                            parent.spawn((
+                                Text(cell),
+                                cell_font.clone(),
+                                TextColor(side_color(side)),
+                            ));
+                        }
+                    }
+                });
+
+            // The latest goals and eliminations.
+            parent
+                .spawn(Node {
+                    flex_direction: FlexDirection::Column,
+                    align_items: AlignItems::Center,
+                    ..default()
+                })
+                .with_children(|parent| {
+                    let skipped = match_stats
+                        .timeline
+                        .len()
+                        .saturating_sub(MAX_TIMELINE_ENTRIES);
+
+                    for entry in match_stats.timeline.iter().skip(skipped) {
+                        let (side, message) = match entry.event {
+                            TimelineEvent::Scored(side, hit_points) => (
+                                side,
+                                t!(
+                                    "ui.results.timeline.scored",
+                                    side = side_name(side),
+                                    hit_points = hit_points
+                                ),
+                            ),
+                            TimelineEvent::Eliminated(side) => (
+                                side,
+                                t!(
+                                    "ui.results.timeline.eliminated",
+                                    side = side_name(side)
+                                ),
+                            ),
+                        };
+
+                        parent.spawn((
+                            Text(format!(
+                                "{} {message}",
+                                format_time(entry.time)
+                            )),
+                            cell_font.clone(),
+                            TextColor(side_color(side)),
+                        ));
+                    }
+                });
+
+            parent
+                .spawn((
+                    ResultsUi,
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                ))
+                .with_children(|parent| {
+                    spawn_menu_item(parent, font, t!("ui.results.rematch"))
+                        .observe(start_new_game);
+                    spawn_menu_item(parent, font, t!("ui.results.back"))
+                        .observe(return_to_main_menu_screen);
+                });
+        });
+}
+
+fn format_time(time: Duration) -> String {
+    let seconds = time.as_secs();
+
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
@@ -0,0 +1,436 @@
+use bevy::prelude::*;
+use rust_i18n::t;
+use strum::{EnumIter, IntoEnumIterator};
+
+use crate::{
+    assets::GameAssets,
+    components::ForStates,
+    settings::{
+        CameraMode, ColorPalette, GAME_SPEEDS, POINTER_SLOTS, QUALITY_PRESETS,
+        QualityPreset, RESOLUTIONS, Settings, UI_SCALES, WindowModeSetting,
+        system_locale,
+    },
+    states::MenuScreen,
+};
+
+use super::{
+    MenuItemActivated, MenuItemAdjusted, MenuList, return_to_main_menu_screen,
+    spawn_menu_item,
+};
+
+pub(super) struct SettingsUiPlugin;
+
+impl Plugin for SettingsUiPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_systems(OnEnter(MenuScreen::Settings), spawn_settings_ui)
+            .add_systems(
+                Update,
+                update_setting_row_texts.run_if(in_state(MenuScreen::Settings)),
+            );
+    }
+}
+
+const ROW_FONT_SIZE: f32 = 16.0;
+
+/// How much a volume changes with each press.
+const VOLUME_STEP: f32 = 0.1;
+
+/// Marks the list of options on the settings screen.
+#[derive(Component, Debug)]
+#[require(MenuList)]
+pub struct SettingsUi;
+
+/// A settings screen row that shows and changes one option.
+///
+/// Left and right step through its values, and accepting steps forward.
+#[derive(Clone, Component, Copy, Debug, EnumIter, Eq, PartialEq)]
+pub enum SettingRow {
+    WindowMode,
+    Resolution,
+    Vsync,
+    Quality,
+    AmbientOcclusion,
+    Reflections,
+    TemporalAntiAliasing,
+    LowEffects,
+    MasterVolume,
+    MusicVolume,
+    SfxVolume,
+    PointerSlot,
+    Language,
+    CameraSway,
+    CameraMode,
+    CameraShake,
+    UiScale,
+    Palette,
+    TeamSymbols,
+    HighContrastBall,
+    ReducedMotion,
+    GameSpeed,
+}
+
+impl SettingRow {
+    /// Changes the option by `step` values, wrapping around.
+    pub fn adjust(&self, settings: &mut Settings, step: i32) {
+        let display = &mut settings.display;
+        let graphics = &mut settings.graphics;
+        let audio = &mut settings.audio;
+        let general = &mut settings.general;
+        let controls = &mut settings.controls;
+        let accessibility = &mut settings.accessibility;
+
+        match self {
+            Self::WindowMode => {
+                display.window_mode = display.window_mode.cycle(step);
+            },
+            Self::Resolution => {
+                display.resolution =
+                    cycle(&RESOLUTIONS, &display.resolution, step);
+            },
+            Self::Vsync => display.vsync = !display.vsync,
+            Self::Quality => {
+                graphics.quality =
+                    cycle(&QUALITY_PRESETS, &graphics.quality, step);
+            },
+            Self::AmbientOcclusion => {
+                graphics.ambient_occlusion = !graphics.ambient_occlusion;
+            },
+            Self::Reflections => {
+                graphics.reflections = !graphics.reflections;
+            },
+            Self::TemporalAntiAliasing => {
+                graphics.temporal_anti_aliasing =
+                    !graphics.temporal_anti_aliasing;
+            },
+            Self::LowEffects => graphics.low_effects = !graphics.low_effects,
+            Self::MasterVolume => {
+                audio.master_volume = step_volume(audio.master_volume, step);
+            },
+            Self::MusicVolume => {
+                audio.music_volume = step_volume(audio.music_volume, step);
+            },
+            Self::SfxVolume => {
+                audio.sfx_volume = step_volume(audio.sfx_volume, step);
+            },
+            Self::PointerSlot => {
+                controls.pointer_slot =
+                    cycle(&POINTER_SLOTS, &controls.pointer_slot, step);
+            },
+            Self::Language => {
+                let mut locales: Vec<Option<String>> =
+                    rust_i18n::available_locales!()
+                        .into_iter()
+                        .map(|locale| Some(locale.to_string()))
+                        .collect();
+
+                locales.sort();
+                locales.insert(0, None);
+                general.language = cycle(&locales, &general.language, step);
+            },
+            Self::CameraSway => general.camera_sway = !general.camera_sway,
+            Self::CameraMode => {
+                general.camera_mode = general.camera_mode.cycle(step);
+            },
+            Self::CameraShake => general.camera_shake = !general.camera_shake,
+            Self::UiScale => {
+                general.ui_scale = cycle(&UI_SCALES, &general.ui_scale, step);
+            },
+            Self::Palette => {
+                accessibility.palette = accessibility.palette.cycle(step);
+            },
+            Self::TeamSymbols => {
+                accessibility.team_symbols = !accessibility.team_symbols;
+            },
+            Self::HighContrastBall => {
+                accessibility.high_contrast_ball =
+                    !accessibility.high_contrast_ball;
+            },
+            Self::ReducedMotion => {
+                accessibility.reduced_motion = !accessibility.reduced_motion;
+            },
+            Self::GameSpeed => {
+                accessibility.game_speed =
+                    cycle(&GAME_SPEEDS, &accessibility.game_speed, step);
+            },
+        }
+    }
+
+    /// The row's label and current value.
+    pub fn text(&self, settings: &Settings) -> String {
+        let on_off = |on: bool| {
+            if on {
+                t!("ui.settings.values.on")
+            } else {
+                t!("ui.settings.values.off")
+            }
+        };
+        let percent = |volume: f32| format!("{:.0}%", volume * 100.0);
+        let (name, value) = match self {
+            Self::WindowMode => (
+                t!("ui.settings.rows.window_mode"),
+                match settings.display.window_mode {
+                    WindowModeSetting::Windowed => {
+                        t!("ui.settings.values.windowed")
+                    },
+                    WindowModeSetting::Borderless => {
+                        t!("ui.settings.values.borderless")
+                    },
+                    WindowModeSetting::Fullscreen => {
+                        t!("ui.settings.values.fullscreen")
+                    },
+                }
+                .to_string(),
+            ),
+            Self::Resolution => (
+                t!("ui.settings.rows.resolution"),
+                format!(
+                    "{}x{}",
+                    settings.display.resolution.x,
+                    settings.display.resolution.y
+                ),
+            ),
+            Self::Vsync => (
+                t!("ui.settings.rows.vsync"),
+                on_off(settings.display.vsync).to_string(),
+            ),
+            Self::Quality => (
+                t!("ui.settings.rows.quality"),
+                match settings.graphics.quality {
+                    None => t!("ui.settings.values.auto"),
+                    Some(QualityPreset::Low) => t!("ui.settings.values.low"),
+                    Some(QualityPreset::Medium) => {
+                        t!("ui.settings.values.medium")
+                    },
+                    Some(QualityPreset::High) => t!("ui.settings.values.high"),
+                    Some(QualityPreset::Ultra) => {
+                        t!("ui.settings.values.ultra")
+                    },
+                }
+                .to_string(),
+            ),
+            Self::AmbientOcclusion => (
+                t!("ui.settings.rows.ambient_occlusion"),
+                on_off(settings.graphics.ambient_occlusion).to_string(),
+            ),
+            Self::Reflections => (
+                t!("ui.settings.rows.reflections"),
+                on_off(settings.graphics.reflections).to_string(),
+            ),
+            Self::TemporalAntiAliasing => (
+                t!("ui.settings.rows.temporal_anti_aliasing"),
+                on_off(settings.graphics.temporal_anti_aliasing).to_string(),
+            ),
+            Self::LowEffects => (
+                t!("ui.settings.rows.low_effects"),
+                on_off(settings.graphics.low_effects).to_string(),
+            ),
+            Self::MasterVolume => (
+                t!("ui.settings.rows.master_volume"),
+                percent(settings.audio.master_volume),
+            ),
+            Self::MusicVolume => (
+                t!("ui.settings.rows.music_volume"),
+                percent(settings.audio.music_volume),
+            ),
+            Self::SfxVolume => (
+                t!("ui.settings.rows.sfx_volume"),
+                percent(settings.audio.sfx_volume),
+            ),
+            Self::PointerSlot => (
+                t!("ui.settings.rows.pointer_slot"),
+                match settings.controls.pointer_slot {
+                    Some(slot) => t!(
+                        "ui.settings.values.player_slot",
+                        slot = slot as usize + 1
+                    ),
+                    None => t!("ui.settings.values.off"),
+                }
+                .to_string(),
+            ),
+            Self::Language => (
+                t!("ui.settings.rows.language"),
+                match &settings.general.language {
+                    Some(language) => language_name(language),
+                    None => t!(
+                        "ui.settings.values.default",
+                        language = language_name(system_locale())
+                    )
+                    .to_string(),
+                },
+            ),
+            Self::CameraSway => (
+                t!("ui.settings.rows.camera_sway"),
+                on_off(settings.general.camera_sway).to_string(),
+            ),
+            Self::CameraMode => (
+                t!("ui.settings.rows.camera_mode"),
+                match settings.general.camera_mode {
+                    CameraMode::Sway => t!("ui.settings.values.sway"),
+                    CameraMode::TopDown => t!("ui.settings.values.top_down"),
+                    CameraMode::PlayerAtBottom => {
+                        t!("ui.settings.values.player_at_bottom")
+                    },
+                    CameraMode::FollowBalls => {
+                        t!("ui.settings.values.follow_balls")
+                    },
+                }
+                .to_string(),
+            ),
+            Self::CameraShake => (
+                t!("ui.settings.rows.camera_shake"),
+                on_off(settings.general.camera_shake).to_string(),
+            ),
+            Self::UiScale => (
+                t!("ui.settings.rows.ui_scale"),
+                format!("{:.0}%", settings.general.ui_scale * 100.0),
+            ),
+            Self::Palette => (
+                t!("ui.settings.rows.palette"),
+                match settings.accessibility.palette {
+                    ColorPalette::Original => {
+                        t!("ui.settings.values.original")
+                    },
+                    ColorPalette::RedGreenSafe => {
+                        t!("ui.settings.values.red_green_safe")
+                    },
+                    ColorPalette::BlueYellowSafe => {
+                        t!("ui.settings.values.blue_yellow_safe")
+                    },
+                }
+                .to_string(),
+            ),
+            Self::TeamSymbols => (
+                t!("ui.settings.rows.team_symbols"),
+                on_off(settings.accessibility.team_symbols).to_string(),
+            ),
+            Self::HighContrastBall => (
+                t!("ui.settings.rows.high_contrast_ball"),
+                on_off(settings.accessibility.high_contrast_ball).to_string(),
+            ),
+            Self::ReducedMotion => (
+                t!("ui.settings.rows.reduced_motion"),
+                on_off(settings.accessibility.reduced_motion).to_string(),
+            ),
+            Self::GameSpeed => (
+                t!("ui.settings.rows.game_speed"),
+                percent(settings.accessibility.game_speed),
+            ),
+        };
+
+        t!("ui.settings.row", name = name, value = value).to_string()
+    }
+}
+
+/// Gets the value `step` places after `current` in `values`, wrapping around.
+///
+/// Values that aren't in the list start from the first one.
+fn cycle<T: Clone + PartialEq>(values: &[T], current: &T, step: i32) -> T {
+    let index = values.iter().position(|value| value == current);
+    let next = match index {
+        Some(index) => (index as i32 + step).rem_euclid(values.len() as i32),
+        None => 0,
+    };
+
+    values[next as usize].clone()
+}
+
+/// The name of a language, written in that language.
+fn language_name(locale: &str) -> String {
+    t!("ui.language.name", locale = locale).to_string()
+}
+
+fn step_volume(volume: f32, step: i32) -> f32 {
+    let steps = (volume / VOLUME_STEP).round() as i32 + step;
+
+    (steps as f32 * VOLUME_STEP).clamp(0.0, 1.0)
+}
+
+fn spawn_settings_ui(mut commands: Commands, game_assets: Res<GameAssets>) {
+    let font = &game_assets.font_menu;
+
+    commands
+        .spawn((
+            ForStates(vec![MenuScreen::Settings]),
+            Node {
+                position_type: PositionType::Absolute,
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                flex_direction: FlexDirection::Column,
+                align_items: AlignItems::Center,
+                justify_content: JustifyContent::Center,
+                row_gap: Val::Px(8.0),
+                ..default()
+            },
+            BackgroundColor(Color::srgba(1.0, 1.0, 1.0, 0.85)),
+        ))
+        .with_children(|parent| {
+            parent.spawn((
+                Text(t!("ui.settings.title").to_string()),
+                TextFont {
+                    font: font.clone(),
+                    font_size: 23.0,
+                    ..default()
+                },
+                TextColor(Srgba::BLUE.into()),
+            ));
+
+            parent
+                .spawn((
+                    SettingsUi,
+                    Node {
+                        flex_direction: FlexDirection::Column,
+                        align_items: AlignItems::Center,
+                        ..default()
+                    },
+                ))
+                .with_children(|parent| {
+                    for row in SettingRow::iter() {
+                        spawn_menu_item(parent, font, "")
+                            .insert((
+                                row,
+                                TextFont {
+                                    font: font.clone(),
+                                    font_size: ROW_FONT_SIZE,
+                                    ..default()
+                                },
+                            ))
+                            .observe(step_setting_forward)
+                            .observe(adjust_setting);
+                    }
+
+                    spawn_menu_item(parent, font, t!("ui.settings.back"))
+                        .observe(return_to_main_menu_screen);
+                });
+        });
+}
+
+fn update_setting_row_texts(
+    settings: Res<Settings>,
+    mut setting_rows_query: Query<(Ref<SettingRow>, &mut Text)>,
+) {
+    for (row, mut text) in &mut setting_rows_query {
+        if settings.is_changed() || row.is_added() {
+            text.0 = row.text(&settings);
+        }
+    }
+}
+
+fn step_setting_forward(
+    trigger: Trigger<MenuItemActivated>,
+    mut settings: ResMut<Settings>,
+    setting_rows_query: Query<&SettingRow>,
+) {
+    if let Ok(row) = setting_rows_query.get(trigger.entity()) {
+        row.adjust(&mut settings, 1);
+    }
+}
+
+fn adjust_setting(
+    trigger: Trigger<MenuItemAdjusted>,
+    mut settings: ResMut<Settings>,
+    setting_rows_query: Query<&SettingRow>,
+) {
+    if let Ok(row) = setting_rows_query.get(trigger.entity()) {
+        row.adjust(&mut settings, trigger.event().0);
+    }
+}
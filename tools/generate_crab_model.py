@@ -0,0 +1,459 @@
+#!/usr/bin/env python3
+"""Generates the animated crab model at assets/models/crab.glb.
+
+The crab is built from scaled spheres on a hierarchy of named nodes, which the
+animations move rigidly, so no skinning is needed. It fits a unit cube facing
+-Z, and the game scales it to the configured crab size.
+
+Every part is drawn with the competitor's color in game, so the whole crab
+fades together. Animations are written in the order the game loads them: Idle,
+ScuttleLeft, ScuttleRight, Hit, Defeat.
+
+Run from the repository root with no arguments:
+
+    python3 tools/generate_crab_model.py
+"""
+
+import json
+import math
+import struct
+from pathlib import Path
+
+OUTPUT_PATH = Path(__file__).parent.parent / "assets" / "models" / "crab.glb"
+
+SPHERE_SEGMENTS = 16
+SPHERE_RINGS = 10
+
+# Name, base color and roughness.
+MATERIALS = [
+    ("Shell", [1.0, 1.0, 1.0, 1.0], 0.3),
+    ("Eye", [0.95, 0.95, 0.92, 1.0], 0.2),
+    ("Pupil", [0.05, 0.05, 0.05, 1.0], 0.1),
+]
+
+LEG_COUNT = 3
+
+
+def quat(axis, angle):
+    """A rotation of `angle` radians about `axis`, as glTF's x, y, z, w."""
+    length = math.sqrt(sum(component * component for component in axis))
+    half_sin = math.sin(0.5 * angle) / length
+
+    return [axis[0] * half_sin, axis[1] * half_sin, axis[2] * half_sin,
+            math.cos(0.5 * angle)]
+
+
+def quat_mul(a, b):
+    ax, ay, az, aw = a
+    bx, by, bz, bw = b
+
+    return [
+        aw * bx + ax * bw + ay * bz - az * by,
+        aw * by - ax * bz + ay * bw + az * bx,
+        aw * bz + ax * by - ay * bx + az * bw,
+        aw * bw - ax * bx - ay * by - az * bz,
+    ]
+
+
+class Builder:
+    """Accumulates the binary buffer and JSON of a glTF file."""
+
+    def __init__(self):
+        self.binary = bytearray()
+        self.buffer_views = []
+        self.accessors = []
+
+    def add_accessor(self, values, component_type, accessor_type, target=None,
+                     with_bounds=False):
+        """Appends flat `values` and returns the index of their accessor."""
+        width = {"SCALAR": 1, "VEC3": 3, "VEC4": 4}[accessor_type]
+        pack = "<%d%s" % (len(values), "H" if component_type == 5123 else "f")
+        data = struct.pack(pack, *values)
+
+        while len(self.binary) % 4:
+            self.binary.append(0)
+
+        view = {"buffer": 0, "byteOffset": len(self.binary),
+                "byteLength": len(data)}
+
+        if target is not None:
+            view["target"] = target
+
+        self.binary.extend(data)
+        self.buffer_views.append(view)
+
+        accessor = {
+            "bufferView": len(self.buffer_views) - 1,
+            "componentType": component_type,
+            "count": len(values) // width,
+            "type": accessor_type,
+        }
+
+        if with_bounds:
+            columns = [values[i::width] for i in range(width)]
+            accessor["min"] = [min(column) for column in columns]
+            accessor["max"] = [max(column) for column in columns]
+
+        self.accessors.append(accessor)
+
+        return len(self.accessors) - 1
+
+
+def unit_sphere(builder):
+    """Adds a UV sphere of radius one, returning its attributes and indices."""
+    positions = []
+    normals = []
+    indices = []
+
+    for ring in range(SPHERE_RINGS + 1):
+        polar = math.pi * ring / SPHERE_RINGS
+
+        for segment in range(SPHERE_SEGMENTS + 1):
+            azimuth = 2.0 * math.pi * segment / SPHERE_SEGMENTS
+            normal = [
+                math.sin(polar) * math.cos(azimuth),
+                math.cos(polar),
+                math.sin(polar) * math.sin(azimuth),
+            ]
+            positions.extend(normal)
+            normals.extend(normal)
+
+    for ring in range(SPHERE_RINGS):
+        for segment in range(SPHERE_SEGMENTS):
+            a = ring * (SPHERE_SEGMENTS + 1) + segment
+            b = a + SPHERE_SEGMENTS + 1
+
+            # Counter-clockwise when seen from outside.
+            indices.extend([a, a + 1, b, a + 1, b + 1, b])
+
+    attributes = {
+        "POSITION": builder.add_accessor(positions, 5126, "VEC3", 34962,
+                                         with_bounds=True),
+        "NORMAL": builder.add_accessor(normals, 5126, "VEC3", 34962),
+    }
+    index_accessor = builder.add_accessor(indices, 5123, "SCALAR", 34963)
+
+    return attributes, index_accessor
+
+
+class Model:
+    """The crab's node hierarchy, where each part pivots about its node."""
+
+    def __init__(self):
+        self.nodes = []
+
+    def add_node(self, name, translation=None, rotation=None, scale=None,
+                 mesh=None, parent=None):
+        node = {"name": name}
+
+        if translation is not None:
+            node["translation"] = translation
+
+        if rotation is not None:
+            node["rotation"] = rotation
+
+        if scale is not None:
+            node["scale"] = scale
+
+        if mesh is not None:
+            node["mesh"] = mesh
+
+        self.nodes.append(node)
+        index = len(self.nodes) - 1
+
+        if parent is not None:
+            self.nodes[parent].setdefault("children", []).append(index)
+
+        return index
+
+    def add_part(self, name, parent, pivot, mesh, offset, radii):
+        """Adds a pivot node with a scaled sphere hanging off it."""
+        pivot_node = self.add_node(name, translation=pivot, parent=parent)
+        self.add_node(name + "Mesh", translation=offset, scale=radii,
+                      mesh=mesh, parent=pivot_node)
+
+        return pivot_node
+
+    def index(self, name):
+        return next(i for i, node in enumerate(self.nodes)
+                    if node["name"] == name)
+
+
+def build_model():
+    shell, eye, pupil = 0, 1, 2
+    model = Model()
+    root = model.add_node("Crab")
+
+    model.add_node("BodyMesh", translation=[0.0, 0.0, 0.0],
+                   scale=[0.36, 0.17, 0.3], mesh=shell, parent=root)
+
+    for side, sign in (("Left", -1.0), ("Right", 1.0)):
+        # Eyes on stalks, looking forward.
+        stalk = model.add_part("EyeStalk" + side, root,
+                               [0.1 * sign, 0.12, -0.18], shell,
+                               [0.0, 0.08, 0.0], [0.025, 0.09, 0.025])
+        eye_node = model.add_part("Eye" + side, stalk, [0.0, 0.17, 0.0],
+                                  eye, [0.0, 0.0, 0.0], [0.055, 0.055, 0.055])
+        model.add_node("Pupil" + side, translation=[0.0, 0.0, -0.045],
+                       scale=[0.025, 0.025, 0.02], mesh=pupil,
+                       parent=eye_node)
+
+        # Claws, reaching forward from the front corners.
+        arm = model.add_part("Claw" + side, root, [0.26 * sign, 0.02, -0.16],
+                             shell, [0.06 * sign, 0.0, -0.08],
+                             [0.06, 0.05, 0.12])
+        model.add_node("Pincer" + side + "Mesh",
+                       translation=[0.1 * sign, 0.03, -0.22],
+                       scale=[0.11, 0.08, 0.13], mesh=shell, parent=arm)
+
+        # Legs, splayed out and down to the sand.
+        for leg in range(LEG_COUNT):
+            model.add_part("Leg%s%d" % (side, leg), root,
+                           [0.26 * sign, -0.04, -0.02 + 0.12 * leg], shell,
+                           [0.12 * sign, -0.18, 0.0], [0.2, 0.035, 0.035])
+
+    # Tilt the legs so they reach out and down to the sand.
+    for side, sign in (("Left", -1.0), ("Right", 1.0)):
+        for leg in range(LEG_COUNT):
+            leg_mesh = model.nodes[model.index("Leg%s%dMesh" % (side, leg))]
+            leg_mesh["rotation"] = quat([0.0, 0.0, 1.0], 1.1 * sign)
+
+    return model
+
+
+def build_animations(builder, model):
+    """Builds each animation from keyframes of node rotations and translations.
+
+    Rotations are relative to each node's rest pose, which for the animated
+    pivot nodes is always no rotation.
+    """
+
+    def channels_for(keyframes):
+        """Turns {(node, path): [(time, value)]} into samplers and channels."""
+        samplers = []
+        channels = []
+
+        for (name, path), frames in keyframes.items():
+            times = [time for time, _ in frames]
+            values = [component for _, value in frames for component in value]
+            samplers.append({
+                "input": builder.add_accessor(times, 5126, "SCALAR",
+                                              with_bounds=True),
+                "output": builder.add_accessor(
+                    values, 5126, "VEC4" if path == "rotation" else "VEC3"),
+                "interpolation": "LINEAR",
+            })
+            channels.append({
+                "sampler": len(samplers) - 1,
+                "target": {"node": model.index(name), "path": path},
+            })
+
+        return {"samplers": samplers, "channels": channels}
+
+    def rest(name):
+        return model.nodes[model.index(name)].get("translation",
+                                                  [0.0, 0.0, 0.0])
+
+    def offset(name, delta):
+        return [a + b for a, b in zip(rest(name), delta)]
+
+    x_axis = [1.0, 0.0, 0.0]
+    y_axis = [0.0, 1.0, 0.0]
+    z_axis = [0.0, 0.0, 1.0]
+    animations = []
+
+    # Idle: a slow breath, with the claws flexing.
+    idle = {
+        ("Crab", "translation"): [
+            (0.0, [0.0, 0.0, 0.0]),
+            (1.0, [0.0, 0.02, 0.0]),
+            (2.0, [0.0, 0.0, 0.0]),
+        ],
+    }
+
+    for side, sign in (("Left", -1.0), ("Right", 1.0)):
+        idle[("Claw" + side, "rotation")] = [
+            (0.0, quat(x_axis, 0.0)),
+            (1.0, quat(x_axis, 0.2)),
+            (2.0, quat(x_axis, 0.0)),
+        ]
+        idle[("EyeStalk" + side, "rotation")] = [
+            (0.0, quat(z_axis, 0.0)),
+            (1.0, quat(z_axis, -0.1 * sign)),
+            (2.0, quat(z_axis, 0.0)),
+        ]
+
+    animations.append(("Idle", channels_for(idle)))
+
+    # Scuttling: legs lift in alternating waves and the body leans the way
+    # it's going.
+    for direction, lean in (("Left", 1.0), ("Right", -1.0)):
+        period = 0.4
+        scuttle = {
+            ("Crab", "rotation"): [
+                (0.0, quat(z_axis, 0.08 * lean)),
+                (0.5 * period, quat(z_axis, 0.12 * lean)),
+                (period, quat(z_axis, 0.08 * lean)),
+            ],
+            ("Crab", "translation"): [
+                (0.0, [0.0, 0.0, 0.0]),
+                (0.25 * period, [0.0, 0.025, 0.0]),
+                (0.5 * period, [0.0, 0.0, 0.0]),
+                (0.75 * period, [0.0, 0.025, 0.0]),
+                (period, [0.0, 0.0, 0.0]),
+            ],
+        }
+
+        for side, sign in (("Left", -1.0), ("Right", 1.0)):
+            for leg in range(LEG_COUNT):
+                # Neighboring legs, and the same leg on either side, step out
+                # of phase. Leading with the legs on the side it's heading.
+                phase = 0.5 * ((leg + (side == direction)) % 2)
+                lift = quat(z_axis, -0.45 * sign)
+                swing = quat_mul(quat(y_axis, 0.25 * sign * lean), lift)
+                frames = []
+
+                for step in range(5):
+                    t = (step / 4.0 + phase) % 1.0
+                    # Up and across for the first half of each stride.
+                    up = max(0.0, math.sin(2.0 * math.pi * t))
+                    frames.append((period * step / 4.0,
+                                   [a * up + b * (1.0 - up)
+                                    for a, b in zip(swing, quat(z_axis, 0.0))]))
+
+                scuttle[("Leg%s%d" % (side, leg), "rotation")] = [
+                    (time, normalize(value)) for time, value in frames
+                ]
+
+            scuttle[("Claw" + side, "rotation")] = [
+                (0.0, quat(x_axis, 0.3)),
+                (period, quat(x_axis, 0.3)),
+            ]
+
+        animations.append(("Scuttle" + direction, channels_for(scuttle)))
+
+    # Hit: knocked back with claws thrown up, then recovering.
+    hit = {
+        ("Crab", "translation"): [
+            (0.0, [0.0, 0.0, 0.0]),
+            (0.08, [0.0, 0.06, 0.12]),
+            (0.35, [0.0, 0.0, 0.0]),
+        ],
+        ("Crab", "rotation"): [
+            (0.0, quat(x_axis, 0.0)),
+            (0.08, quat(x_axis, 0.35)),
+            (0.35, quat(x_axis, 0.0)),
+        ],
+    }
+
+    for side, sign in (("Left", -1.0), ("Right", 1.0)):
+        hit[("Claw" + side, "rotation")] = [
+            (0.0, quat(x_axis, 0.0)),
+            (0.08, quat_mul(quat(z_axis, -0.6 * sign), quat(x_axis, 0.9))),
+            (0.35, quat(x_axis, 0.0)),
+        ]
+
+    animations.append(("Hit", channels_for(hit)))
+
+    # Defeat: rolls onto its back and sinks into the sand, legs curled.
+    defeat = {
+        ("Crab", "translation"): [
+            (0.0, [0.0, 0.0, 0.0]),
+            (0.3, [0.0, 0.25, 0.0]),
+            (0.7, [0.0, 0.0, 0.0]),
+            (1.2, [0.0, -0.3, 0.0]),
+        ],
+        ("Crab", "rotation"): [
+            (0.0, quat(z_axis, 0.0)),
+            (0.35, quat(z_axis, 0.5 * math.pi)),
+            (0.7, quat(z_axis, math.pi)),
+            (1.2, quat(z_axis, math.pi)),
+        ],
+    }
+
+    for side, sign in (("Left", -1.0), ("Right", 1.0)):
+        defeat[("Claw" + side, "rotation")] = [
+            (0.0, quat(x_axis, 0.0)),
+            (0.7, quat(x_axis, -0.8)),
+            (1.2, quat(x_axis, -0.6)),
+        ]
+
+        for leg in range(LEG_COUNT):
+            name = "Leg%s%d" % (side, leg)
+            defeat[(name, "rotation")] = [
+                (0.0, quat(z_axis, 0.0)),
+                (0.7, quat(z_axis, -0.7 * sign)),
+                (1.2, quat(z_axis, -0.5 * sign)),
+            ]
+            defeat[(name, "translation")] = [
+                (0.0, rest(name)),
+                (1.2, offset(name, [0.0, 0.02, 0.0])),
+            ]
+
+    animations.append(("Defeat", channels_for(defeat)))
+
+    return [{"name": name, **animation} for name, animation in animations]
+
+
+def normalize(rotation):
+    length = math.sqrt(sum(component * component for component in rotation))
+
+    return [component / length for component in rotation]
+
+
+def main():
+    builder = Builder()
+    attributes, indices = unit_sphere(builder)
+    model = build_model()
+    animations = build_animations(builder, model)
+
+    while len(builder.binary) % 4:
+        builder.binary.append(0)
+
+    gltf = {
+        "asset": {"version": "2.0",
+                  "generator": "bevy-crab-pong generate_crab_model.py"},
+        "scene": 0,
+        "scenes": [{"name": "Crab", "nodes": [0]}],
+        "nodes": model.nodes,
+        "meshes": [
+            {"name": name, "primitives": [{
+                "attributes": attributes,
+                "indices": indices,
+                "material": index,
+            }]}
+            for index, (name, _, _) in enumerate(MATERIALS)
+        ],
+        "materials": [
+            {"name": name, "pbrMetallicRoughness": {
+                "baseColorFactor": color,
+                "metallicFactor": 0.0,
+                "roughnessFactor": roughness,
+            }}
+            for name, color, roughness in MATERIALS
+        ],
+        "animations": animations,
+        "accessors": builder.accessors,
+        "bufferViews": builder.buffer_views,
+        "buffers": [{"byteLength": len(builder.binary)}],
+    }
+
+    json_chunk = json.dumps(gltf, separators=(",", ":")).encode()
+    json_chunk += b" " * (-len(json_chunk) % 4)
+    binary_chunk = bytes(builder.binary)
+    length = 12 + 8 + len(json_chunk) + 8 + len(binary_chunk)
+
+    OUTPUT_PATH.parent.mkdir(parents=True, exist_ok=True)
+
+    with open(OUTPUT_PATH, "wb") as file:
+        file.write(struct.pack("<4sII", b"glTF", 2, length))
+        file.write(struct.pack("<I4s", len(json_chunk), b"JSON"))
+        file.write(json_chunk)
+        file.write(struct.pack("<I4s", len(binary_chunk), b"BIN\0"))
+        file.write(binary_chunk)
+
+    print("Wrote %s (%d bytes)" % (OUTPUT_PATH, length))
+
+
+if __name__ == "__main__":
+    main()
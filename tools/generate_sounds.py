@@ -0,0 +1,300 @@
+#!/usr/bin/env python3
+"""Generates the game's sound effects and music loops as WAV files.
+
+Sound effects go in assets/sounds/ and music in assets/music/. Everything is
+synthesized from simple waveforms with a fixed random seed, so running this
+again gives the same files.
+
+Music loops are a whole number of bars with every note ending before the
+loop does, so they repeat without a seam.
+
+Run from the repository root with no arguments:
+
+    python3 tools/generate_sounds.py
+"""
+
+import math
+import random
+import struct
+import wave
+from pathlib import Path
+
+ASSETS_PATH = Path(__file__).parent.parent / "assets"
+
+SAMPLE_RATE = 22050
+
+# Peak levels, keeping the music under the sound effects.
+SOUND_LEVEL = 0.9
+MUSIC_LEVEL = 0.45
+
+NOTE_NAMES = ["C", "C#", "D", "D#", "E", "F", "F#", "G", "G#", "A", "A#", "B"]
+
+
+def frequency(note):
+    """The frequency of a note like "A4" or "C#3"."""
+    name, octave = note[:-1], int(note[-1])
+    semitones = NOTE_NAMES.index(name) - 9 + 12 * (octave - 4)
+
+    return 440.0 * 2.0 ** (semitones / 12.0)
+
+
+def sine(phase):
+    return math.sin(2.0 * math.pi * phase)
+
+
+def triangle(phase):
+    return 4.0 * abs((phase % 1.0) - 0.5) - 1.0
+
+
+def soft_square(phase):
+    """A square wave with its harshest harmonics left out."""
+    return sum(sine(phase * harmonic) / harmonic
+               for harmonic in (1, 3, 5, 7)) * 0.9
+
+
+def envelope(t, duration, attack, release):
+    """Fades in over `attack` and out over `release` seconds."""
+    if t < 0.0 or t >= duration:
+        return 0.0
+
+    return min(1.0, t / attack if attack > 0.0 else 1.0,
+               (duration - t) / release if release > 0.0 else 1.0)
+
+
+class Track:
+    """A buffer of samples that notes and noises are mixed into."""
+
+    def __init__(self, seconds):
+        self.samples = [0.0] * int(seconds * SAMPLE_RATE)
+
+    def add_tone(self, start, duration, start_frequency, volume,
+                 waveform=sine, end_frequency=None, attack=0.005,
+                 release=None, decay=0.0):
+        """Mixes in a tone, optionally sliding in pitch and decaying."""
+        end_frequency = end_frequency or start_frequency
+        release = duration * 0.3 if release is None else release
+        phase = 0.0
+        first = int(start * SAMPLE_RATE)
+
+        for i in range(int(duration * SAMPLE_RATE)):
+            index = first + i
+
+            if index >= len(self.samples):
+                break
+
+            t = i / SAMPLE_RATE
+            progress = t / duration
+            phase += (start_frequency
+                      + (end_frequency - start_frequency) * progress) \
+                / SAMPLE_RATE
+            level = envelope(t, duration, attack, release) \
+                * math.exp(-decay * t)
+            self.samples[index] += waveform(phase) * volume * level
+
+    def add_noise(self, rng, start, duration, volume, smoothing=0.0,
+                  attack=0.002, release=None, decay=0.0):
+        """Mixes in noise, low-passed more the higher `smoothing` is."""
+        release = duration * 0.5 if release is None else release
+        value = 0.0
+        first = int(start * SAMPLE_RATE)
+
+        for i in range(int(duration * SAMPLE_RATE)):
+            index = first + i
+
+            if index >= len(self.samples):
+                break
+
+            t = i / SAMPLE_RATE
+            value = value * smoothing \
+                + rng.uniform(-1.0, 1.0) * (1.0 - smoothing)
+            level = envelope(t, duration, attack, release) \
+                * math.exp(-decay * t)
+            self.samples[index] += value * volume * level
+
+    def write(self, path, level):
+        """Writes the track normalized so its loudest sample is at `level`."""
+        peak = max(abs(sample) for sample in self.samples) or 1.0
+        frames = b"".join(
+            struct.pack("<h", int(32767 * level * sample / peak))
+            for sample in self.samples
+        )
+        path.parent.mkdir(parents=True, exist_ok=True)
+
+        with wave.open(str(path), "wb") as file:
+            file.setnchannels(1)
+            file.setsampwidth(2)
+            file.setframerate(SAMPLE_RATE)
+            file.writeframes(frames)
+
+        print("Wrote %s (%.2fs)" % (path, len(self.samples) / SAMPLE_RATE))
+
+
+def sound_effects(rng):
+    """Each sound effect, by file name."""
+    sounds = {}
+
+    # A crab's claw knocking a ball back.
+    track = Track(0.2)
+    track.add_tone(0.0, 0.18, 520.0, 0.8, triangle, 380.0, decay=18.0)
+    track.add_noise(rng, 0.0, 0.03, 0.3, smoothing=0.5)
+    sounds["deflect"] = track
+
+    # A ball knocking against a pole or barrier.
+    track = Track(0.15)
+    track.add_tone(0.0, 0.14, 300.0, 0.8, sine, 240.0, decay=25.0)
+    track.add_tone(0.0, 0.06, 900.0, 0.2, sine, decay=40.0)
+    sounds["bounce"] = track
+
+    # Two balls clacking together.
+    track = Track(0.1)
+    track.add_tone(0.0, 0.08, 1600.0, 0.5, sine, 1500.0, decay=50.0)
+    track.add_noise(rng, 0.0, 0.015, 0.4)
+    sounds["clack"] = track
+
+    # A ball splashing into the water behind a goal.
+    track = Track(0.7)
+    track.add_noise(rng, 0.0, 0.65, 0.9, smoothing=0.8, attack=0.01,
+                    decay=5.0)
+    track.add_tone(0.0, 0.3, 420.0, 0.4, sine, 140.0, decay=8.0)
+    sounds["goal"] = track
+
+    # A goal being closed off by its pole.
+    track = Track(0.9)
+
+    for i, note in enumerate(["G4", "D#4", "C4"]):
+        track.add_tone(0.22 * i, 0.3, frequency(note), 0.5, soft_square,
+                       release=0.1)
+
+    track.add_noise(rng, 0.0, 0.2, 0.2, smoothing=0.9)
+    sounds["eliminated"] = track
+
+    # A rising fanfare for the winners.
+    track = Track(1.6)
+
+    for i, note in enumerate(["C5", "E5", "G5"]):
+        track.add_tone(0.12 * i, 0.16, frequency(note), 0.45, soft_square,
+                       release=0.04)
+
+    for note in ["C5", "E5", "G5", "C6"]:
+        track.add_tone(0.4, 1.1, frequency(note), 0.22, triangle,
+                       release=0.6)
+
+    sounds["victory"] = track
+
+    # A falling phrase for the losers.
+    track = Track(1.5)
+
+    for i, note in enumerate(["G4", "F#4", "F4"]):
+        track.add_tone(0.25 * i, 0.25, frequency(note), 0.45, triangle,
+                       release=0.05)
+
+    track.add_tone(0.75, 0.7, frequency("E4"), 0.45, triangle,
+                   frequency("D#4"), release=0.5)
+    sounds["defeat"] = track
+
+    # Moving between menu items.
+    track = Track(0.05)
+    track.add_tone(0.0, 0.04, 1200.0, 0.4, triangle, decay=60.0)
+    sounds["menu_move"] = track
+
+    # Choosing a menu item.
+    track = Track(0.15)
+    track.add_tone(0.0, 0.06, frequency("E5"), 0.4, triangle, release=0.01)
+    track.add_tone(0.06, 0.08, frequency("A5"), 0.4, triangle, decay=20.0)
+    sounds["menu_select"] = track
+
+    # Pausing, played higher when resuming.
+    track = Track(0.3)
+    track.add_tone(0.0, 0.12, frequency("A5"), 0.4, sine, release=0.02)
+    track.add_tone(0.12, 0.16, frequency("E5"), 0.4, sine, decay=12.0)
+    sounds["pause"] = track
+
+    return sounds
+
+
+def music_loop(rng, bpm, chords, melody, style):
+    """A loop of one bar per chord, with a melody of (beat, beats, note)."""
+    beat = 60.0 / bpm
+    track = Track(len(chords) * 4 * beat)
+
+    for bar, chord in enumerate(chords):
+        start = bar * 4 * beat
+        root = frequency(chord[0])
+
+        # Bass on the beat, arpeggio on the off-beats.
+        for step in range(4):
+            bass = root / 2.0 if step % 2 == 0 else root
+            track.add_tone(start + step * beat, 0.9 * beat, bass, 0.3,
+                           triangle, release=0.1)
+
+        for step in range(8):
+            note = chord[step % len(chord)]
+            track.add_tone(start + (step + 0.5) * 0.5 * beat, 0.4 * beat,
+                           frequency(note) * 2.0, 0.08, sine, decay=6.0)
+
+        if style == "drums":
+            for step in range(8):
+                track.add_noise(rng, start + step * 0.5 * beat, 0.05, 0.12,
+                                decay=60.0)
+
+            for step in (0, 2):
+                track.add_tone(start + step * beat, 0.15, 120.0, 0.5, sine,
+                               45.0, decay=20.0)
+
+            for step in (1, 3):
+                track.add_noise(rng, start + step * beat, 0.12, 0.25,
+                                smoothing=0.3, decay=25.0)
+
+    for start_beat, beats, note in melody:
+        track.add_tone(start_beat * beat, beats * beat * 0.95, frequency(note),
+                       0.18, soft_square if style == "drums" else triangle,
+                       attack=0.01, release=0.08)
+
+    return track
+
+
+def music(rng):
+    """Each music loop, by file name."""
+    return {
+        # Relaxed, for the start menu and attract matches.
+        "menu": music_loop(
+            rng, 100,
+            [["C4", "E4", "G4"], ["A3", "C4", "E4"],
+             ["F3", "A3", "C4"], ["G3", "B3", "D4"]],
+            [(0, 1.5, "E5"), (1.5, 0.5, "D5"), (2, 2, "C5"),
+             (4, 1, "A4"), (5, 1, "C5"), (6, 2, "E5"),
+             (8, 1.5, "F5"), (9.5, 0.5, "E5"), (10, 2, "C5"),
+             (12, 1, "B4"), (13, 1, "D5"), (14, 2, "G4")],
+            "arpeggio",
+        ),
+        # Upbeat, for matches.
+        "match": music_loop(
+            rng, 132,
+            [["A3", "C4", "E4"], ["F3", "A3", "C4"],
+             ["C4", "E4", "G4"], ["G3", "B3", "D4"]] * 2,
+            [(0, 0.5, "A4"), (0.5, 0.5, "C5"), (1, 1, "E5"), (2, 1, "D5"),
+             (3, 1, "C5"), (4, 1.5, "F5"), (5.5, 0.5, "E5"), (6, 2, "C5"),
+             (8, 0.5, "G4"), (8.5, 0.5, "C5"), (9, 1, "E5"), (10, 1, "G5"),
+             (11, 1, "E5"), (12, 1.5, "D5"), (13.5, 0.5, "B4"),
+             (14, 2, "G4"),
+             (16, 0.5, "E5"), (16.5, 0.5, "E5"), (17, 1, "A5"),
+             (18, 1, "G5"), (19, 1, "E5"), (20, 1, "F5"), (21, 1, "A5"),
+             (22, 2, "C6"), (24, 1, "G5"), (25, 1, "E5"), (26, 2, "C5"),
+             (28, 1, "B4"), (29, 1, "D5"), (30, 2, "E5")],
+            "drums",
+        ),
+    }
+
+
+def main():
+    rng = random.Random(7)
+
+    for name, track in sound_effects(rng).items():
+        track.write(ASSETS_PATH / "sounds" / (name + ".wav"), SOUND_LEVEL)
+
+    for name, track in music(rng).items():
+        track.write(ASSETS_PATH / "music" / (name + ".wav"), MUSIC_LEVEL)
+
+
+if __name__ == "__main__":
+    main()